@@ -0,0 +1,476 @@
+//! `aiandi adr` command implementation.
+//!
+//! Architecture Decision Records in `docs/adr/`: create them from the
+//! template in the README, supersede one with another, regenerate the
+//! README's ADR table, and lint for missing status and broken links.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{Local, NaiveDate};
+use colored::Colorize;
+
+use crate::markdown;
+
+/// ADR directory, relative to the project root
+pub const ADR_DIR: &str = "docs/adr";
+
+/// Statuses the ADR format allows
+pub const STATUSES: &[&str] = &["Proposed", "Accepted", "Deprecated", "Superseded"];
+
+/// Heading of the README section `index` regenerates
+const INDEX_HEADING: &str = "## ADR List";
+
+/// CLI arguments for the adr command
+#[derive(Debug, Clone, clap::Args)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: AdrCommand,
+
+    /// Project root holding docs/adr/
+    #[arg(long, global = true, default_value = ".")]
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum AdrCommand {
+    /// Create an ADR from the template
+    New {
+        /// ADR title
+        title: String,
+    },
+    /// Mark <old> as superseded by <new> and <new> as accepted, cross-linking both
+    Supersede {
+        /// ADR being replaced (file name, with or without date and `.md`)
+        old: String,
+        /// ADR replacing it
+        new: String,
+    },
+    /// Regenerate the ADR table in docs/adr/README.md
+    Index,
+    /// Flag ADRs with a missing status or broken links (exits nonzero)
+    Lint,
+}
+
+/// An ADR file
+#[derive(Debug, Clone)]
+pub struct Adr {
+    /// File name, e.g. `2026-01-07-monorepo-architecture-exploration.md`
+    pub file: String,
+    pub path: PathBuf,
+    pub date: Option<String>,
+    pub title: String,
+    pub status: Option<String>,
+}
+
+impl Adr {
+    fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let file = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let title = content
+            .lines()
+            .find_map(|l| l.strip_prefix("# "))
+            .map(|t| t.trim_start_matches("ADR:").trim().to_string())
+            .unwrap_or_else(|| file.trim_end_matches(".md").to_string());
+
+        Ok(Self {
+            date: markdown::bold_field(&content, "date").or_else(|| date_prefix(&file)),
+            status: markdown::bold_field(&content, "status"),
+            path: path.to_path_buf(),
+            file,
+            title,
+        })
+    }
+
+    /// The first word of the status (`Superseded` for "Superseded by ..."),
+    /// or `None` if missing
+    pub fn status_word(&self) -> Option<&str> {
+        self.status
+            .as_deref()
+            .and_then(|s| s.split(|c: char| !c.is_alphabetic()).next())
+            .filter(|w| !w.is_empty())
+    }
+}
+
+/// `YYYY-MM-DD` from the start of a file name
+fn date_prefix(file: &str) -> Option<String> {
+    let prefix = file.get(..10)?;
+    NaiveDate::parse_from_str(prefix, "%Y-%m-%d")
+        .ok()
+        .map(|_| prefix.to_string())
+}
+
+fn adr_dir(root: &Path) -> PathBuf {
+    root.join(ADR_DIR)
+}
+
+/// Every ADR (date-prefixed `.md` files), newest first
+pub fn adrs(root: &Path) -> Result<Vec<Adr>> {
+    let dir = adr_dir(root);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut adrs = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if path.is_file() && name.ends_with(".md") && date_prefix(&name).is_some() {
+            adrs.push(Adr::load(&path)?);
+        }
+    }
+    adrs.sort_by(|a, b| b.file.cmp(&a.file));
+    Ok(adrs)
+}
+
+/// Find an ADR by file name; the date prefix and `.md` may be left off
+pub fn find(root: &Path, name: &str) -> Result<Adr> {
+    let name = name.trim_end_matches(".md");
+    let matches: Vec<Adr> = adrs(root)?
+        .into_iter()
+        .filter(|adr| {
+            let stem = adr.file.trim_end_matches(".md");
+            stem == name || stem.get(11..) == Some(name)
+        })
+        .collect();
+    match matches.len() {
+        0 => bail!("No ADR '{}' in {}", name, ADR_DIR),
+        1 => Ok(matches.into_iter().next().expect("one match")),
+        _ => bail!(
+            "'{}' matches more than one ADR; use the dated file name",
+            name
+        ),
+    }
+}
+
+// ============================================================================
+// new
+// ============================================================================
+
+/// A new ADR in the README's format
+pub fn render_adr(title: &str, date: NaiveDate) -> String {
+    format!(
+        "# ADR: {title}\n\n\
+         **Date:** {date}  \n\
+         **Status:** Proposed  \n\
+         **Context:** [Brief description of situation]  \n\
+         **Decision:** [What was decided]\n\n\
+         ---\n\n\
+         ## Context\n\n[Full description of the problem/situation]\n\n\
+         ## Decision\n\n[The decision and its rationale]\n\n\
+         ## Consequences\n\n[Positive, negative, and neutral outcomes]\n\n\
+         ## References\n\n[Related sessions, PRs, issues]\n",
+        date = date.format("%Y-%m-%d"),
+    )
+}
+
+/// Create `docs/adr/YYYY-MM-DD-slug.md`, returning its path
+pub fn new_adr(root: &Path, title: &str, date: NaiveDate) -> Result<PathBuf> {
+    let slug = markdown::slugify(title);
+    if slug.is_empty() {
+        bail!("Title cannot be empty");
+    }
+    let dir = adr_dir(root);
+    let path = dir.join(format!("{}-{}.md", date.format("%Y-%m-%d"), slug));
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    fs::write(&path, render_adr(title.trim(), date))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+// ============================================================================
+// supersede
+// ============================================================================
+
+/// Mark `old` superseded by `new`, accept `new`, and link them both ways
+pub fn supersede(root: &Path, old: &str, new: &str) -> Result<(Adr, Adr)> {
+    let old = find(root, old)?;
+    let new = find(root, new)?;
+    if old.file == new.file {
+        bail!("An ADR cannot supersede itself");
+    }
+
+    let link = |adr: &Adr| format!("[{}]({})", adr.title, adr.file);
+    let content = fs::read_to_string(&old.path)?;
+    let content = markdown::set_bold_field(&content, "Status", "Superseded", "Date");
+    let content = markdown::set_bold_field(&content, "Superseded by", &link(&new), "Status");
+    fs::write(&old.path, content).with_context(|| format!("Failed to update {}", old.file))?;
+
+    let content = fs::read_to_string(&new.path)?;
+    let content = markdown::set_bold_field(&content, "Status", "Accepted", "Date");
+    let content = markdown::set_bold_field(&content, "Supersedes", &link(&old), "Status");
+    fs::write(&new.path, content).with_context(|| format!("Failed to update {}", new.file))?;
+
+    Ok((old, new))
+}
+
+// ============================================================================
+// index
+// ============================================================================
+
+/// The ADR table, newest first
+pub fn index_table(adrs: &[Adr]) -> String {
+    let mut table = String::from("| Date | Title | Status |\n|------|-------|--------|\n");
+    for adr in adrs {
+        table.push_str(&format!(
+            "| {} | [{}]({}) | {} |\n",
+            adr.date.as_deref().unwrap_or(""),
+            adr.title.replace('|', "\\|"),
+            adr.file,
+            adr.status_word().unwrap_or("Unknown")
+        ));
+    }
+    table
+}
+
+/// `readme` with the ADR List section's body replaced by `table`
+///
+/// The section runs to the next heading or `---` rule; it is appended if
+/// the README doesn't have one.
+pub fn replace_index(readme: &str, table: &str) -> String {
+    let lines: Vec<&str> = readme.lines().collect();
+    let Some(start) = lines.iter().position(|l| l.trim() == INDEX_HEADING) else {
+        let mut updated = readme.trim_end().to_string();
+        updated.push_str(&format!("\n\n{}\n\n{}", INDEX_HEADING, table));
+        return updated;
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| l.starts_with('#') || l.trim() == "---")
+        .map_or(lines.len(), |i| start + 1 + i);
+
+    let mut updated = lines[..=start].join("\n");
+    updated.push_str("\n\n");
+    updated.push_str(table);
+    if end < lines.len() {
+        updated.push('\n');
+        updated.push_str(&lines[end..].join("\n"));
+        if readme.ends_with('\n') {
+            updated.push('\n');
+        }
+    }
+    updated
+}
+
+/// Regenerate the README's ADR table; returns how many ADRs it lists
+pub fn index(root: &Path) -> Result<usize> {
+    let adrs = adrs(root)?;
+    let readme = adr_dir(root).join("README.md");
+    let current = if readme.exists() {
+        fs::read_to_string(&readme)?
+    } else {
+        "# Architecture Decision Records (ADRs)\n".to_string()
+    };
+    fs::create_dir_all(adr_dir(root))?;
+    fs::write(&readme, replace_index(&current, &index_table(&adrs)))
+        .with_context(|| format!("Failed to write {}", readme.display()))?;
+    Ok(adrs.len())
+}
+
+// ============================================================================
+// lint
+// ============================================================================
+
+/// Relative link targets outside fenced code blocks
+fn relative_links(content: &str) -> Vec<String> {
+    let mut links = Vec::new();
+    let mut in_code = false;
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        let mut rest = line;
+        while let Some(i) = rest.find("](") {
+            rest = &rest[i + 2..];
+            let Some(end) = rest.find(')') else { break };
+            let target = rest[..end].split('#').next().unwrap_or("").trim();
+            let external = target.contains("://") || target.starts_with("mailto:");
+            if !target.is_empty() && !external {
+                links.push(target.to_string());
+            }
+            rest = &rest[end..];
+        }
+    }
+    links
+}
+
+/// Problems per file, for files that have any
+pub fn lint(root: &Path) -> Result<Vec<(String, Vec<String>)>> {
+    let dir = adr_dir(root);
+    let mut files: Vec<(String, PathBuf, Option<Adr>)> = adrs(root)?
+        .into_iter()
+        .map(|adr| (adr.file.clone(), adr.path.clone(), Some(adr)))
+        .collect();
+    let readme = dir.join("README.md");
+    if readme.exists() {
+        files.push(("README.md".to_string(), readme, None));
+    }
+
+    let mut report = Vec::new();
+    for (file, path, adr) in files {
+        let content = fs::read_to_string(&path)?;
+        let mut problems = Vec::new();
+        if let Some(adr) = &adr {
+            match adr.status_word() {
+                None => problems.push("missing **Status:**".to_string()),
+                Some(word) if !STATUSES.contains(&word) => problems.push(format!(
+                    "unknown status '{}' (expected {})",
+                    word,
+                    STATUSES.join(", ")
+                )),
+                Some(_) => {}
+            }
+        }
+        for link in relative_links(&content) {
+            if !dir.join(&link).exists() {
+                problems.push(format!("broken link: {}", link));
+            }
+        }
+        if !problems.is_empty() {
+            report.push((file, problems));
+        }
+    }
+    Ok(report)
+}
+
+/// Run from CLI arguments
+pub fn run_from_args(args: &Args) {
+    let root = &args.root;
+    let result = match &args.command {
+        AdrCommand::New { title } => {
+            new_adr(root, title, Local::now().date_naive()).and_then(|path| {
+                println!("{} Created {}", "✓".green(), path.display());
+                index(root).map(|_| ())
+            })
+        }
+        AdrCommand::Supersede { old, new } => supersede(root, old, new).and_then(|(old, new)| {
+            println!("{} {} superseded by {}", "✓".green(), old.file, new.file);
+            index(root).map(|_| ())
+        }),
+        AdrCommand::Index => index(root).map(|count| {
+            println!(
+                "{} Indexed {} ADRs in {}/README.md",
+                "✓".green(),
+                count,
+                ADR_DIR
+            );
+        }),
+        AdrCommand::Lint => lint(root).map(|report| {
+            if report.is_empty() {
+                println!("{} No problems", "✓".green());
+                return;
+            }
+            for (file, problems) in &report {
+                println!("{} {}", "✗".red(), file);
+                for problem in problems {
+                    println!("    {}", problem);
+                }
+            }
+            std::process::exit(1);
+        }),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{} {}", "Error:".red(), e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    const README: &str = "# ADRs\n\n## ADR List\n\n| Date | Title | Status |\n|--|--|--|\n| old | row | x |\n\n---\n\n**Principle:** Why.\n";
+
+    #[test]
+    fn test_new_and_supersede() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let old = new_adr(root, "Use Kuzu", date("2026-01-07")).unwrap();
+        new_adr(root, "Use TerminusDB", date("2026-01-15")).unwrap();
+        assert!(old.ends_with("docs/adr/2026-01-07-use-kuzu.md"));
+        assert!(new_adr(root, "Use Kuzu", date("2026-01-07")).is_err());
+
+        let adr = find(root, "use-kuzu").unwrap();
+        assert_eq!(adr.title, "Use Kuzu");
+        assert_eq!(adr.status_word(), Some("Proposed"));
+
+        supersede(root, "use-kuzu", "2026-01-15-use-terminusdb.md").unwrap();
+        let old = fs::read_to_string(&old).unwrap();
+        assert!(old.contains(
+            "**Status:** Superseded  \n**Superseded by:** [Use TerminusDB](2026-01-15-use-terminusdb.md)  \n"
+        ));
+        let new =
+            fs::read_to_string(root.join(ADR_DIR).join("2026-01-15-use-terminusdb.md")).unwrap();
+        assert!(new.contains(
+            "**Status:** Accepted  \n**Supersedes:** [Use Kuzu](2026-01-07-use-kuzu.md)  \n"
+        ));
+        assert_eq!(
+            find(root, "use-terminusdb").unwrap().status_word(),
+            Some("Accepted")
+        );
+        assert!(lint(root).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_replace_index() {
+        let adrs = vec![Adr {
+            file: "2026-01-07-a.md".to_string(),
+            path: PathBuf::new(),
+            date: Some("2026-01-07".to_string()),
+            title: "A | B".to_string(),
+            status: Some("Superseded by [C](c.md)".to_string()),
+        }];
+        let updated = replace_index(README, &index_table(&adrs));
+        assert_eq!(
+            updated,
+            "# ADRs\n\n## ADR List\n\n| Date | Title | Status |\n|------|-------|--------|\n| 2026-01-07 | [A \\| B](2026-01-07-a.md) | Superseded |\n\n---\n\n**Principle:** Why.\n"
+        );
+        assert!(replace_index("# ADRs\n", "T\n").ends_with("# ADRs\n\n## ADR List\n\nT\n"));
+    }
+
+    #[test]
+    fn test_lint() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join(ADR_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("2026-01-07-a.md"),
+            "# ADR: A\n\nSee [B](2026-01-08-b.md) and [site](https://example.com).\n\n```\n[x](nowhere.md)\n```\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("2026-01-09-c.md"),
+            "# ADR: C\n\n**Status:** Done\n",
+        )
+        .unwrap();
+
+        let report = lint(temp.path()).unwrap();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].0, "2026-01-09-c.md");
+        assert!(report[0].1[0].starts_with("unknown status 'Done'"));
+        assert_eq!(
+            report[1].1,
+            vec!["missing **Status:**", "broken link: 2026-01-08-b.md"]
+        );
+    }
+}
@@ -261,7 +261,7 @@ mod tests {
     #[test]
     fn test_check_directory_exists() {
         let temp = TempDir::new().unwrap();
-        let check = check_directory(&temp.path().to_path_buf(), "Test dir");
+        let check = check_directory(temp.path(), "Test dir");
 
         assert_eq!(check.status, CheckStatus::Pass);
     }
@@ -295,7 +295,7 @@ mod tests {
     #[test]
     fn test_check_file_is_directory() {
         let temp = TempDir::new().unwrap();
-        let check = check_file(&temp.path().to_path_buf(), "Is dir");
+        let check = check_file(temp.path(), "Is dir");
 
         assert_eq!(check.status, CheckStatus::Fail);
         assert!(check.details.unwrap().contains("not a file"));
@@ -0,0 +1,519 @@
+//! `aiandi governance` command implementation.
+//!
+//! Lifecycle of evolution proposals and the decision ledger under
+//! `governance/evolution/`: scaffold a proposal, record the decision that
+//! ratifies or rejects it, and list what is still open.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{Local, NaiveDate};
+use colored::Colorize;
+
+use crate::markdown;
+
+/// Pending proposals, relative to the project root
+pub const PROPOSALS_DIR: &str = "governance/evolution/proposals";
+
+/// Ratified and rejected decisions, relative to the project root
+pub const DECISIONS_DIR: &str = "governance/evolution/decisions";
+
+/// Statuses after which a proposal is no longer open
+const CLOSED_STATUSES: &[&str] = &["ratified", "rejected", "implemented", "superseded"];
+
+/// CLI arguments for the governance command
+#[derive(Debug, Clone, clap::Args)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: GovernanceCommand,
+
+    /// Project root holding governance/
+    #[arg(long, global = true, default_value = ".")]
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum GovernanceCommand {
+    /// Scaffold an evolution proposal
+    Propose(ProposeArgs),
+    /// Record the decision on a proposal
+    Decide(DecideArgs),
+    /// List open proposals and their age
+    Status,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct ProposeArgs {
+    /// Proposal title
+    pub title: String,
+
+    /// Who is proposing
+    #[arg(long, default_value = "Governance Session")]
+    pub author: String,
+
+    /// Show what would be created without creating
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct DecideArgs {
+    /// Proposal file name, with or without `.md`
+    pub proposal: String,
+
+    /// The decision
+    #[arg(long, short = 'o')]
+    pub outcome: Outcome,
+
+    /// Why it was decided this way
+    #[arg(long, short = 'r')]
+    pub reasoning: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Outcome {
+    Ratified,
+    Rejected,
+}
+
+impl Outcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Ratified => "ratified",
+            Outcome::Rejected => "rejected",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Outcome::Ratified => "Ratified",
+            Outcome::Rejected => "Rejected",
+        }
+    }
+}
+
+/// A proposal file
+///
+/// Proposals written by `propose` carry front matter; older ones use
+/// `**Status:**` / `**Created:**` lines, which are read the same way.
+#[derive(Debug, Clone)]
+pub struct Proposal {
+    /// File name without `.md`
+    pub name: String,
+    pub path: PathBuf,
+    pub title: String,
+    /// Lowercased, e.g. `proposed`, `ratified`
+    pub status: String,
+    pub created: Option<NaiveDate>,
+    /// Id of the decision on it, once decided
+    pub decision: Option<String>,
+}
+
+impl Proposal {
+    pub fn is_open(&self) -> bool {
+        !CLOSED_STATUSES.contains(&self.status.as_str())
+    }
+
+    /// Whole days since it was created
+    pub fn age_days(&self, today: NaiveDate) -> Option<i64> {
+        self.created.map(|created| (today - created).num_days())
+    }
+
+    fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let get = |key: &str| {
+            markdown::field(&content, key).or_else(|| markdown::bold_field(&content, key))
+        };
+
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let title = markdown::field(&content, "title")
+            .or_else(|| {
+                content
+                    .lines()
+                    .find_map(|l| l.strip_prefix("# "))
+                    .map(|t| t.trim().to_string())
+            })
+            .unwrap_or_else(|| name.clone());
+        // Older files write "Proposal" while undecided and
+        // "Ratified (DEC-001)" once decided
+        let raw_status = get("status").unwrap_or_default();
+        let status = match raw_status.split_whitespace().next().map(str::to_lowercase) {
+            None => "proposed".to_string(),
+            Some(s) if s == "proposal" => "proposed".to_string(),
+            Some(s) => s,
+        };
+        let decision = get("decision").or_else(|| {
+            let (_, rest) = raw_status.split_once('(')?;
+            rest.strip_suffix(')').map(String::from)
+        });
+
+        Ok(Self {
+            created: get("created").and_then(|c| NaiveDate::parse_from_str(&c, "%Y-%m-%d").ok()),
+            decision,
+            name,
+            path: path.to_path_buf(),
+            title,
+            status,
+        })
+    }
+}
+
+/// `.md` files in `dir`, sorted by name (READMEs excluded)
+fn markdown_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        let is_md = path.extension().is_some_and(|e| e == "md");
+        let is_readme = path
+            .file_stem()
+            .is_some_and(|s| s.eq_ignore_ascii_case("readme"));
+        if path.is_file() && is_md && !is_readme {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Every proposal, sorted by file name
+pub fn proposals(root: &Path) -> Result<Vec<Proposal>> {
+    markdown_files(&root.join(PROPOSALS_DIR))?
+        .iter()
+        .map(|path| Proposal::load(path))
+        .collect()
+}
+
+/// Find a proposal by file name, with or without `.md`
+pub fn find_proposal(root: &Path, name: &str) -> Result<Proposal> {
+    let name = name.trim_end_matches(".md");
+    proposals(root)?
+        .into_iter()
+        .find(|p| p.name == name)
+        .with_context(|| format!("No proposal '{}' in {}", name, PROPOSALS_DIR))
+}
+
+// ============================================================================
+// propose
+// ============================================================================
+
+/// The scaffold for a new proposal
+pub fn render_proposal(title: &str, author: &str, created: NaiveDate) -> String {
+    format!(
+        "---\ntitle: {title}\nstatus: proposed\ncreated: {created}\nauthor: {author}\n---\n\n\
+         # {title}\n\n\
+         ## Problem\n\n[What is not working, and what observation shows it]\n\n\
+         ## Proposal\n\n[The change to canon, protocols or architecture]\n\n\
+         ## Alternatives Considered\n\n[Other options, and why not them]\n\n\
+         ## Consequences\n\n[What changes if this is ratified; what it costs]\n",
+        created = created.format("%Y-%m-%d"),
+    )
+}
+
+/// Write a new proposal, returning its path
+pub fn propose(root: &Path, title: &str, author: &str, dry_run: bool) -> Result<PathBuf> {
+    let slug = markdown::slugify(title);
+    if slug.is_empty() {
+        bail!("Title cannot be empty");
+    }
+    let dir = root.join(PROPOSALS_DIR);
+    let path = dir.join(format!("{}.md", slug));
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+    if dry_run {
+        return Ok(path);
+    }
+
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    fs::write(
+        &path,
+        render_proposal(title.trim(), author, Local::now().date_naive()),
+    )
+    .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+// ============================================================================
+// decide
+// ============================================================================
+
+/// The next decision id, one past the highest `DEC-NNN` in the ledger
+pub fn next_decision_id(root: &Path) -> Result<String> {
+    let highest = markdown_files(&root.join(DECISIONS_DIR))?
+        .iter()
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_str()?;
+            stem.strip_prefix("DEC-")?
+                .split('-')
+                .next()?
+                .parse::<u32>()
+                .ok()
+        })
+        .max()
+        .unwrap_or(0);
+    Ok(format!("DEC-{:03}", highest + 1))
+}
+
+/// The decision record
+pub fn render_decision(
+    id: &str,
+    proposal: &Proposal,
+    outcome: Outcome,
+    reasoning: Option<&str>,
+    decided: NaiveDate,
+) -> String {
+    format!(
+        "---\nid: {id}\ntitle: {title}\noutcome: {outcome}\nproposal: ../proposals/{name}.md\ndecided: {decided}\n---\n\n\
+         # {id}: {title}\n\n\
+         **Outcome:** {label}  \n\
+         **Proposal:** [{name}](../proposals/{name}.md)\n\n\
+         ## Reasoning\n\n{reasoning}\n",
+        title = proposal.title,
+        outcome = outcome.as_str(),
+        name = proposal.name,
+        decided = decided.format("%Y-%m-%d"),
+        label = outcome.label(),
+        reasoning = reasoning
+            .map(str::trim)
+            .unwrap_or("[Why this was decided, and what it changes]"),
+    )
+}
+
+/// `content` with its status set, in whichever form the file uses
+fn mark_decided(content: &str, outcome: Outcome, id: &str) -> String {
+    if !markdown::front_matter(content).is_empty() {
+        let content = markdown::set_field(content, "status", outcome.as_str());
+        return markdown::set_field(&content, "decision", id);
+    }
+    if markdown::bold_field(content, "status").is_some() {
+        let status = format!("{} ({})", outcome.label(), id);
+        return markdown::set_bold_field(content, "Status", &status, "Created");
+    }
+    let content = markdown::set_field(content, "status", outcome.as_str());
+    markdown::set_field(&content, "decision", id)
+}
+
+/// Record the decision on `name`, update the proposal's status, and
+/// return the decision's id and path
+pub fn decide(
+    root: &Path,
+    name: &str,
+    outcome: Outcome,
+    reasoning: Option<&str>,
+) -> Result<(String, PathBuf)> {
+    let proposal = find_proposal(root, name)?;
+    if let Some(decision) = &proposal.decision {
+        bail!("'{}' was already decided in {}", proposal.name, decision);
+    }
+    if !proposal.is_open() {
+        bail!("'{}' is already {}", proposal.name, proposal.status);
+    }
+
+    let id = next_decision_id(root)?;
+    let dir = root.join(DECISIONS_DIR);
+    let path = dir.join(format!("{}-{}.md", id, proposal.name));
+    let record = render_decision(
+        &id,
+        &proposal,
+        outcome,
+        reasoning,
+        Local::now().date_naive(),
+    );
+
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    fs::write(&path, record).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let content = fs::read_to_string(&proposal.path)
+        .with_context(|| format!("Failed to read {}", proposal.path.display()))?;
+    fs::write(&proposal.path, mark_decided(&content, outcome, &id))
+        .with_context(|| format!("Failed to update {}", proposal.path.display()))?;
+    Ok((id, path))
+}
+
+// ============================================================================
+// status
+// ============================================================================
+
+/// Open proposals as a table, oldest first
+pub fn status_report(root: &Path, today: NaiveDate) -> Result<String> {
+    let mut open: Vec<Proposal> = proposals(root)?
+        .into_iter()
+        .filter(|p| p.is_open())
+        .collect();
+    let decided = markdown_files(&root.join(DECISIONS_DIR))?.len();
+    if open.is_empty() {
+        return Ok(format!("No open proposals ({} decided)\n", decided));
+    }
+
+    // Undated proposals last
+    open.sort_by_key(|p| (p.created.is_none(), p.created));
+    let width = open.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    let mut out = format!(
+        "{} open proposal{} ({} decided)\n",
+        open.len(),
+        if open.len() == 1 { "" } else { "s" },
+        decided
+    );
+    for p in &open {
+        let age = p
+            .age_days(today)
+            .map_or("?".to_string(), |d| format!("{}d", d));
+        out.push_str(&format!(
+            "  {:<width$}  {:<9} {:>5}  {}\n",
+            p.name,
+            p.status,
+            age,
+            p.title,
+            width = width
+        ));
+    }
+    Ok(out)
+}
+
+/// Run from CLI arguments
+pub fn run_from_args(args: &Args) {
+    let root = &args.root;
+    let result = match &args.command {
+        GovernanceCommand::Propose(propose_args) => propose(
+            root,
+            &propose_args.title,
+            &propose_args.author,
+            propose_args.dry_run,
+        )
+        .map(|path| {
+            if propose_args.dry_run {
+                println!("{} Would create {}", "[dry-run]".blue(), path.display());
+            } else {
+                println!("{} Created {}", "✓".green(), path.display());
+            }
+        }),
+        GovernanceCommand::Decide(decide_args) => decide(
+            root,
+            &decide_args.proposal,
+            decide_args.outcome,
+            decide_args.reasoning.as_deref(),
+        )
+        .map(|(id, path)| {
+            println!(
+                "{} {} {} {}",
+                "✓".green(),
+                id,
+                decide_args.outcome.as_str(),
+                path.display()
+            );
+        }),
+        GovernanceCommand::Status => {
+            status_report(root, Local::now().date_naive()).map(|report| print!("{}", report))
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("{} {}", "Error:".red(), e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn legacy_proposal(root: &Path) {
+        let dir = root.join(PROPOSALS_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("agent-design-framework.md"),
+            "# Agent Design Framework\n\n**Status:** Proposal  \n**Created:** 2026-01-11  \n\n---\n\nBody\n",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_reads_legacy_proposal() {
+        let temp = TempDir::new().unwrap();
+        legacy_proposal(temp.path());
+
+        let p = find_proposal(temp.path(), "agent-design-framework.md").unwrap();
+        assert_eq!(p.title, "Agent Design Framework");
+        assert_eq!(p.status, "proposed");
+        assert!(p.is_open());
+        assert_eq!(p.age_days(date("2026-01-21")), Some(10));
+    }
+
+    #[test]
+    fn test_propose_and_decide() {
+        let temp = TempDir::new().unwrap();
+        legacy_proposal(temp.path());
+        let path = propose(temp.path(), "Ledger for Decisions", "Ops", false).unwrap();
+        assert!(path.ends_with("proposals/ledger-for-decisions.md"));
+        assert!(propose(temp.path(), "Ledger for Decisions", "Ops", false).is_err());
+
+        let (id, record) = decide(
+            temp.path(),
+            "ledger-for-decisions",
+            Outcome::Ratified,
+            Some("Needed"),
+        )
+        .unwrap();
+        assert_eq!(id, "DEC-001");
+        let record = fs::read_to_string(record).unwrap();
+        assert!(record.contains("proposal: ../proposals/ledger-for-decisions.md"));
+        assert!(record.contains("# DEC-001: Ledger for Decisions"));
+
+        let decided = find_proposal(temp.path(), "ledger-for-decisions").unwrap();
+        assert_eq!(decided.status, "ratified");
+        assert_eq!(decided.decision.as_deref(), Some("DEC-001"));
+        assert!(decide(temp.path(), "ledger-for-decisions", Outcome::Rejected, None).is_err());
+
+        let (id, _) = decide(
+            temp.path(),
+            "agent-design-framework",
+            Outcome::Rejected,
+            None,
+        )
+        .unwrap();
+        assert_eq!(id, "DEC-002");
+        let legacy = fs::read_to_string(
+            temp.path()
+                .join(PROPOSALS_DIR)
+                .join("agent-design-framework.md"),
+        )
+        .unwrap();
+        assert!(legacy.contains("**Status:** Rejected (DEC-002)  \n**Created:** 2026-01-11"));
+        let legacy = find_proposal(temp.path(), "agent-design-framework").unwrap();
+        assert_eq!(legacy.status, "rejected");
+        assert_eq!(legacy.decision.as_deref(), Some("DEC-002"));
+
+        assert_eq!(
+            status_report(temp.path(), date("2026-01-21")).unwrap(),
+            "No open proposals (2 decided)\n"
+        );
+    }
+
+    #[test]
+    fn test_status_lists_open_oldest_first() {
+        let temp = TempDir::new().unwrap();
+        propose(temp.path(), "Newer", "Ops", false).unwrap();
+        legacy_proposal(temp.path());
+
+        let report = status_report(temp.path(), date("2026-01-21")).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "2 open proposals (0 decided)");
+        assert!(
+            lines[1].contains("agent-design-framework  proposed    10d  Agent Design Framework")
+        );
+        assert!(lines[2].contains("newer"));
+    }
+}
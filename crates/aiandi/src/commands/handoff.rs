@@ -0,0 +1,540 @@
+//! `aiandi handoff` command implementation.
+//!
+//! Session-to-session handoff through `inbox/`, following
+//! `docs/inbox-outbox-protocol.md`: a closing session writes items for its
+//! successor, the opening session reads them as one bundle and acknowledges
+//! them into `inbox/processed/`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use colored::Colorize;
+
+use crate::markdown;
+
+/// Handoff items, relative to the project root
+pub const INBOX_DIR: &str = "inbox";
+
+/// Acknowledged items, relative to the inbox
+pub const PROCESSED_DIR: &str = "processed";
+
+/// CLI arguments for the handoff command
+#[derive(Debug, Clone, clap::Args)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: HandoffCommand,
+
+    /// Project root holding inbox/
+    #[arg(long, global = true, default_value = ".")]
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum HandoffCommand {
+    /// Leave an item in inbox/ for the next session
+    Write(WriteArgs),
+    /// Show pending items, highest priority first, as one context bundle
+    Read(ReadArgs),
+    /// Move items to inbox/processed/
+    Ack(AckArgs),
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct WriteArgs {
+    /// Title of the item
+    pub title: String,
+
+    /// Kind of item
+    #[arg(long = "type", short = 't', default_value = "context")]
+    pub kind: HandoffType,
+
+    /// How soon the successor needs it
+    #[arg(long, short = 'p', default_value = "medium")]
+    pub priority: HandoffPriority,
+
+    /// Session this comes from (default: today's date)
+    #[arg(long)]
+    pub from_session: Option<String>,
+
+    /// What the successor needs to know
+    #[arg(long, short = 'b')]
+    pub body: Option<String>,
+
+    /// Why it matters
+    #[arg(long, short = 'w')]
+    pub why: Option<String>,
+
+    /// Show what would be written without writing
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct ReadArgs {
+    /// Only list the items, without their content
+    #[arg(long, short = 'l')]
+    pub list: bool,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct AckArgs {
+    /// Item file names (as shown by `handoff read`)
+    pub items: Vec<String>,
+
+    /// Acknowledge every pending item
+    #[arg(long, short = 'a', conflicts_with = "items")]
+    pub all: bool,
+}
+
+/// The item types the protocol defines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum HandoffType {
+    /// Key learnings, patterns discovered, decisions made
+    Inheritance,
+    /// Gotchas, breaking changes, things to avoid
+    Warning,
+    /// Background information that will save time
+    Context,
+    /// Pointers to relevant files, docs, issues
+    Links,
+}
+
+impl HandoffType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HandoffType::Inheritance => "inheritance",
+            HandoffType::Warning => "warning",
+            HandoffType::Context => "context",
+            HandoffType::Links => "links",
+        }
+    }
+}
+
+/// Ordered so that sorting puts `High` first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum HandoffPriority {
+    High,
+    Medium,
+    Low,
+}
+
+impl HandoffPriority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HandoffPriority::High => "high",
+            HandoffPriority::Medium => "medium",
+            HandoffPriority::Low => "low",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "high" => Some(HandoffPriority::High),
+            "medium" => Some(HandoffPriority::Medium),
+            "low" => Some(HandoffPriority::Low),
+            _ => None,
+        }
+    }
+}
+
+/// A pending inbox item
+///
+/// Front matter is optional in the protocol, so every field may be missing;
+/// items without a priority sort as `medium`.
+#[derive(Debug, Clone)]
+pub struct HandoffItem {
+    /// File name within `inbox/`
+    pub name: String,
+    pub kind: Option<String>,
+    pub priority: Option<String>,
+    pub from_session: Option<String>,
+    pub created: Option<String>,
+    /// The markdown after the front matter
+    pub body: String,
+}
+
+impl HandoffItem {
+    fn rank(&self) -> HandoffPriority {
+        self.priority
+            .as_deref()
+            .and_then(HandoffPriority::parse)
+            .unwrap_or(HandoffPriority::Medium)
+    }
+}
+
+// ============================================================================
+// write
+// ============================================================================
+
+/// Options for writing an item (for testing and the MCP server)
+#[derive(Debug, Clone)]
+pub struct WriteOptions {
+    pub title: String,
+    pub kind: HandoffType,
+    pub priority: HandoffPriority,
+    pub from_session: Option<String>,
+    pub body: Option<String>,
+    pub why: Option<String>,
+    pub dry_run: bool,
+}
+
+impl From<&WriteArgs> for WriteOptions {
+    fn from(args: &WriteArgs) -> Self {
+        Self {
+            title: args.title.clone(),
+            kind: args.kind,
+            priority: args.priority,
+            from_session: args.from_session.clone(),
+            body: args.body.clone(),
+            why: args.why.clone(),
+            dry_run: args.dry_run,
+        }
+    }
+}
+
+/// The item's markdown, front matter first
+pub fn render_item(options: &WriteOptions, now: DateTime<Utc>) -> String {
+    let from_session = options
+        .from_session
+        .clone()
+        .unwrap_or_else(|| now.format("%Y-%m-%d").to_string());
+    let mut md = format!(
+        "---\ntype: {}\npriority: {}\nfrom-session: {}\ncreated: {}\n---\n\n# {}\n\n{}\n",
+        options.kind.as_str(),
+        options.priority.as_str(),
+        from_session,
+        now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        options.title.trim(),
+        options
+            .body
+            .as_deref()
+            .map(str::trim)
+            .unwrap_or("[Content that successor session needs]")
+    );
+    if let Some(why) = &options.why {
+        md.push_str(&format!("\n## Why This Matters\n\n{}\n", why.trim()));
+    }
+    md
+}
+
+/// Write an item to `root/inbox/`, returning its path
+pub fn write_item(root: &Path, options: &WriteOptions) -> Result<PathBuf> {
+    let slug = markdown::slugify(&options.title);
+    if slug.is_empty() {
+        bail!("Title cannot be empty");
+    }
+
+    let dir = root.join(INBOX_DIR);
+    let path = dir.join(format!("{}-{}.md", options.kind.as_str(), slug));
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+    if options.dry_run {
+        return Ok(path);
+    }
+
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    fs::write(&path, render_item(options, Utc::now()))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+// ============================================================================
+// read
+// ============================================================================
+
+/// Pending items in `root/inbox/`, highest priority first, then oldest
+pub fn pending(root: &Path) -> Result<Vec<HandoffItem>> {
+    let dir = root.join(INBOX_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut items = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().is_none_or(|e| e != "md") {
+            continue;
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let field = |key: &str| markdown::field(&content, key);
+        items.push(HandoffItem {
+            name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            kind: field("type"),
+            priority: field("priority"),
+            from_session: field("from-session"),
+            created: field("created"),
+            body: markdown::strip_front_matter(&content).trim().to_string(),
+        });
+    }
+
+    // Undated items after dated ones of the same priority
+    items.sort_by(|a, b| {
+        let key = |i: &HandoffItem| {
+            (
+                i.rank(),
+                i.created.is_none(),
+                i.created.clone(),
+                i.name.clone(),
+            )
+        };
+        key(a).cmp(&key(b))
+    });
+    Ok(items)
+}
+
+/// One-line summary, as the protocol reports it to the user
+pub fn summary(items: &[HandoffItem]) -> String {
+    if items.is_empty() {
+        return "*Inbox: empty. No predecessor handoff.*".to_string();
+    }
+
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for item in items {
+        let kind = item.kind.clone().unwrap_or_else(|| "untyped".to_string());
+        match counts.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((kind, 1)),
+        }
+    }
+    let breakdown: Vec<String> = counts
+        .iter()
+        .map(|(kind, n)| {
+            // "links" is already plural
+            let plural = *n > 1 && !kind.ends_with('s');
+            format!("{} {}{}", n, kind, if plural { "s" } else { "" })
+        })
+        .collect();
+    format!(
+        "*Inbox: {} item{} pending ({}).*",
+        items.len(),
+        if items.len() == 1 { "" } else { "s" },
+        breakdown.join(", ")
+    )
+}
+
+/// All pending items as one markdown document
+pub fn bundle(items: &[HandoffItem]) -> String {
+    let mut md = summary(items);
+    md.push('\n');
+    for item in items {
+        let mut about = vec![item.rank().as_str().to_string()];
+        about.extend(item.kind.clone());
+        about.extend(item.from_session.as_ref().map(|s| format!("from {}", s)));
+        md.push_str(&format!(
+            "\n---\n\n<!-- inbox/{} ({}) -->\n\n{}\n",
+            item.name,
+            about.join(", "),
+            item.body
+        ));
+    }
+    md
+}
+
+// ============================================================================
+// ack
+// ============================================================================
+
+/// Move items to `inbox/processed/`, prefixed with when they were processed
+///
+/// With no names, every pending item is acknowledged. Returns the new paths.
+pub fn ack(root: &Path, names: &[String], now: DateTime<Utc>) -> Result<Vec<PathBuf>> {
+    let inbox = root.join(INBOX_DIR);
+    let names: Vec<String> = if names.is_empty() {
+        pending(root)?.into_iter().map(|item| item.name).collect()
+    } else {
+        names.to_vec()
+    };
+
+    // Check every name before moving anything
+    for name in &names {
+        let path = inbox.join(name);
+        if name.contains(['/', '\\']) || !path.is_file() {
+            bail!("No pending item '{}' in {}", name, inbox.display());
+        }
+    }
+
+    let processed = inbox.join(PROCESSED_DIR);
+    fs::create_dir_all(&processed)
+        .with_context(|| format!("Failed to create {}", processed.display()))?;
+    let stamp = now.format("%Y%m%dT%H%M%SZ");
+    let mut moved = Vec::new();
+    for name in names {
+        let target = processed.join(format!("{}-{}", stamp, name));
+        fs::rename(inbox.join(&name), &target)
+            .with_context(|| format!("Failed to move {} to {}", name, processed.display()))?;
+        moved.push(target);
+    }
+    Ok(moved)
+}
+
+/// Run from CLI arguments
+pub fn run_from_args(args: &Args) {
+    let result = match &args.command {
+        HandoffCommand::Write(write) => run_write(&args.root, write),
+        HandoffCommand::Read(read) => run_read(&args.root, read),
+        HandoffCommand::Ack(acknowledge) => run_ack(&args.root, acknowledge),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{} {}", "Error:".red(), e);
+        std::process::exit(1);
+    }
+}
+
+fn run_write(root: &Path, args: &WriteArgs) -> Result<()> {
+    let options = WriteOptions::from(args);
+    let path = write_item(root, &options)?;
+    if options.dry_run {
+        println!("{} Would write {}", "[dry-run]".blue(), path.display());
+    } else {
+        println!("{} Wrote {}", "✓".green(), path.display());
+    }
+    Ok(())
+}
+
+fn run_read(root: &Path, args: &ReadArgs) -> Result<()> {
+    let items = pending(root)?;
+    if !args.list {
+        print!("{}", bundle(&items));
+        return Ok(());
+    }
+
+    println!("{}", summary(&items));
+    for item in &items {
+        println!(
+            "  {} {:<8} {:<12} {}{}",
+            "→".yellow(),
+            item.rank().as_str(),
+            item.kind.as_deref().unwrap_or("-"),
+            item.name,
+            item.from_session
+                .as_deref()
+                .map(|s| format!(" (from {})", s))
+                .unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+fn run_ack(root: &Path, args: &AckArgs) -> Result<()> {
+    if args.items.is_empty() && !args.all {
+        bail!("Name the items to acknowledge, or pass --all");
+    }
+    let moved = ack(root, &args.items, Utc::now())?;
+    if moved.is_empty() {
+        println!("{} Inbox already empty", "→".yellow());
+    }
+    for path in moved {
+        println!("{} {}", "✓".green(), path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn options(title: &str, kind: HandoffType, priority: HandoffPriority) -> WriteOptions {
+        WriteOptions {
+            title: title.to_string(),
+            kind,
+            priority,
+            from_session: Some("2026-01-10-gtd-capture".to_string()),
+            body: Some("Body".to_string()),
+            why: None,
+            dry_run: false,
+        }
+    }
+
+    #[test]
+    fn test_render_item_front_matter() {
+        let mut opts = options(
+            "AUR Package Location",
+            HandoffType::Inheritance,
+            HandoffPriority::High,
+        );
+        opts.why = Some("Saves re-discovering it".to_string());
+        let now = "2026-01-10T18:30:00Z".parse().unwrap();
+        let md = render_item(&opts, now);
+
+        assert!(md.starts_with(
+            "---\ntype: inheritance\npriority: high\nfrom-session: 2026-01-10-gtd-capture\ncreated: 2026-01-10T18:30:00Z\n---\n\n# AUR Package Location\n"
+        ));
+        assert!(md.ends_with("## Why This Matters\n\nSaves re-discovering it\n"));
+        assert_eq!(markdown::front_matter(&md).len(), 4);
+    }
+
+    #[test]
+    fn test_pending_sorted_by_priority() {
+        let temp = TempDir::new().unwrap();
+        for (title, kind, priority) in [
+            ("Low one", HandoffType::Links, HandoffPriority::Low),
+            ("API change", HandoffType::Warning, HandoffPriority::High),
+            ("Background", HandoffType::Context, HandoffPriority::Medium),
+        ] {
+            write_item(temp.path(), &options(title, kind, priority)).unwrap();
+        }
+        // Hand-written, without front matter
+        fs::write(temp.path().join("inbox/notes.md"), "# Notes\n").unwrap();
+        fs::create_dir_all(temp.path().join("inbox/processed")).unwrap();
+        fs::write(temp.path().join("inbox/processed/old.md"), "old").unwrap();
+
+        let items = pending(temp.path()).unwrap();
+        let names: Vec<&str> = items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "warning-api-change.md",
+                "context-background.md",
+                "notes.md",
+                "links-low-one.md"
+            ]
+        );
+        assert_eq!(
+            summary(&items),
+            "*Inbox: 4 items pending (1 warning, 1 context, 1 untyped, 1 links).*"
+        );
+        let bundle = bundle(&items);
+        assert!(bundle.contains("<!-- inbox/notes.md (medium) -->\n\n# Notes\n"));
+        assert!(bundle.contains(
+            "<!-- inbox/warning-api-change.md (high, warning, from 2026-01-10-gtd-capture) -->\n\n# API change\n\nBody\n"
+        ));
+
+        let again = write_item(
+            temp.path(),
+            &options("API change", HandoffType::Warning, HandoffPriority::Low),
+        );
+        assert!(again.is_err());
+    }
+
+    #[test]
+    fn test_ack_moves_to_processed() {
+        let temp = TempDir::new().unwrap();
+        for title in ["First", "Second"] {
+            write_item(
+                temp.path(),
+                &options(title, HandoffType::Context, HandoffPriority::Medium),
+            )
+            .unwrap();
+        }
+        let now = "2026-01-11T09:00:00Z".parse().unwrap();
+
+        assert!(ack(temp.path(), &["missing.md".to_string()], now).is_err());
+
+        let moved = ack(temp.path(), &["context-first.md".to_string()], now).unwrap();
+        assert!(moved[0].ends_with("inbox/processed/20260111T090000Z-context-first.md"));
+        assert_eq!(pending(temp.path()).unwrap().len(), 1);
+
+        ack(temp.path(), &[], now).unwrap();
+        assert!(pending(temp.path()).unwrap().is_empty());
+        assert_eq!(summary(&[]), "*Inbox: empty. No predecessor handoff.*");
+    }
+}
@@ -1,12 +1,47 @@
 //! `aiandi inbox` command implementation.
 //!
-//! Quick capture to GTD inbox via TaskWarrior.
+//! Quick capture to the GTD inbox: a TaskWarrior task, a
+//! `00-inbox/capture-*.md` note in the vault, or both.
 
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local};
 use colored::Colorize;
 
+use crate::config::Config;
+use crate::markdown::slugify;
+
+/// Vault folder captures are written to
+pub const VAULT_INBOX: &str = "00-inbox";
+
+/// Longest slug used in a capture file name
+const MAX_SLUG_LEN: usize = 48;
+
+/// Where a capture goes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CaptureBackend {
+    /// TaskWarrior task tagged +inbox
+    #[default]
+    Taskwarrior,
+    /// Markdown capture file in the vault inbox
+    Vault,
+    /// Both of the above
+    Both,
+}
+
+impl CaptureBackend {
+    fn taskwarrior(self) -> bool {
+        matches!(self, Self::Taskwarrior | Self::Both)
+    }
+
+    fn vault(self) -> bool {
+        matches!(self, Self::Vault | Self::Both)
+    }
+}
+
 /// CLI arguments for the inbox command
 #[derive(Debug, Clone, clap::Args)]
 pub struct Args {
@@ -21,6 +56,18 @@ pub struct Args {
     #[arg(long, short = 'p')]
     pub project: Option<String>,
 
+    /// Where to capture
+    #[arg(long, short = 'b', value_enum, default_value_t = CaptureBackend::Taskwarrior)]
+    pub backend: CaptureBackend,
+
+    /// Recorded as `source` in vault captures
+    #[arg(long, default_value = "cli")]
+    pub source: String,
+
+    /// Vault to capture into (defaults to the one in the aiandi config)
+    #[arg(long)]
+    pub vault: Option<PathBuf>,
+
     /// Show what would be added without adding
     #[arg(long)]
     pub dry_run: bool,
@@ -32,6 +79,11 @@ pub struct InboxOptions {
     pub text: String,
     pub tags: Vec<String>,
     pub project: Option<String>,
+    pub backend: CaptureBackend,
+    /// Where the capture came from (vault backend only)
+    pub source: Option<String>,
+    /// Vault root (None = the configured vault)
+    pub vault: Option<PathBuf>,
     pub dry_run: bool,
 }
 
@@ -41,19 +93,29 @@ impl From<&Args> for InboxOptions {
             text: args.text.clone(),
             tags: args.tags.clone(),
             project: args.project.clone(),
+            backend: args.backend,
+            source: Some(args.source.clone()),
+            vault: args.vault.clone(),
             dry_run: args.dry_run,
         }
     }
 }
 
 /// Result of inbox capture
-#[derive(Debug)]
+#[derive(Debug, Default)]
 #[allow(dead_code)] // Fields used for debugging and future features
 pub struct InboxResult {
     /// TaskWarrior task ID (if created)
     pub task_id: Option<u32>,
-    /// Full command that was/would be executed
+    /// Full command that was/would be executed (empty for vault-only captures)
     pub command: String,
+    /// Vault capture file that was/would be written
+    pub capture_path: Option<PathBuf>,
+    /// What was (or, in a dry run, would be) done, one line per backend
+    ///
+    /// Left to the caller to show: under `aiandi serve` stdout carries the
+    /// MCP protocol.
+    pub messages: Vec<String>,
 }
 
 /// Check if TaskWarrior is installed
@@ -97,19 +159,50 @@ pub fn run_with_options(options: &InboxOptions) -> Result<InboxResult> {
         bail!("Task description cannot be empty");
     }
 
-    // Check TaskWarrior is available
-    check_taskwarrior()?;
+    // Resolve everything before writing anywhere, and take the vault capture
+    // back if `task add` fails, so `both` never half-captures
+    if options.backend.taskwarrior() {
+        check_taskwarrior()?;
+    }
+    let vault = if options.backend.vault() {
+        Some(resolve_vault(options.vault.as_deref())?)
+    } else {
+        None
+    };
+
+    let mut result = InboxResult::default();
+    if let Some(vault) = vault {
+        let (path, message) = capture_to_vault(&vault, options, Local::now())?;
+        result.capture_path = Some(path);
+        result.messages.push(message);
+    }
+    if options.backend.taskwarrior() {
+        let (task_id, command, message) = match capture_to_taskwarrior(options) {
+            Ok(added) => added,
+            Err(e) => {
+                // A retry would otherwise leave a duplicate capture
+                if let Some(path) = &result.capture_path {
+                    let _ = fs::remove_file(path);
+                }
+                return Err(e);
+            }
+        };
+        result.task_id = task_id;
+        result.command = command;
+        result.messages.push(message);
+    }
+    Ok(result)
+}
 
+/// Add the task; returns its ID, the command line and what was done
+fn capture_to_taskwarrior(options: &InboxOptions) -> Result<(Option<u32>, String, String)> {
     // Build command
     let args = build_task_command(options);
     let command_str = format!("task {}", args.join(" "));
 
     if options.dry_run {
-        println!("{} Would run: {}", "[dry-run]".blue(), command_str);
-        return Ok(InboxResult {
-            task_id: None,
-            command: command_str,
-        });
+        let message = format!("Would run: {}", command_str);
+        return Ok((None, command_str, message));
     }
 
     // Execute TaskWarrior
@@ -127,17 +220,114 @@ pub fn run_with_options(options: &InboxOptions) -> Result<InboxResult> {
     let stdout = String::from_utf8_lossy(&output.stdout);
     let task_id = parse_task_id(&stdout);
 
-    // Print confirmation
-    if let Some(id) = task_id {
-        println!("{} Captured to inbox: task {}", "✓".green(), id);
+    let message = match task_id {
+        Some(id) => format!("Captured to inbox: task {}", id),
+        None => "Captured to inbox".to_string(),
+    };
+    Ok((task_id, command_str, message))
+}
+
+/// The explicit vault, else the one recorded by `aiandi vault init`
+pub fn resolve_vault(explicit: Option<&Path>) -> Result<PathBuf> {
+    let vault = match explicit {
+        Some(path) => path.to_path_buf(),
+        None => {
+            let config_path = Config::default_path();
+            Config::load(&config_path)?
+                .vault
+                .map(|v| v.path)
+                .with_context(|| {
+                    format!(
+                        "No vault configured in {}. Run `aiandi vault init <path>` or pass --vault",
+                        config_path.display()
+                    )
+                })?
+        }
+    };
+    if !vault.is_dir() {
+        bail!("Vault not found: {}", vault.display());
+    }
+    Ok(vault)
+}
+
+/// `capture-YYYY-MM-DD-HHmmss-slug.md`, per the vault file patterns
+pub fn capture_file_name(text: &str, now: DateTime<Local>) -> String {
+    // Whole words only, unless the first word alone is too long
+    let mut slug = String::new();
+    for word in slugify(text).split('-') {
+        if slug.len() + word.len() + 1 > MAX_SLUG_LEN {
+            if slug.is_empty() {
+                slug = word.chars().take(MAX_SLUG_LEN).collect();
+            }
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(word);
+    }
+    let stamp = now.format("%Y-%m-%d-%H%M%S");
+    if slug.is_empty() {
+        format!("capture-{}.md", stamp)
     } else {
-        println!("{} Captured to inbox", "✓".green());
+        format!("capture-{}-{}.md", stamp, slug)
+    }
+}
+
+/// Capture note: front matter for tags, project and source, then the text
+pub fn capture_content(options: &InboxOptions, now: DateTime<Local>) -> String {
+    let tags: Vec<&str> = options
+        .tags
+        .iter()
+        .map(|t| t.trim_start_matches('+'))
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    let mut content = String::from("---\n");
+    content.push_str(&format!("created: {}\n", now.to_rfc3339()));
+    content.push_str(&format!("tags: [{}]\n", tags.join(", ")));
+    if let Some(project) = &options.project {
+        content.push_str(&format!("project: {}\n", project));
+    }
+    if let Some(source) = &options.source {
+        content.push_str(&format!("source: {}\n", source));
+    }
+    content.push_str("---\n\n");
+    content.push_str(options.text.trim());
+    content.push('\n');
+    content
+}
+
+/// Write the capture note into the vault inbox; returns its path and what
+/// was done
+fn capture_to_vault(
+    vault: &Path,
+    options: &InboxOptions,
+    now: DateTime<Local>,
+) -> Result<(PathBuf, String)> {
+    let inbox = vault.join(VAULT_INBOX);
+    let name = capture_file_name(&options.text, now);
+    let mut path = inbox.join(&name);
+    // Two captures of the same text within a second
+    let mut n = 2;
+    while path.exists() {
+        path = inbox.join(name.replace(".md", &format!("-{}.md", n)));
+        n += 1;
+    }
+    let relative = path
+        .strip_prefix(vault)
+        .unwrap_or(&path)
+        .display()
+        .to_string();
+
+    if options.dry_run {
+        return Ok((path, format!("Would write {}", relative)));
     }
 
-    Ok(InboxResult {
-        task_id,
-        command: command_str,
-    })
+    fs::create_dir_all(&inbox).with_context(|| format!("Failed to create {}", inbox.display()))?;
+    fs::write(&path, capture_content(options, now))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok((path, format!("Captured to vault: {}", relative)))
 }
 
 /// Parse task ID from TaskWarrior output
@@ -156,9 +346,21 @@ fn parse_task_id(output: &str) -> Option<u32> {
 /// Run from CLI arguments
 pub fn run_from_args(args: &Args) {
     let options = InboxOptions::from(args);
-    if let Err(e) = run_with_options(&options) {
-        eprintln!("{} {}", "Error:".red(), e);
-        std::process::exit(1);
+    match run_with_options(&options) {
+        Ok(result) => {
+            let prefix = if options.dry_run {
+                "[dry-run]".blue()
+            } else {
+                "✓".green()
+            };
+            for message in result.messages {
+                println!("{} {}", prefix, message);
+            }
+        }
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red(), e);
+            std::process::exit(1);
+        }
     }
 }
 
@@ -260,6 +462,141 @@ mod tests {
         assert!(!args.is_empty());
     }
 
+    fn at(time: &str) -> DateTime<Local> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Local)
+    }
+
+    #[test]
+    fn test_capture_file_name() {
+        let now = at("2026-03-01T09:05:07+00:00");
+        let stamp = now.format("%Y-%m-%d-%H%M%S").to_string();
+        assert_eq!(
+            capture_file_name("Read: the Rust book!", now),
+            format!("capture-{}-read-the-rust-book.md", stamp)
+        );
+        assert_eq!(
+            capture_file_name("???", now),
+            format!("capture-{}.md", stamp)
+        );
+
+        let long = capture_file_name(&"word ".repeat(40), now);
+        let slug = long
+            .trim_start_matches(&format!("capture-{}-", stamp))
+            .trim_end_matches(".md");
+        assert!(slug.len() <= MAX_SLUG_LEN);
+        assert!(slug.ends_with("word"));
+    }
+
+    #[test]
+    fn test_capture_content_front_matter() {
+        let options = InboxOptions {
+            text: "  https://example.com/article  ".to_string(),
+            tags: vec!["+read".to_string(), "link".to_string()],
+            project: Some("research".to_string()),
+            source: Some("mcp".to_string()),
+            ..Default::default()
+        };
+        let content = capture_content(&options, at("2026-03-01T09:05:07+00:00"));
+
+        assert_eq!(
+            crate::markdown::field(&content, "tags").as_deref(),
+            Some("[read, link]")
+        );
+        assert_eq!(
+            crate::markdown::field(&content, "project").as_deref(),
+            Some("research")
+        );
+        assert_eq!(
+            crate::markdown::field(&content, "source").as_deref(),
+            Some("mcp")
+        );
+        assert_eq!(
+            crate::markdown::strip_front_matter(&content),
+            "\nhttps://example.com/article\n"
+        );
+    }
+
+    #[test]
+    fn test_vault_backend_writes_capture() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let options = InboxOptions {
+            text: "Idea for the garden".to_string(),
+            backend: CaptureBackend::Vault,
+            vault: Some(temp.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        let first = run_with_options(&options).unwrap();
+        let second = run_with_options(&options).unwrap();
+        assert_eq!(first.messages.len(), 1);
+        assert!(first.messages[0].starts_with("Captured to vault: 00-inbox/capture-"));
+        let (first, second) = (first.capture_path.unwrap(), second.capture_path.unwrap());
+        assert_ne!(first, second, "same-second captures must not collide");
+        assert_eq!(first.parent().unwrap(), temp.path().join(VAULT_INBOX));
+        assert!(fs::read_to_string(&first)
+            .unwrap()
+            .ends_with("Idea for the garden\n"));
+
+        let dry_run = InboxOptions {
+            dry_run: true,
+            ..options
+        };
+        let result = run_with_options(&dry_run).unwrap();
+        assert!(result.messages[0].starts_with("Would write 00-inbox/capture-"));
+        assert!(!result.capture_path.unwrap().exists());
+        assert!(result.command.is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_both_backend_removes_capture_when_task_fails() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let vault = temp.path().join("vault");
+        fs::create_dir(&vault).unwrap();
+
+        // A `task` that is installed but can't add (e.g. locked data)
+        let bin = temp.path().join("bin");
+        fs::create_dir(&bin).unwrap();
+        let task = bin.join("task");
+        fs::write(&task, "#!/bin/sh\necho 'database is locked' >&2\nexit 1\n").unwrap();
+        fs::set_permissions(&task, fs::Permissions::from_mode(0o755)).unwrap();
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        let mut dirs = vec![bin];
+        dirs.extend(std::env::split_paths(&path));
+        std::env::set_var("PATH", std::env::join_paths(dirs).unwrap());
+
+        let options = InboxOptions {
+            text: "Call the plumber".to_string(),
+            backend: CaptureBackend::Both,
+            vault: Some(vault.clone()),
+            ..Default::default()
+        };
+        let result = run_with_options(&options);
+        std::env::set_var("PATH", path);
+
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("database is locked"), "{}", err);
+        let left: Vec<_> = fs::read_dir(vault.join(VAULT_INBOX)).unwrap().collect();
+        assert!(left.is_empty(), "capture left behind: {:?}", left);
+    }
+
+    #[test]
+    fn test_vault_backend_needs_existing_vault() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let options = InboxOptions {
+            text: "Lost".to_string(),
+            backend: CaptureBackend::Vault,
+            vault: Some(temp.path().join("missing")),
+            ..Default::default()
+        };
+        let err = run_with_options(&options).unwrap_err();
+        assert!(err.to_string().contains("Vault not found"));
+    }
+
     // Integration test - only runs if TaskWarrior is installed
     #[test]
     #[ignore] // Run with: cargo test -- --ignored
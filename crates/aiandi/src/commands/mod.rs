@@ -1,4 +1,12 @@
+pub mod adr;
 pub mod doctor;
+pub mod governance;
+pub mod handoff;
 pub mod inbox;
 pub mod init;
+pub mod plan;
+pub mod route;
 pub mod serve;
+pub mod session;
+pub mod transmission;
+pub mod vault;
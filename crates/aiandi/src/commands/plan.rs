@@ -0,0 +1,461 @@
+//! `aiandi plan` command implementation.
+//!
+//! Time-aware engagement orientation from the GTD mapping: the time of day
+//! picks a phase, and the phase picks which TaskWarrior slices to show.
+//! Read-only, so it can run any number of times a day.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
+use colored::Colorize;
+use serde::Deserialize;
+
+use crate::commands::inbox::check_taskwarrior;
+
+/// Next actions offered as tomorrow's seeds besides what is due
+const SEED_COUNT: usize = 3;
+
+/// Heading for next actions without an `@context` tag
+const NO_CONTEXT: &str = "(no context)";
+
+/// CLI arguments for the plan command
+#[derive(Debug, Clone, clap::Args)]
+pub struct Args {
+    /// Phase to plan for (default: from the current time)
+    #[arg(long, value_enum)]
+    pub phase: Option<Phase>,
+}
+
+/// Part of the day, per gtd-mapping.md
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Phase {
+    /// 05:00-11:59, full day planning
+    Morning,
+    /// 12:00-16:59, remaining runway regroup
+    Afternoon,
+    /// 17:00-20:59, close and seed tomorrow
+    Evening,
+    /// 21:00-04:59, tomorrow focus
+    Night,
+}
+
+impl Phase {
+    /// The phase containing `hour` (0-23)
+    pub fn at_hour(hour: u32) -> Self {
+        match hour {
+            5..=11 => Self::Morning,
+            12..=16 => Self::Afternoon,
+            17..=20 => Self::Evening,
+            _ => Self::Night,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Morning => "Morning",
+            Self::Afternoon => "Afternoon",
+            Self::Evening => "Evening",
+            Self::Night => "Night",
+        }
+    }
+
+    fn focus(self) -> &'static str {
+        match self {
+            Self::Morning => "Full day planning",
+            Self::Afternoon => "Remaining runway regroup",
+            Self::Evening => "Close + tomorrow seed",
+            Self::Night => "Tomorrow focus",
+        }
+    }
+}
+
+/// A pending task from `task export`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Task {
+    #[serde(default)]
+    pub id: u32,
+    pub uuid: String,
+    pub description: String,
+    pub project: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// TaskWarrior timestamp, e.g. `20261019T150000Z`
+    pub due: Option<String>,
+    #[serde(default)]
+    pub urgency: f64,
+    /// UUID list (TaskWarrior 3) or comma-separated string (2.x)
+    pub depends: Option<serde_json::Value>,
+}
+
+impl Task {
+    fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    fn due(&self) -> Option<DateTime<Local>> {
+        let due = NaiveDateTime::parse_from_str(self.due.as_deref()?, "%Y%m%dT%H%M%SZ").ok()?;
+        Some(Utc.from_utc_datetime(&due).with_timezone(&Local))
+    }
+
+    fn depends(&self) -> Vec<&str> {
+        match &self.depends {
+            Some(serde_json::Value::Array(uuids)) => {
+                uuids.iter().filter_map(|u| u.as_str()).collect()
+            }
+            Some(serde_json::Value::String(uuids)) => uuids.split(',').collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn line(&self, now: DateTime<Local>) -> String {
+        let mut line = format!("- [{}] {}", self.id, self.description);
+        if let Some(project) = &self.project {
+            let _ = write!(line, " (project: {})", project);
+        }
+        if let Some(due) = self.due() {
+            if due < now {
+                let _ = write!(line, " — overdue since {}", due.format("%Y-%m-%d %H:%M"));
+            } else if due.date_naive() == now.date_naive() {
+                let _ = write!(line, " — due {}", due.format("%H:%M"));
+            } else {
+                let _ = write!(line, " — due {}", due.format("%Y-%m-%d %H:%M"));
+            }
+        }
+        line
+    }
+}
+
+/// Tasks from `task export` JSON
+pub fn parse_tasks(json: &str) -> Result<Vec<Task>> {
+    serde_json::from_str(json).context("Unexpected TaskWarrior export output")
+}
+
+/// The TaskWarrior views a plan is built from
+#[derive(Debug, Default)]
+pub struct Slices<'a> {
+    /// Overdue or due before midnight, earliest first
+    pub due_today: Vec<&'a Task>,
+    /// Due tomorrow, earliest first
+    pub due_tomorrow: Vec<&'a Task>,
+    /// Unblocked `+next` tasks not due today, by `@context`, most urgent first
+    pub next_by_context: BTreeMap<String, Vec<&'a Task>>,
+    /// Unprocessed `+inbox` items
+    pub inbox: usize,
+}
+
+impl<'a> Slices<'a> {
+    pub fn new(tasks: &'a [Task], now: DateTime<Local>) -> Self {
+        let today = now.date_naive();
+        let tomorrow = today + Duration::days(1);
+        let pending: HashSet<&str> = tasks.iter().map(|t| t.uuid.as_str()).collect();
+
+        let mut by_urgency: Vec<&Task> = tasks.iter().collect();
+        by_urgency.sort_by(|a, b| b.urgency.total_cmp(&a.urgency));
+
+        let mut slices = Slices::default();
+        for task in by_urgency {
+            let due_day = task.due().map(|d| d.date_naive());
+            if task.has_tag("inbox") {
+                slices.inbox += 1;
+            }
+            if due_day.is_some_and(|d| d <= today) {
+                slices.due_today.push(task);
+                continue;
+            }
+            if due_day == Some(tomorrow) {
+                slices.due_tomorrow.push(task);
+            }
+            let blocked = task.depends().iter().any(|u| pending.contains(u));
+            if task.has_tag("next") && !blocked {
+                let contexts: Vec<&String> =
+                    task.tags.iter().filter(|t| t.starts_with('@')).collect();
+                if contexts.is_empty() {
+                    slices
+                        .next_by_context
+                        .entry(NO_CONTEXT.to_string())
+                        .or_default()
+                        .push(task);
+                }
+                for context in contexts {
+                    slices
+                        .next_by_context
+                        .entry(context.clone())
+                        .or_default()
+                        .push(task);
+                }
+            }
+        }
+        slices.due_today.sort_by_key(|t| t.due());
+        slices.due_tomorrow.sort_by_key(|t| t.due());
+        slices
+    }
+
+    /// Due tomorrow, then the most urgent next actions not already listed
+    pub fn seeds(&self) -> Vec<&'a Task> {
+        let mut seeds = self.due_tomorrow.clone();
+        let mut next: Vec<&Task> = self.next_by_context.values().flatten().copied().collect();
+        next.sort_by(|a, b| b.urgency.total_cmp(&a.urgency));
+        for task in next {
+            if seeds.len() >= self.due_tomorrow.len() + SEED_COUNT {
+                break;
+            }
+            if !seeds.iter().any(|s| s.uuid == task.uuid) {
+                seeds.push(task);
+            }
+        }
+        seeds
+    }
+}
+
+fn section(doc: &mut String, heading: &str, tasks: &[&Task], empty: &str, now: DateTime<Local>) {
+    let _ = writeln!(doc, "\n## {}\n", heading);
+    if tasks.is_empty() {
+        let _ = writeln!(doc, "_{}_", empty);
+    }
+    for task in tasks {
+        let _ = writeln!(doc, "{}", task.line(now));
+    }
+}
+
+fn next_actions(doc: &mut String, slices: &Slices, now: DateTime<Local>) {
+    let _ = writeln!(doc, "\n## Next actions by context");
+    if slices.next_by_context.is_empty() {
+        let _ = writeln!(
+            doc,
+            "\n_No +next actions. Clarify the inbox or review projects._"
+        );
+    }
+    for (context, tasks) in &slices.next_by_context {
+        let _ = writeln!(doc, "\n### {}\n", context);
+        for task in tasks {
+            let _ = writeln!(doc, "{}", task.line(now));
+        }
+    }
+}
+
+fn inbox(doc: &mut String, slices: &Slices) {
+    let _ = writeln!(doc, "\n## Inbox\n");
+    if slices.inbox == 0 {
+        let _ = writeln!(doc, "_Inbox zero._");
+    } else {
+        let _ = writeln!(
+            doc,
+            "{} item(s) to process: `aiandi route`, then clarify the rest.",
+            slices.inbox
+        );
+    }
+}
+
+/// The orientation document for `phase` at `now`
+pub fn render(phase: Phase, tasks: &[Task], now: DateTime<Local>) -> String {
+    let slices = Slices::new(tasks, now);
+    let tomorrow: NaiveDate = now.date_naive() + Duration::days(1);
+
+    let mut doc = format!(
+        "# Plan: {} — {}\n\n**Focus:** {}\n",
+        phase.name(),
+        now.format("%Y-%m-%d %H:%M"),
+        phase.focus()
+    );
+    match phase {
+        Phase::Morning => {
+            section(
+                &mut doc,
+                "Due today",
+                &slices.due_today,
+                "Nothing due today.",
+                now,
+            );
+            next_actions(&mut doc, &slices, now);
+            inbox(&mut doc, &slices);
+        }
+        Phase::Afternoon => {
+            section(
+                &mut doc,
+                "Still due today",
+                &slices.due_today,
+                "Nothing left due today.",
+                now,
+            );
+            next_actions(&mut doc, &slices, now);
+        }
+        Phase::Evening => {
+            section(
+                &mut doc,
+                "Close out: still open today",
+                &slices.due_today,
+                "Today is closed.",
+                now,
+            );
+            section(
+                &mut doc,
+                &format!("Tomorrow's seeds ({})", tomorrow.format("%a %Y-%m-%d")),
+                &slices.seeds(),
+                "Nothing seeded yet.",
+                now,
+            );
+            inbox(&mut doc, &slices);
+        }
+        Phase::Night => {
+            // After midnight "tomorrow" is the day that just started
+            let (label, focus) = if now.hour() < 5 {
+                (now.date_naive(), slices.due_today.clone())
+            } else {
+                (tomorrow, slices.seeds())
+            };
+            section(
+                &mut doc,
+                &format!("Tomorrow's focus ({})", label.format("%a %Y-%m-%d")),
+                &focus,
+                "Nothing scheduled. Rest.",
+                now,
+            );
+        }
+    }
+    doc
+}
+
+/// Pending tasks from TaskWarrior
+fn pending_tasks() -> Result<Vec<Task>> {
+    check_taskwarrior()?;
+    let output = Command::new("task")
+        .args(["rc.verbose=nothing", "status:pending", "export"])
+        .output()
+        .context("Failed to execute TaskWarrior")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("TaskWarrior failed: {}", stderr.trim());
+    }
+    parse_tasks(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Build the plan for `phase` (default: the current one)
+pub fn run(phase: Option<Phase>) -> Result<String> {
+    let now = Local::now();
+    let phase = phase.unwrap_or_else(|| Phase::at_hour(now.hour()));
+    Ok(render(phase, &pending_tasks()?, now))
+}
+
+/// Run from CLI arguments
+pub fn run_from_args(args: &Args) {
+    match run(args.phase) {
+        Ok(doc) => print!("{}", doc),
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Local> {
+        Local
+            .from_local_datetime(&NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap())
+            .unwrap()
+    }
+
+    fn tw(time: DateTime<Local>) -> String {
+        time.with_timezone(&Utc)
+            .format("%Y%m%dT%H%M%SZ")
+            .to_string()
+    }
+
+    fn tasks(now: DateTime<Local>) -> Vec<Task> {
+        let json = format!(
+            r#"[
+            {{"id":1,"uuid":"a","description":"Pay rent","due":"{overdue}","urgency":9.0}},
+            {{"id":2,"uuid":"b","description":"Send report","project":"work","due":"{today}","tags":["next","@computer"],"urgency":12.0}},
+            {{"id":3,"uuid":"c","description":"Call dentist","tags":["next","@phone"],"urgency":8.0}},
+            {{"id":4,"uuid":"d","description":"Water plants","tags":["next"],"urgency":3.0}},
+            {{"id":5,"uuid":"e","description":"Book flights","due":"{tomorrow}","urgency":6.0}},
+            {{"id":6,"uuid":"f","description":"Pack bags","tags":["next","@home"],"depends":["e"],"urgency":7.0}},
+            {{"id":7,"uuid":"g","description":"Idea","tags":["inbox"],"urgency":15.0}}
+        ]"#,
+            overdue = tw(now - Duration::days(2)),
+            today = tw(now + Duration::hours(2)),
+            tomorrow = tw(now + Duration::days(1)),
+        );
+        parse_tasks(&json).unwrap()
+    }
+
+    #[test]
+    fn test_phase_boundaries() {
+        assert_eq!(Phase::at_hour(4), Phase::Night);
+        assert_eq!(Phase::at_hour(5), Phase::Morning);
+        assert_eq!(Phase::at_hour(11), Phase::Morning);
+        assert_eq!(Phase::at_hour(12), Phase::Afternoon);
+        assert_eq!(Phase::at_hour(17), Phase::Evening);
+        assert_eq!(Phase::at_hour(21), Phase::Night);
+        assert_eq!(Phase::at_hour(0), Phase::Night);
+    }
+
+    #[test]
+    fn test_slices() {
+        let now = at("2026-10-19 09:00");
+        let tasks = tasks(now);
+        let slices = Slices::new(&tasks, now);
+
+        let ids = |ts: &[&Task]| ts.iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids(&slices.due_today), vec![1, 2]);
+        assert_eq!(ids(&slices.due_tomorrow), vec![5]);
+        assert_eq!(slices.inbox, 1);
+        // Due-today and blocked tasks aren't offered as next actions
+        assert_eq!(
+            slices.next_by_context.keys().collect::<Vec<_>>(),
+            vec!["(no context)", "@phone"]
+        );
+        assert_eq!(ids(&slices.seeds()), vec![5, 3, 4]);
+    }
+
+    #[test]
+    fn test_render_by_phase() {
+        let now = at("2026-10-19 09:00");
+        let tasks = tasks(now);
+
+        let morning = render(Phase::Morning, &tasks, now);
+        assert!(morning.starts_with("# Plan: Morning — 2026-10-19 09:00"));
+        assert!(morning.contains("## Due today"));
+        assert!(morning.contains("- [2] Send report (project: work) — due 11:00"));
+        assert!(morning.contains("overdue since"));
+        assert!(morning.contains("### @phone\n\n- [3] Call dentist"));
+        assert!(morning.contains("1 item(s) to process"));
+
+        let evening = render(Phase::Evening, &tasks, now);
+        assert!(evening.contains("## Tomorrow's seeds (Tue 2026-10-20)"));
+        assert!(!evening.contains("Next actions by context"));
+
+        let night = render(Phase::Night, &tasks, now);
+        assert!(night.contains("## Tomorrow's focus"));
+        assert!(night.contains("- [5] Book flights"));
+
+        // Same input, same document
+        assert_eq!(
+            render(Phase::Afternoon, &tasks, now),
+            render(Phase::Afternoon, &tasks, now)
+        );
+    }
+
+    #[test]
+    fn test_render_empty() {
+        let now = at("2026-10-19 13:00");
+        let plan = render(Phase::Afternoon, &[], now);
+        assert!(plan.contains("_Nothing left due today._"));
+        assert!(plan.contains("_No +next actions."));
+    }
+
+    #[test]
+    fn test_depends_formats() {
+        let tasks = parse_tasks(
+            r#"[{"uuid":"a","description":"x","depends":"b,c"},{"uuid":"b","description":"y"}]"#,
+        )
+        .unwrap();
+        assert_eq!(tasks[0].depends(), vec!["b", "c"]);
+        assert!(tasks[1].depends().is_empty());
+    }
+}
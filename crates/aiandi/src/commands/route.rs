@@ -0,0 +1,673 @@
+//! `aiandi route` command implementation.
+//!
+//! Classifies inbox items (vault captures and TaskWarrior `+inbox` tasks)
+//! against the declarative rules in `_aiandi/protocols/routing-rules.toml`,
+//! applies the auto-routes, and leaves everything else for clarification.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::commands::inbox::{self, CaptureBackend, VAULT_INBOX};
+use crate::markdown;
+use crate::vault_template::ROUTING_RULES;
+
+/// Rules file inside a vault
+pub const RULES_FILE: &str = "_aiandi/protocols/routing-rules.toml";
+
+/// Routed captures are archived here rather than deleted
+pub const SOURCES_DIR: &str = "archives/sources";
+
+static URL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"https?://[^\s<>]+").unwrap());
+
+static RECOMMENDER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:[Rr]ecommended|[Ss]uggested|[Rr]ec'?d?) by\s+([A-Z][\w'-]*(?:\s+[A-Z][\w'-]*)?)",
+    )
+    .unwrap()
+});
+
+/// CLI arguments for the route command
+#[derive(Debug, Clone, clap::Args)]
+pub struct Args {
+    /// Vault to route into (defaults to the one in the aiandi config)
+    #[arg(long)]
+    pub vault: Option<PathBuf>,
+
+    /// Rules file (defaults to the vault's, then the built-in rules)
+    #[arg(long)]
+    pub rules: Option<PathBuf>,
+
+    /// Which inbox to route
+    #[arg(long, value_enum, default_value_t = CaptureBackend::Both)]
+    pub from: CaptureBackend,
+
+    /// Print the routing plan without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Options for the route command (for testing)
+#[derive(Debug, Clone, Default)]
+pub struct RouteOptions {
+    /// Vault root (None = the configured vault)
+    pub vault: Option<PathBuf>,
+    /// Rules file (None = the vault's, then the built-in rules)
+    pub rules: Option<PathBuf>,
+    pub from: CaptureBackend,
+    pub dry_run: bool,
+}
+
+impl From<&Args> for RouteOptions {
+    fn from(args: &Args) -> Self {
+        Self {
+            vault: args.vault.clone(),
+            rules: args.rules.clone(),
+            from: args.from,
+            dry_run: args.dry_run,
+        }
+    }
+}
+
+/// What a matching rule does with an item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    /// Add a rendered line to a vault file
+    Append,
+    /// Discard the item
+    Delete,
+    /// Keep the item in the inbox
+    Leave,
+}
+
+/// One `[[rule]]` from the rules file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub name: String,
+    /// true = must contain a link, false = must not
+    pub url: Option<bool>,
+    pub min_words: Option<usize>,
+    pub max_words: Option<usize>,
+    /// Any of these words or phrases, case-insensitive
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Regular expression matched against the text
+    pub pattern: Option<String>,
+    pub action: Action,
+    /// File to append to, relative to the vault
+    pub target: Option<String>,
+    /// Line to append; see `render`
+    pub template: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default)]
+    rule: Vec<Rule>,
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    rule: Rule,
+    keywords: Option<Regex>,
+    pattern: Option<Regex>,
+}
+
+/// Ordered routing rules; the first match wins
+#[derive(Debug)]
+pub struct Rules {
+    rules: Vec<CompiledRule>,
+}
+
+impl Rules {
+    pub fn parse(text: &str) -> Result<Self> {
+        let file: RulesFile = toml::from_str(text).context("Invalid routing rules")?;
+        let mut rules = Vec::new();
+        for rule in file.rule {
+            if rule.action == Action::Append && (rule.target.is_none() || rule.template.is_none()) {
+                bail!("Rule '{}' appends but has no target or template", rule.name);
+            }
+            let keywords = if rule.keywords.is_empty() {
+                None
+            } else {
+                let words: Vec<String> = rule.keywords.iter().map(|k| regex::escape(k)).collect();
+                Some(Regex::new(&format!(r"(?i)\b(?:{})\b", words.join("|")))?)
+            };
+            let pattern = rule
+                .pattern
+                .as_deref()
+                .map(Regex::new)
+                .transpose()
+                .with_context(|| format!("Rule '{}' has an invalid pattern", rule.name))?;
+            rules.push(CompiledRule {
+                rule,
+                keywords,
+                pattern,
+            });
+        }
+        Ok(Self { rules })
+    }
+
+    /// The first rule `text` matches, if any
+    pub fn classify(&self, text: &str) -> Option<&Rule> {
+        let has_url = URL.is_match(text);
+        let words = URL.replace_all(text, " ").split_whitespace().count();
+
+        self.rules
+            .iter()
+            .find(|c| {
+                let rule = &c.rule;
+                rule.url.is_none_or(|want| want == has_url)
+                    && rule.min_words.is_none_or(|min| words >= min)
+                    && rule.max_words.is_none_or(|max| words <= max)
+                    && c.keywords.as_ref().is_none_or(|re| re.is_match(text))
+                    && c.pattern.as_ref().is_none_or(|re| re.is_match(text))
+            })
+            .map(|c| &c.rule)
+    }
+}
+
+/// `--rules`, else the vault's rules file, else the built-in rules;
+/// returns the rules and where they came from
+fn load_rules(explicit: Option<&Path>, vault: &Path) -> Result<(Rules, String)> {
+    let path = explicit
+        .map(Path::to_path_buf)
+        .or_else(|| Some(vault.join(RULES_FILE)).filter(|p| p.exists()));
+    match path {
+        Some(path) => {
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let rules = Rules::parse(&text).with_context(|| format!("In {}", path.display()))?;
+            Ok((rules, path.display().to_string()))
+        }
+        None => Ok((Rules::parse(ROUTING_RULES)?, "built-in".to_string())),
+    }
+}
+
+/// Fill in a rule template:
+/// - `{text}`: the whole item on one line
+/// - `{url}`: its first link
+/// - `{title}`: the text without links or recommender, else the link
+/// - `{recommender}`: `[[Person]]` from "recommended by Person"
+pub fn render(template: &str, text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let url = URL.find(&text).map(|m| m.as_str()).unwrap_or("");
+    let recommender = RECOMMENDER
+        .captures(&text)
+        .map(|c| format!("[[{}]]", &c[1]))
+        .unwrap_or_default();
+
+    let stripped = RECOMMENDER.replace_all(&text, "");
+    let stripped = URL.replace_all(&stripped, "");
+    let mut title = stripped
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| c.is_whitespace() || "-–—:,;(".contains(c))
+        .to_string();
+    if title.is_empty() {
+        title = url
+            .split_once("://")
+            .map_or(url, |(_, rest)| rest)
+            .trim_start_matches("www.")
+            .trim_end_matches('/')
+            .to_string();
+    }
+
+    template
+        .replace("{text}", &text)
+        .replace("{url}", url)
+        .replace("{title}", &title)
+        .replace("{recommender}", &recommender)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Where an inbox item lives
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemSource {
+    /// `00-inbox/capture-*.md` in the vault
+    Capture(PathBuf),
+    /// Pending TaskWarrior task tagged +inbox
+    Task { id: u32, uuid: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct InboxItem {
+    pub source: ItemSource,
+    pub text: String,
+}
+
+impl InboxItem {
+    pub fn label(&self) -> String {
+        match &self.source {
+            ItemSource::Capture(path) => path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            ItemSource::Task { id, .. } => {
+                let mut text: String = self.text.chars().take(40).collect();
+                if text.len() < self.text.len() {
+                    text.push('…');
+                }
+                format!("task {} ({})", id, text)
+            }
+        }
+    }
+}
+
+/// Capture files in the vault inbox, oldest first
+pub fn vault_items(vault: &Path) -> Result<Vec<InboxItem>> {
+    let inbox = vault.join(VAULT_INBOX);
+    if !inbox.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<PathBuf> = fs::read_dir(&inbox)
+        .with_context(|| format!("Failed to read {}", inbox.display()))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.is_file()
+                && p.file_name().is_some_and(|n| {
+                    let n = n.to_string_lossy();
+                    n.starts_with("capture-") && n.ends_with(".md")
+                })
+        })
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            Ok(InboxItem {
+                text: markdown::strip_front_matter(&content).trim().to_string(),
+                source: ItemSource::Capture(path),
+            })
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct ExportedTask {
+    id: u32,
+    uuid: String,
+    description: String,
+}
+
+/// Items from `task export` JSON
+pub fn parse_export(json: &str) -> Result<Vec<InboxItem>> {
+    let tasks: Vec<ExportedTask> =
+        serde_json::from_str(json).context("Unexpected TaskWarrior export output")?;
+    Ok(tasks
+        .into_iter()
+        .map(|t| InboxItem {
+            source: ItemSource::Task {
+                id: t.id,
+                uuid: t.uuid,
+            },
+            text: t.description,
+        })
+        .collect())
+}
+
+/// Run TaskWarrior non-interactively
+fn task(args: &[&str]) -> Result<String> {
+    let output = Command::new("task")
+        .args(["rc.confirmation=off", "rc.verbose=nothing"])
+        .args(args)
+        .output()
+        .context("Failed to execute TaskWarrior")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("TaskWarrior failed: {}", stderr.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn taskwarrior_items() -> Result<Vec<InboxItem>> {
+    parse_export(&task(&["+inbox", "status:pending", "export"])?)
+}
+
+fn append_line(path: &Path, line: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let needs_newline = fs::read_to_string(path).is_ok_and(|c| !c.is_empty() && !c.ends_with('\n'));
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    if needs_newline {
+        writeln!(file)?;
+    }
+    writeln!(file, "{}", line).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Take a routed or deleted item out of its inbox
+fn retire(vault: &Path, item: &InboxItem, delete: bool) -> Result<()> {
+    match &item.source {
+        ItemSource::Capture(path) if delete => {
+            fs::remove_file(path).with_context(|| format!("Failed to delete {}", path.display()))
+        }
+        ItemSource::Capture(path) => {
+            let sources = vault.join(SOURCES_DIR);
+            fs::create_dir_all(&sources)
+                .with_context(|| format!("Failed to create {}", sources.display()))?;
+            let dest = sources.join(path.file_name().unwrap_or_default());
+            fs::rename(path, &dest).with_context(|| format!("Failed to move {}", path.display()))
+        }
+        ItemSource::Task { uuid, .. } => {
+            task(&[uuid, if delete { "delete" } else { "done" }]).map(|_| ())
+        }
+    }
+}
+
+/// Outcome of a routing run
+#[derive(Debug, Default)]
+pub struct RouteResult {
+    /// Items appended to a vault file
+    pub routed: usize,
+    /// Items discarded
+    pub deleted: usize,
+    /// Items left for clarification
+    pub left: usize,
+}
+
+/// Route every inbox item the rules cover
+pub fn run_with_options(options: &RouteOptions) -> Result<RouteResult> {
+    let vault = inbox::resolve_vault(options.vault.as_deref())?;
+    let (rules, rules_source) = load_rules(options.rules.as_deref(), &vault)?;
+
+    let mut items = Vec::new();
+    if matches!(options.from, CaptureBackend::Vault | CaptureBackend::Both) {
+        items.extend(vault_items(&vault)?);
+    }
+    match options.from {
+        CaptureBackend::Taskwarrior => {
+            inbox::check_taskwarrior()?;
+            items.extend(taskwarrior_items()?);
+        }
+        CaptureBackend::Both if inbox::check_taskwarrior().is_ok() => {
+            items.extend(taskwarrior_items()?);
+        }
+        CaptureBackend::Both => {
+            println!(
+                "{} TaskWarrior not found, routing vault captures only",
+                "→".yellow()
+            );
+        }
+        CaptureBackend::Vault => {}
+    }
+
+    println!(
+        "Routing {} inbox item(s) with {} rules\n",
+        items.len(),
+        rules_source
+    );
+
+    let mut result = RouteResult::default();
+    for item in &items {
+        let label = item.label();
+        let Some(rule) = rules.classify(&item.text) else {
+            println!("{} {} left for clarification", "→".yellow(), label);
+            result.left += 1;
+            continue;
+        };
+
+        match rule.action {
+            Action::Leave => {
+                println!(
+                    "{} {} left for clarification ({})",
+                    "→".yellow(),
+                    label,
+                    rule.name
+                );
+                result.left += 1;
+            }
+            Action::Delete => {
+                if options.dry_run {
+                    println!(
+                        "{} {} would be deleted ({})",
+                        "[dry-run]".blue(),
+                        label,
+                        rule.name
+                    );
+                } else {
+                    retire(&vault, item, true)?;
+                    println!("{} {} deleted ({})", "✓".green(), label, rule.name);
+                }
+                result.deleted += 1;
+            }
+            Action::Append => {
+                // Both are checked when the rules are parsed
+                let target = rule.target.as_deref().unwrap_or_default();
+                let line = render(rule.template.as_deref().unwrap_or_default(), &item.text);
+                if options.dry_run {
+                    println!(
+                        "{} {} → {} ({})\n          {}",
+                        "[dry-run]".blue(),
+                        label,
+                        target,
+                        rule.name,
+                        line
+                    );
+                } else {
+                    append_line(&vault.join(target), &line)?;
+                    retire(&vault, item, false)?;
+                    println!("{} {} → {} ({})", "✓".green(), label, target, rule.name);
+                }
+                result.routed += 1;
+            }
+        }
+    }
+
+    println!();
+    if options.dry_run {
+        println!("Dry run complete. Nothing changed.");
+    }
+    println!(
+        "{} routed, {} deleted, {} left for clarification.",
+        result.routed, result.deleted, result.left
+    );
+    Ok(result)
+}
+
+/// Run from CLI arguments
+pub fn run_from_args(args: &Args) {
+    if let Err(e) = run_with_options(&RouteOptions::from(args)) {
+        eprintln!("{} {}", "Error:".red(), e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn default_rules() -> Rules {
+        Rules::parse(ROUTING_RULES).unwrap()
+    }
+
+    fn rule_name<'a>(rules: &'a Rules, text: &str) -> Option<&'a str> {
+        rules.classify(text).map(|r| r.name.as_str())
+    }
+
+    #[test]
+    fn test_default_rules_classify() {
+        let rules = default_rules();
+        assert_eq!(rule_name(&rules, "hmm"), Some("fragment"));
+        assert_eq!(rule_name(&rules, ""), Some("fragment"));
+        assert_eq!(
+            rule_name(&rules, "https://example.com/post"),
+            Some("bare-link")
+        );
+        assert_eq!(
+            rule_name(&rules, "cool crate https://crates.io/crates/x"),
+            Some("bare-link")
+        );
+        assert_eq!(
+            rule_name(&rules, "The Expanse TV show, recommended by Sam"),
+            Some("media")
+        );
+        assert_eq!(
+            rule_name(&rules, "Great article https://example.com/long-read"),
+            Some("media-link")
+        );
+        assert_eq!(rule_name(&rules, "Call the plumber about the leak"), None);
+        // A media word alone is not a recommendation
+        assert_eq!(rule_name(&rules, "Book flights to Berlin"), None);
+        // A link with a long description needs a human
+        assert_eq!(
+            rule_name(
+                &rules,
+                "compare this approach with what we did for the sync engine last year https://x.io"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rules_conditions_and_order() {
+        let rules = Rules::parse(
+            r#"
+            [[rule]]
+            name = "keep-urgent"
+            pattern = "^high:"
+            action = "leave"
+
+            [[rule]]
+            name = "errand"
+            keywords = ["buy", "pick up"]
+            min_words = 2
+            action = "append"
+            target = "02-areas/Errands.md"
+            template = "- [ ] {text}"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(rule_name(&rules, "high: buy milk"), Some("keep-urgent"));
+        assert_eq!(rule_name(&rules, "Pick up dry cleaning"), Some("errand"));
+        assert_eq!(rule_name(&rules, "buy"), None);
+        assert_eq!(rule_name(&rules, "buyers guide"), None);
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        let missing_target = "[[rule]]\nname = \"x\"\naction = \"append\"\n";
+        assert!(Rules::parse(missing_target).is_err());
+        let bad_pattern = "[[rule]]\nname = \"x\"\npattern = \"(\"\naction = \"leave\"\n";
+        assert!(Rules::parse(bad_pattern).is_err());
+        let typo = "[[rule]]\nname = \"x\"\nmax_word = 1\naction = \"leave\"\n";
+        assert!(Rules::parse(typo).is_err());
+    }
+
+    #[test]
+    fn test_render_templates() {
+        let link = "- [ ] Review: {title} - <{url}> #someday";
+        assert_eq!(
+            render(link, "https://www.example.com/post/"),
+            "- [ ] Review: example.com/post - <https://www.example.com/post/> #someday"
+        );
+        assert_eq!(
+            render(
+                link,
+                "Rust async book -\nhttps://rust-lang.github.io/async-book/"
+            ),
+            "- [ ] Review: Rust async book - <https://rust-lang.github.io/async-book/> #someday"
+        );
+        assert_eq!(
+            render(
+                "- [ ] {title} {url} {recommender}",
+                "Dune (book) recommended by Ana Lopez"
+            ),
+            "- [ ] Dune (book) [[Ana Lopez]]"
+        );
+    }
+
+    #[test]
+    fn test_parse_export() {
+        let json = r#"[{"id":3,"uuid":"ab-12","description":"Buy milk","status":"pending","tags":["inbox"]}]"#;
+        let items = parse_export(json).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].text, "Buy milk");
+        assert_eq!(items[0].label(), "task 3 (Buy milk)");
+        assert!(parse_export("not json").is_err());
+    }
+
+    fn capture(vault: &Path, name: &str, text: &str) -> PathBuf {
+        let path = vault.join(VAULT_INBOX).join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, format!("---\nsource: cli\n---\n\n{}\n", text)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_route_vault_captures() {
+        let temp = TempDir::new().unwrap();
+        let vault = temp.path();
+        let link = capture(vault, "capture-1-link.md", "https://example.com/a");
+        let noise = capture(vault, "capture-2-x.md", "x");
+        let task = capture(vault, "capture-3-call.md", "Call Ana about the lease");
+        fs::write(vault.join(VAULT_INBOX).join("notes.md"), "x").unwrap();
+
+        let options = RouteOptions {
+            vault: Some(vault.to_path_buf()),
+            from: CaptureBackend::Vault,
+            dry_run: true,
+            ..Default::default()
+        };
+        let planned = run_with_options(&options).unwrap();
+        assert_eq!((planned.routed, planned.deleted, planned.left), (1, 1, 1));
+        assert!(link.exists() && noise.exists());
+
+        let applied = run_with_options(&RouteOptions {
+            dry_run: false,
+            ..options.clone()
+        })
+        .unwrap();
+        assert_eq!((applied.routed, applied.deleted, applied.left), (1, 1, 1));
+        assert!(!link.exists() && !noise.exists() && task.exists());
+        assert!(vault.join(SOURCES_DIR).join("capture-1-link.md").exists());
+        assert_eq!(
+            fs::read_to_string(vault.join("01-projects/Recursive-Loop.md")).unwrap(),
+            "- [ ] Review: example.com/a - <https://example.com/a> #someday\n"
+        );
+
+        // Nothing left to route on a second run
+        let again = run_with_options(&options).unwrap();
+        assert_eq!((again.routed, again.deleted, again.left), (0, 0, 1));
+    }
+
+    #[test]
+    fn test_vault_rules_file_overrides_built_in() {
+        let temp = TempDir::new().unwrap();
+        let vault = temp.path();
+        capture(vault, "capture-1-x.md", "x");
+        let rules = vault.join(RULES_FILE);
+        fs::create_dir_all(rules.parent().unwrap()).unwrap();
+        fs::write(&rules, "").unwrap();
+
+        let result = run_with_options(&RouteOptions {
+            vault: Some(vault.to_path_buf()),
+            from: CaptureBackend::Vault,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(result.left, 1);
+    }
+}
@@ -3,7 +3,10 @@
 //! MCP server for OpenCode integration.
 //! Exposes aiandi capabilities as MCP tools.
 
+use std::path::Path;
+
 use anyhow::Result;
+use clap::ValueEnum;
 use rmcp::{
     handler::server::{router::tool::ToolRouter, wrapper::Parameters},
     model::*,
@@ -11,8 +14,9 @@ use rmcp::{
 };
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
-use crate::commands::{doctor, inbox, init};
-use inbox::InboxOptions;
+use crate::commands::{doctor, governance, handoff, inbox, init, plan};
+use handoff::{HandoffPriority, HandoffType, WriteOptions};
+use inbox::{CaptureBackend, InboxOptions};
 use init::InitOptions;
 
 // ============================================================================
@@ -35,6 +39,16 @@ pub struct InboxRequest {
     tags: Option<String>,
     /// Project to assign
     project: Option<String>,
+    /// Where to capture: taskwarrior (default), vault, or both
+    backend: Option<String>,
+    /// Recorded as `source` in vault captures (default: mcp)
+    source: Option<String>,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct PlanRequest {
+    /// Phase: morning, afternoon, evening or night. Defaults to the current time of day.
+    phase: Option<String>,
 }
 
 #[derive(serde::Deserialize, schemars::JsonSchema)]
@@ -42,6 +56,59 @@ pub struct DoctorRequest {
     // Doctor takes no parameters currently
 }
 
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct ProposeRequest {
+    /// Proposal title
+    title: String,
+    /// Who is proposing (default: Governance Session)
+    author: Option<String>,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct DecideRequest {
+    /// Proposal file name in governance/evolution/proposals/, with or without .md
+    proposal: String,
+    /// ratified or rejected
+    outcome: String,
+    /// Why it was decided this way
+    reasoning: Option<String>,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct GovernanceStatusRequest {
+    // Lists every open proposal
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct HandoffWriteRequest {
+    /// Title of the item
+    title: String,
+    /// What the successor session needs to know
+    body: String,
+    /// Kind of item: inheritance, warning, context or links (default: context)
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    /// high, medium or low (default: medium)
+    priority: Option<String>,
+    /// Session this comes from (default: today's date)
+    from_session: Option<String>,
+    /// Why it matters to the successor
+    why: Option<String>,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct HandoffReadRequest {
+    // Reads every pending item
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct HandoffAckRequest {
+    /// Item file names to acknowledge (comma-separated)
+    items: Option<String>,
+    /// Acknowledge every pending item; required when no items are named
+    all: Option<bool>,
+}
+
 // ============================================================================
 // The aiandi MCP Server
 // ============================================================================
@@ -95,7 +162,7 @@ impl AiandiServer {
     }
 
     #[tool(
-        description = "Capture text to GTD inbox via TaskWarrior. Adds task with +inbox tag for later processing."
+        description = "Capture text to the GTD inbox. Backend taskwarrior (default) adds a task tagged +inbox; vault writes 00-inbox/capture-*.md in the configured vault with tags, project and source front matter; both does both."
     )]
     async fn aiandi_inbox(&self, Parameters(req): Parameters<InboxRequest>) -> String {
         // Parse tags
@@ -109,27 +176,62 @@ impl AiandiServer {
             })
             .unwrap_or_default();
 
+        let backend = match req
+            .backend
+            .as_deref()
+            .map(|b| CaptureBackend::from_str(b, true))
+        {
+            None => CaptureBackend::default(),
+            Some(Ok(backend)) => backend,
+            Some(Err(e)) => return format!("✗ Invalid backend: {}", e),
+        };
+
         // Build options
         let options = InboxOptions {
             text: req.text.clone(),
             tags: tags_vec,
             project: req.project.clone(),
+            backend,
+            source: Some(req.source.unwrap_or_else(|| "mcp".to_string())),
+            vault: None,
             dry_run: false,
         };
 
         // Run inbox capture
         match inbox::run_with_options(&options) {
             Ok(result) => {
+                let mut captured = Vec::new();
                 if let Some(task_id) = result.task_id {
-                    format!("✓ Captured to inbox: task {}", task_id)
+                    captured.push(format!("task {}", task_id));
+                }
+                if let Some(path) = result.capture_path {
+                    captured.push(path.display().to_string());
+                }
+                if captured.is_empty() {
+                    "✓ Captured to inbox".to_string()
                 } else {
-                    "✓ Captured to inbox (dry-run mode)".to_string()
+                    format!("✓ Captured to inbox: {}", captured.join(", "))
                 }
             }
             Err(e) => format!("✗ Capture failed: {}", e),
         }
     }
 
+    #[tool(
+        description = "Time-aware GTD orientation from TaskWarrior. Detects the phase (morning 05-12, afternoon 12-17, evening 17-21, night) or takes one, and returns a markdown plan: due today, next actions by context, tomorrow's seeds. Read-only; run it any time."
+    )]
+    async fn aiandi_plan(&self, Parameters(req): Parameters<PlanRequest>) -> String {
+        let phase = match req.phase.as_deref().map(|p| plan::Phase::from_str(p, true)) {
+            None => None,
+            Some(Ok(phase)) => Some(phase),
+            Some(Err(e)) => return format!("✗ Invalid phase: {}", e),
+        };
+        match plan::run(phase) {
+            Ok(doc) => doc,
+            Err(e) => format!("✗ Planning failed: {}", e),
+        }
+    }
+
     #[tool(
         description = "Run system health checks. Verifies OpenCode installation, TaskWarrior, config directories, and skills."
     )]
@@ -138,6 +240,126 @@ impl AiandiServer {
         doctor::run();
         "✓ Health checks complete. See output for details.".to_string()
     }
+
+    #[tool(
+        description = "Scaffold an evolution proposal in governance/evolution/proposals/ with status front matter."
+    )]
+    async fn aiandi_governance_propose(
+        &self,
+        Parameters(req): Parameters<ProposeRequest>,
+    ) -> String {
+        let author = req.author.as_deref().unwrap_or("Governance Session");
+        match governance::propose(Path::new("."), &req.title, author, false) {
+            Ok(path) => format!("✓ Created {}", path.display()),
+            Err(e) => format!("✗ Proposal failed: {}", e),
+        }
+    }
+
+    #[tool(
+        description = "Record a ratified or rejected decision on a proposal in governance/evolution/decisions/. Assigns the next DEC-NNN id, links back to the proposal and updates its status."
+    )]
+    async fn aiandi_governance_decide(&self, Parameters(req): Parameters<DecideRequest>) -> String {
+        let outcome = match governance::Outcome::from_str(&req.outcome, true) {
+            Ok(outcome) => outcome,
+            Err(e) => return format!("✗ Invalid outcome: {}", e),
+        };
+        match governance::decide(
+            Path::new("."),
+            &req.proposal,
+            outcome,
+            req.reasoning.as_deref(),
+        ) {
+            Ok((id, path)) => format!("✓ {} {} ({})", id, outcome.as_str(), path.display()),
+            Err(e) => format!("✗ Decision failed: {}", e),
+        }
+    }
+
+    #[tool(description = "List open governance proposals with their status and age in days.")]
+    async fn aiandi_governance_status(
+        &self,
+        _params: Parameters<GovernanceStatusRequest>,
+    ) -> String {
+        match governance::status_report(Path::new("."), chrono::Local::now().date_naive()) {
+            Ok(report) => report,
+            Err(e) => format!("✗ Status failed: {}", e),
+        }
+    }
+
+    #[tool(
+        description = "Leave a handoff item in inbox/ for the next session, with type, priority, from-session and created front matter."
+    )]
+    async fn aiandi_handoff_write(
+        &self,
+        Parameters(req): Parameters<HandoffWriteRequest>,
+    ) -> String {
+        let kind = match req.kind.as_deref().map(|k| HandoffType::from_str(k, true)) {
+            None => HandoffType::Context,
+            Some(Ok(kind)) => kind,
+            Some(Err(e)) => return format!("✗ Invalid type: {}", e),
+        };
+        let priority = match req
+            .priority
+            .as_deref()
+            .map(|p| HandoffPriority::from_str(p, true))
+        {
+            None => HandoffPriority::Medium,
+            Some(Ok(priority)) => priority,
+            Some(Err(e)) => return format!("✗ Invalid priority: {}", e),
+        };
+
+        let options = WriteOptions {
+            title: req.title,
+            kind,
+            priority,
+            from_session: req.from_session,
+            body: Some(req.body),
+            why: req.why,
+            dry_run: false,
+        };
+        match handoff::write_item(Path::new("."), &options) {
+            Ok(path) => format!("✓ Wrote {}", path.display()),
+            Err(e) => format!("✗ Handoff failed: {}", e),
+        }
+    }
+
+    #[tool(
+        description = "Read pending inbox/ handoff items from previous sessions, highest priority first, as one markdown bundle. Use in the opening ritual's Inherit phase, then acknowledge them."
+    )]
+    async fn aiandi_handoff_read(&self, _params: Parameters<HandoffReadRequest>) -> String {
+        match handoff::pending(Path::new(".")) {
+            Ok(items) => handoff::bundle(&items),
+            Err(e) => format!("✗ Reading inbox failed: {}", e),
+        }
+    }
+
+    #[tool(
+        description = "Acknowledge handoff items by moving them to inbox/processed/ with a timestamp. Name the items, or pass all=true to acknowledge every pending item."
+    )]
+    async fn aiandi_handoff_ack(&self, Parameters(req): Parameters<HandoffAckRequest>) -> String {
+        let names: Vec<String> = req
+            .items
+            .as_deref()
+            .map(|items| {
+                items
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if names.is_empty() && req.all != Some(true) {
+            return "✗ Name the items to acknowledge, or pass all=true".to_string();
+        }
+
+        match handoff::ack(Path::new("."), &names, chrono::Utc::now()) {
+            Ok(moved) if moved.is_empty() => "✓ Inbox already empty".to_string(),
+            Ok(moved) => format!(
+                "✓ Acknowledged {} item(s) into inbox/processed/",
+                moved.len()
+            ),
+            Err(e) => format!("✗ Acknowledge failed: {}", e),
+        }
+    }
 }
 
 #[tool_handler]
@@ -248,4 +470,15 @@ mod tests {
         assert_eq!(info.server_info.version, env!("CARGO_PKG_VERSION"));
         assert!(info.capabilities.tools.is_some());
     }
+
+    #[tokio::test]
+    async fn test_handoff_ack_requires_names_or_all() {
+        let server = AiandiServer::new();
+
+        for items in [None, Some(" , ".to_string())] {
+            let request = HandoffAckRequest { items, all: None };
+            let result = server.aiandi_handoff_ack(Parameters(request)).await;
+            assert_eq!(result, "✗ Name the items to acknowledge, or pass all=true");
+        }
+    }
 }
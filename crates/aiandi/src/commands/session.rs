@@ -0,0 +1,699 @@
+//! `aiandi session` command implementation.
+//!
+//! Governance session records: `start` opens a notes file under a session
+//! id, `close` files it and the session's outbox transmissions into
+//! `governance/sessions/archive/<session-id>/`, and `recent` / `search`
+//! read the archive back for the LBRP's Inherit phase.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use colored::Colorize;
+
+use crate::markdown;
+
+/// Open session notes, relative to the project root
+pub const SESSIONS_DIR: &str = "governance/sessions";
+
+/// Closed sessions
+pub const ARCHIVE_DIR: &str = "governance/sessions/archive";
+
+/// Transmissions waiting to be archived with their session
+pub const OUTBOX_DIR: &str = "governance/sessions/outbox";
+
+/// CLI arguments for the session command
+#[derive(Debug, Clone, clap::Args)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: SessionCommand,
+
+    /// Project root holding governance/
+    #[arg(long, global = true, default_value = ".")]
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum SessionCommand {
+    /// Open a session: create its id and notes file
+    Start {
+        /// Session topic, used for the id and the notes title
+        title: String,
+
+        /// What the session serves
+        #[arg(long, short = 'g')]
+        goal: Option<String>,
+
+        /// Session id prefix
+        #[arg(long, default_value = "governance")]
+        prefix: String,
+    },
+    /// Archive a session's notes and outbox transmissions
+    ///
+    /// Outbox files are archived if they mention the session id or are
+    /// named with --include.
+    Close {
+        /// Session id (default: the only open session)
+        id: Option<String>,
+
+        /// Outbox file to archive with the session (repeatable)
+        #[arg(long = "include", short = 'i')]
+        include: Vec<String>,
+
+        /// List what would be archived without moving anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Summaries of the most recent archived sessions, as one bundle
+    Recent {
+        /// How many sessions
+        #[arg(long, short = 'n', default_value_t = 3)]
+        count: usize,
+    },
+    /// Search archived sessions by text and front matter
+    Search {
+        /// Text to find (case-insensitive)
+        query: Option<String>,
+
+        /// Only files whose field contains a value, e.g.
+        /// `session=2026-01-15` (front matter or `**Field:**` lines,
+        /// field names matched by prefix; repeatable)
+        #[arg(long = "field", short = 'f', value_parser = parse_field_filter)]
+        fields: Vec<(String, String)>,
+    },
+}
+
+fn parse_field_filter(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_lowercase()))
+        .filter(|(k, _)| !k.is_empty())
+        .ok_or_else(|| format!("expected key=value, got '{}'", s))
+}
+
+/// A session notes file
+#[derive(Debug, Clone)]
+pub struct SessionNotes {
+    pub path: PathBuf,
+    pub id: String,
+    pub title: String,
+    pub date: Option<String>,
+    pub started: Option<DateTime<Utc>>,
+    pub content: String,
+}
+
+impl SessionNotes {
+    /// `None` if the file names no session id
+    fn load(path: &Path) -> Result<Option<Self>> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let Some(id) = get(&content, "session id").or_else(|| get(&content, "session")) else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            path: path.to_path_buf(),
+            title: content
+                .lines()
+                .find_map(|l| l.strip_prefix("# "))
+                .map(|t| t.trim().to_string())
+                .unwrap_or_else(|| id.clone()),
+            date: get(&content, "date"),
+            started: get(&content, "started")
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|d| d.with_timezone(&Utc)),
+            id,
+            content,
+        }))
+    }
+
+    fn is_notes(&self) -> bool {
+        self.path
+            .file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with("Session_Notes_"))
+    }
+
+    /// The `## Session Summary` section, or the first paragraph after the
+    /// header fields
+    pub fn summary(&self) -> String {
+        let body = markdown::strip_front_matter(&self.content);
+        if let Some(section) = section(body, "Session Summary") {
+            return section;
+        }
+        body.split("\n\n")
+            .map(str::trim)
+            .find(|p| {
+                !p.is_empty()
+                    && !p.starts_with('#')
+                    && !p.starts_with("**")
+                    && !p.starts_with("---")
+            })
+            .unwrap_or("")
+            .to_string()
+    }
+}
+
+/// A field from front matter or a `**Field:**` line
+fn get(content: &str, key: &str) -> Option<String> {
+    markdown::field(content, key)
+        .or_else(|| markdown::field(content, &key.replace(' ', "-")))
+        .or_else(|| markdown::bold_field(content, key))
+}
+
+/// The text under `## heading`, up to the next `## ` heading
+fn section(body: &str, heading: &str) -> Option<String> {
+    let start = body
+        .lines()
+        .position(|l| l.trim() == format!("## {}", heading))?;
+    let text: Vec<&str> = body
+        .lines()
+        .skip(start + 1)
+        .take_while(|l| !l.starts_with("## "))
+        .collect();
+    let text = text.join("\n");
+    let text = text.trim().trim_end_matches("---").trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Files under `dir` (recursively) with extension `ext`, sorted
+fn files(dir: &Path, ext: &str, out: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files(&path, ext, out)?;
+        } else if path.extension().is_some_and(|e| e == ext) {
+            out.push(path);
+        }
+    }
+    out.sort();
+    Ok(())
+}
+
+// ============================================================================
+// start
+// ============================================================================
+
+/// `governance-2026-01-15-agentic-memory`
+pub fn session_id(prefix: &str, title: &str, date: DateTime<Local>) -> String {
+    format!(
+        "{}-{}-{}",
+        markdown::slugify(prefix),
+        date.format("%Y-%m-%d"),
+        markdown::slugify(title)
+    )
+}
+
+/// `Session_Notes_2026_01_15_Agentic_Memory_Research.md`
+pub fn notes_file_name(title: &str, date: DateTime<Local>) -> String {
+    let words: Vec<String> = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().collect::<String>() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect();
+    format!(
+        "Session_Notes_{}_{}.md",
+        date.format("%Y_%m_%d"),
+        words.join("_")
+    )
+}
+
+/// The notes scaffold, in the archive's existing format
+pub fn render_notes(id: &str, title: &str, goal: Option<&str>, now: DateTime<Local>) -> String {
+    format!(
+        "# Governance Session Notes: {title}\n\n\
+         **Date:** {date}  \n\
+         **Session ID:** {id}  \n\
+         **Started:** {started}  \n\
+         **Status:** Open  \n\
+         **Goal:** {goal}\n\n\
+         ---\n\n\
+         ## Session Summary\n\n[What this session did, for whoever inherits it]\n\n\
+         ## Decisions\n\n## Artifacts\n\n## Open Threads\n",
+        date = now.format("%Y-%m-%d"),
+        started = now
+            .with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::Secs, true),
+        goal = goal.unwrap_or(title),
+    )
+}
+
+/// Start a session, returning its id and notes path
+pub fn start(
+    root: &Path,
+    title: &str,
+    goal: Option<&str>,
+    prefix: &str,
+    now: DateTime<Local>,
+) -> Result<(String, PathBuf)> {
+    if markdown::slugify(title).is_empty() {
+        bail!("Title cannot be empty");
+    }
+    let id = session_id(prefix, title, now);
+    if open_sessions(root)?.iter().any(|s| s.id == id) || root.join(ARCHIVE_DIR).join(&id).exists()
+    {
+        bail!("Session {} already exists", id);
+    }
+
+    let dir = root.join(SESSIONS_DIR);
+    let path = dir.join(notes_file_name(title, now));
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    fs::write(&path, render_notes(&id, title.trim(), goal, now))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok((id, path))
+}
+
+// ============================================================================
+// close
+// ============================================================================
+
+/// Notes files directly in `governance/sessions/`
+pub fn open_sessions(root: &Path) -> Result<Vec<SessionNotes>> {
+    let dir = root.join(SESSIONS_DIR);
+    let mut sessions = Vec::new();
+    if !dir.is_dir() {
+        return Ok(sessions);
+    }
+    for entry in fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|e| e == "md") {
+            sessions.extend(SessionNotes::load(&path)?);
+        }
+    }
+    sessions.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(sessions)
+}
+
+/// Outbox files that belong to `session`: those mentioning its id, plus
+/// the ones named in `include`
+fn session_outbox(root: &Path, session: &SessionNotes, include: &[String]) -> Result<Vec<PathBuf>> {
+    let dir = root.join(OUTBOX_DIR);
+    let mut belongs = Vec::new();
+    for name in include {
+        let path = dir.join(name);
+        let plain = Path::new(name)
+            .file_name()
+            .is_some_and(|n| n == name.as_str());
+        if !plain || !path.is_file() {
+            bail!("No file '{}' in {}", name, OUTBOX_DIR);
+        }
+        belongs.push(path);
+    }
+
+    if dir.is_dir() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let hidden = path
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with('.'));
+            if path.is_file()
+                && !hidden
+                && fs::read_to_string(&path).is_ok_and(|c| c.contains(&session.id))
+            {
+                belongs.push(path);
+            }
+        }
+    }
+    belongs.sort();
+    belongs.dedup();
+    Ok(belongs)
+}
+
+/// What closing a session will move
+#[derive(Debug)]
+pub struct ClosePlan {
+    pub session: SessionNotes,
+    pub archive: PathBuf,
+    /// Outbox files to archive with the notes
+    pub transmissions: Vec<PathBuf>,
+}
+
+/// Work out what closing a session will move, without touching anything
+pub fn plan_close(root: &Path, id: Option<&str>, include: &[String]) -> Result<ClosePlan> {
+    let open = open_sessions(root)?;
+    let session = match id {
+        Some(id) => open
+            .into_iter()
+            .find(|s| s.id == id)
+            .with_context(|| format!("No open session '{}' in {}", id, SESSIONS_DIR))?,
+        None => match open.len() {
+            0 => bail!("No open session in {}", SESSIONS_DIR),
+            1 => open.into_iter().next().expect("one session"),
+            _ => bail!(
+                "More than one open session; name one of: {}",
+                open.iter()
+                    .map(|s| s.id.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        },
+    };
+
+    let archive = root.join(ARCHIVE_DIR).join(&session.id);
+    if archive.exists() {
+        bail!("{} already exists", archive.display());
+    }
+    let transmissions = session_outbox(root, &session, include)?;
+    Ok(ClosePlan {
+        session,
+        archive,
+        transmissions,
+    })
+}
+
+/// Carry out a close plan; returns the files moved into the archive
+pub fn close(plan: ClosePlan, now: DateTime<Utc>) -> Result<Vec<PathBuf>> {
+    let ClosePlan {
+        session,
+        archive,
+        transmissions,
+    } = plan;
+    fs::create_dir_all(&archive)
+        .with_context(|| format!("Failed to create {}", archive.display()))?;
+
+    let closed = now.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let notes = markdown::set_bold_field(&session.content, "Status", "Closed", "Started");
+    let notes = markdown::set_bold_field(&notes, "Closed", &closed, "Status");
+    let notes_name = session.path.file_name().expect("notes file has a name");
+    let mut moved = vec![archive.join(notes_name)];
+    fs::write(&moved[0], notes)
+        .with_context(|| format!("Failed to write {}", moved[0].display()))?;
+    fs::remove_file(&session.path)
+        .with_context(|| format!("Failed to remove {}", session.path.display()))?;
+
+    for path in transmissions {
+        let target = archive.join(path.file_name().expect("outbox file has a name"));
+        fs::rename(&path, &target).with_context(|| format!("Failed to move {}", path.display()))?;
+        moved.push(target);
+    }
+    Ok(moved)
+}
+
+// ============================================================================
+// recent / search
+// ============================================================================
+
+/// Archived session notes, newest first
+///
+/// Files with a session id count; of several for one session (notes and
+/// meta-observations), the `Session_Notes_` one is used.
+pub fn archived(root: &Path) -> Result<Vec<SessionNotes>> {
+    let mut paths = Vec::new();
+    files(&root.join(ARCHIVE_DIR), "md", &mut paths)?;
+
+    let mut sessions: Vec<SessionNotes> = Vec::new();
+    for path in paths {
+        let Some(notes) = SessionNotes::load(&path)? else {
+            continue;
+        };
+        match sessions.iter_mut().find(|s| s.id == notes.id) {
+            Some(existing) if !existing.is_notes() && notes.is_notes() => *existing = notes,
+            Some(_) => {}
+            None => sessions.push(notes),
+        }
+    }
+    sessions.sort_by(|a, b| (&b.date, &b.started, &b.id).cmp(&(&a.date, &a.started, &a.id)));
+    Ok(sessions)
+}
+
+/// The `count` most recent session summaries as one markdown document
+pub fn recent(root: &Path, count: usize) -> Result<String> {
+    let sessions = archived(root)?;
+    if sessions.is_empty() {
+        return Ok("*No archived sessions.*\n".to_string());
+    }
+
+    let shown = sessions.len().min(count);
+    let mut md = format!(
+        "*{} most recent of {} archived session{}.*\n",
+        shown,
+        sessions.len(),
+        if sessions.len() == 1 { "" } else { "s" }
+    );
+    for session in sessions.iter().take(count) {
+        md.push_str(&format!(
+            "\n---\n\n## {}\n\n*{} · {}*\n\n{}\n",
+            session.title,
+            session.id,
+            session.date.as_deref().unwrap_or("undated"),
+            session.summary()
+        ));
+    }
+    Ok(md)
+}
+
+/// One archived file that matched a search
+#[derive(Debug)]
+pub struct SearchHit {
+    pub path: PathBuf,
+    /// Matching `(line number, line)` pairs; empty for field-only searches
+    pub lines: Vec<(usize, String)>,
+}
+
+/// Archived markdown whose fields all match and that contains `query`
+///
+/// Field names match by prefix, so `session` also finds `Session ID`.
+pub fn search(
+    root: &Path,
+    query: Option<&str>,
+    fields: &[(String, String)],
+) -> Result<Vec<SearchHit>> {
+    let mut paths = Vec::new();
+    files(&root.join(ARCHIVE_DIR), "md", &mut paths)?;
+    let query = query.map(str::to_lowercase).filter(|q| !q.is_empty());
+
+    let mut hits = Vec::new();
+    for path in paths {
+        let content = fs::read_to_string(&path)?;
+        let all_fields: Vec<(String, String)> = markdown::front_matter(&content)
+            .into_iter()
+            .chain(markdown::bold_fields(&content))
+            .map(|(k, v)| (k.to_lowercase(), v.to_lowercase()))
+            .collect();
+        let fields_match = fields.iter().all(|(key, value)| {
+            all_fields
+                .iter()
+                .any(|(k, v)| k.starts_with(key.as_str()) && v.contains(value.as_str()))
+        });
+        if !fields_match {
+            continue;
+        }
+        let lines: Vec<(usize, String)> = match &query {
+            None => Vec::new(),
+            Some(q) => content
+                .lines()
+                .enumerate()
+                .filter(|(_, l)| l.to_lowercase().contains(q))
+                .map(|(i, l)| (i + 1, l.trim().to_string()))
+                .collect(),
+        };
+        if query.is_none() || !lines.is_empty() {
+            hits.push(SearchHit { path, lines });
+        }
+    }
+    Ok(hits)
+}
+
+/// Run from CLI arguments
+pub fn run_from_args(args: &Args) {
+    let root = &args.root;
+    let result = match &args.command {
+        SessionCommand::Start {
+            title,
+            goal,
+            prefix,
+        } => start(root, title, goal.as_deref(), prefix, Local::now()).map(|(id, path)| {
+            println!("{} Started {}", "✓".green(), id);
+            println!("  Notes: {}", path.display());
+        }),
+        SessionCommand::Close {
+            id,
+            include,
+            dry_run,
+        } => plan_close(root, id.as_deref(), include).and_then(|plan| {
+            let name = |path: &Path| {
+                path.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string()
+            };
+            println!(
+                "{} {} to {}",
+                "→".yellow(),
+                if *dry_run {
+                    "Would archive"
+                } else {
+                    "Archiving"
+                },
+                plan.archive.display()
+            );
+            println!("  {}", name(&plan.session.path));
+            for path in &plan.transmissions {
+                println!("  {}", name(path));
+            }
+            if *dry_run {
+                return Ok(());
+            }
+            let archive = plan.archive.clone();
+            close(plan, Utc::now())
+                .map(|_| println!("{} Archived to {}", "✓".green(), archive.display()))
+        }),
+        SessionCommand::Recent { count } => recent(root, *count).map(|md| print!("{}", md)),
+        SessionCommand::Search { query, fields } => {
+            if query.is_none() && fields.is_empty() {
+                eprintln!("{} Give a query, --field, or both", "Error:".red());
+                std::process::exit(1);
+            }
+            search(root, query.as_deref(), fields).map(|hits| {
+                if hits.is_empty() {
+                    println!("{} No matches", "→".yellow());
+                }
+                for hit in hits {
+                    println!("{}", hit.path.display().to_string().bold());
+                    for (number, line) in hit.lines.iter().take(5) {
+                        println!("  {:>4}: {}", number, line);
+                    }
+                    if hit.lines.len() > 5 {
+                        println!("  ... {} more", hit.lines.len() - 5);
+                    }
+                }
+            })
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("{} {}", "Error:".red(), e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn local(s: &str) -> DateTime<Local> {
+        DateTime::parse_from_rfc3339(s)
+            .unwrap()
+            .with_timezone(&Local)
+    }
+
+    fn archive_legacy(root: &Path) {
+        let dir = root.join(ARCHIVE_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("Meta_Observations_2026_01_15_Agentic_Memory_Research.md"),
+            "# Meta-Observations\n\n**Date:** 2026-01-15  \n**Session:** governance-2026-01-15-agentic-memory  \n\n---\n\nObserved.\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("Session_Notes_2026_01_15_Agentic_Memory_Research.md"),
+            "# Governance Session Notes: Agentic Memory Research\n\n**Date:** 2026-01-15  \n**Session ID:** governance-2026-01-15-agentic-memory  \n\n---\n\n## Session Summary\n\nResearched TerminusDB.\n\n---\n\n## Next\n",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_names() {
+        let now = local("2026-01-15T10:00:00+00:00");
+        assert_eq!(
+            notes_file_name("agentic memory research", now),
+            "Session_Notes_2026_01_15_Agentic_Memory_Research.md"
+        );
+        assert_eq!(
+            session_id("governance", "Agentic Memory", now),
+            "governance-2026-01-15-agentic-memory"
+        );
+    }
+
+    #[test]
+    fn test_start_and_close() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let outbox = root.join(OUTBOX_DIR);
+        fs::create_dir_all(&outbox).unwrap();
+        fs::write(outbox.join(".gitkeep"), "").unwrap();
+        fs::write(outbox.join("old.xml"), "<transmission/>").unwrap();
+
+        let (id, notes) = start(root, "Ledger", None, "governance", Local::now()).unwrap();
+        assert!(start(root, "Ledger", None, "governance", Local::now()).is_err());
+        let content = fs::read_to_string(&notes).unwrap();
+        assert!(content.contains(&format!("**Session ID:** {}  \n", id)));
+        let mentions = format!("<transmission session=\"{}\"/>", id);
+        fs::write(outbox.join("report.xml"), mentions).unwrap();
+        // Written during the session, but says nothing about it
+        fs::write(outbox.join("other.xml"), "<transmission/>").unwrap();
+
+        assert!(plan_close(root, None, &["missing.xml".to_string()]).is_err());
+        assert!(plan_close(root, None, &["../old.xml".to_string()]).is_err());
+
+        let plan = plan_close(root, None, &["old.xml".to_string()]).unwrap();
+        assert!(plan.archive.ends_with(format!("archive/{}", id)));
+        assert_eq!(
+            plan.transmissions,
+            vec![outbox.join("old.xml"), outbox.join("report.xml")]
+        );
+        // Planning moves nothing
+        assert!(notes.exists());
+        assert!(!plan.archive.exists());
+
+        let archive = plan.archive.clone();
+        let moved = close(plan, Utc::now()).unwrap();
+        assert_eq!(moved.len(), 3);
+        assert!(!notes.exists());
+        assert!(outbox.join("other.xml").exists());
+        assert!(archive.join("report.xml").exists());
+        assert!(archive.join("old.xml").exists());
+        let archived = fs::read_to_string(&moved[0]).unwrap();
+        assert!(archived.contains("**Status:** Closed  \n**Closed:** "));
+        assert!(plan_close(root, None, &[]).is_err());
+    }
+
+    #[test]
+    fn test_recent_prefers_session_notes() {
+        let temp = TempDir::new().unwrap();
+        archive_legacy(temp.path());
+
+        let sessions = archived(temp.path()).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].summary(), "Researched TerminusDB.");
+
+        let md = recent(temp.path(), 3).unwrap();
+        assert!(md.starts_with("*1 most recent of 1 archived session.*\n"));
+        assert!(md.contains("## Governance Session Notes: Agentic Memory Research\n\n*governance-2026-01-15-agentic-memory · 2026-01-15*\n\nResearched TerminusDB.\n"));
+    }
+
+    #[test]
+    fn test_search() {
+        let temp = TempDir::new().unwrap();
+        archive_legacy(temp.path());
+        let field = |k: &str, v: &str| vec![(k.to_string(), v.to_string())];
+
+        let hits = search(temp.path(), Some("terminusdb"), &[]).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(
+            hits[0].lines,
+            vec![(10, "Researched TerminusDB.".to_string())]
+        );
+
+        let hits = search(temp.path(), None, &field("session", "agentic-memory")).unwrap();
+        assert_eq!(hits.len(), 2);
+        let hits = search(temp.path(), None, &field("session id", "agentic")).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(
+            search(temp.path(), Some("observed"), &field("session id", "x"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
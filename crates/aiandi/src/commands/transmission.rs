@@ -0,0 +1,791 @@
+//! `aiandi transmission` command implementation.
+//!
+//! Scaffold, validate, list and follow threads of transmissions in the
+//! governance outbox, inbox and archive.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use aiandi_transmission::{Element, Node, Priority, Thread, Transmission, TransmissionType};
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+
+/// Directories searched for transmissions, relative to the project root
+pub const TRANSMISSION_DIRS: &[&str] = &[
+    "governance/sessions/outbox",
+    "governance/sessions/inbox",
+    "governance/sessions/archive",
+    "outbox",
+    "inbox",
+];
+
+/// Where new transmissions are written
+pub const DEFAULT_OUTBOX: &str = "governance/sessions/outbox";
+
+/// CLI arguments for the transmission command
+#[derive(Debug, Clone, clap::Args)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: TransmissionCommand,
+
+    /// Project root the transmission directories are relative to
+    #[arg(long, global = true, default_value = ".")]
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum TransmissionCommand {
+    /// Scaffold a transmission at the next position in its thread
+    New(NewArgs),
+    /// Check transmissions against the schema (exits nonzero on errors)
+    Validate(ValidateArgs),
+    /// List transmissions in the outbox, inbox and archive
+    List(ListArgs),
+    /// Show a thread's reply chain in order, flagging gaps and dangling replies
+    Thread(ThreadArgs),
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct NewArgs {
+    /// Transmission type (report, instruction, query, proposal, finding, ...)
+    #[arg(long = "type", short = 't')]
+    pub kind: TransmissionType,
+
+    /// Thread id
+    #[arg(long)]
+    pub thread: String,
+
+    /// Short description for the file name (default: today's date)
+    #[arg(long, short = 'd')]
+    pub description: Option<String>,
+
+    /// Sender
+    #[arg(long, default_value = "aiandi Agent")]
+    pub from: String,
+
+    /// Recipient
+    #[arg(long, default_value = "Governance Committee")]
+    pub to: String,
+
+    /// Priority: low, standard, high or critical
+    #[arg(long, default_value = "standard")]
+    pub priority: Priority,
+
+    /// Directory to write to (default: governance/sessions/outbox)
+    #[arg(long)]
+    pub dir: Option<PathBuf>,
+
+    /// Show what would be created without creating
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct ValidateArgs {
+    /// Files or directories to check (default: all transmission directories)
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct ListArgs {
+    /// Only this type
+    #[arg(long = "type", short = 't')]
+    pub kind: Option<TransmissionType>,
+
+    /// Only this thread
+    #[arg(long)]
+    pub thread: Option<String>,
+
+    /// Only this location: outbox, inbox or archive
+    #[arg(long)]
+    pub location: Option<Location>,
+
+    /// Only senders or recipients containing this text
+    #[arg(long)]
+    pub party: Option<String>,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct ThreadArgs {
+    /// Thread id
+    pub id: String,
+}
+
+/// Which kind of directory a transmission was found in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Location {
+    Outbox,
+    Inbox,
+    Archive,
+}
+
+impl Location {
+    /// The location a path belongs to, by its nearest matching directory
+    fn of(path: &Path) -> Location {
+        for component in path.components().rev() {
+            match component.as_os_str().to_str() {
+                Some("archive") => return Location::Archive,
+                Some("inbox") => return Location::Inbox,
+                Some("outbox") => return Location::Outbox,
+                _ => {}
+            }
+        }
+        Location::Outbox
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Location::Outbox => "outbox",
+            Location::Inbox => "inbox",
+            Location::Archive => "archive",
+        }
+    }
+}
+
+/// A transmission file found on disk
+#[derive(Debug)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub location: Location,
+    /// The parsed transmission, or why it could not be parsed
+    pub transmission: Result<Transmission, String>,
+}
+
+/// Every `.xml` file under the transmission directories of `root`
+pub fn scan(root: &Path) -> Result<Vec<Entry>> {
+    let mut files = Vec::new();
+    for dir in TRANSMISSION_DIRS {
+        collect_xml(&root.join(dir), &mut files)?;
+    }
+    files.sort();
+    files.dedup();
+    Ok(files.into_iter().map(load).collect())
+}
+
+fn load(path: PathBuf) -> Entry {
+    let transmission = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|xml| aiandi_transmission::parse(&xml).map_err(|e| e.to_string()));
+    Entry {
+        location: Location::of(&path),
+        path,
+        transmission,
+    }
+}
+
+fn collect_xml(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if path.is_file() {
+        if path.extension().is_some_and(|e| e == "xml") {
+            files.push(path.to_path_buf());
+        }
+        return Ok(());
+    }
+    if !path.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(path).with_context(|| format!("Failed to read {}", path.display()))? {
+        collect_xml(&entry?.path(), files)?;
+    }
+    Ok(())
+}
+
+/// Transmissions in `thread`, ordered by position
+fn in_thread<'a>(entries: &'a [Entry], thread: &str) -> Vec<(&'a Entry, &'a Transmission)> {
+    let mut members: Vec<(&Entry, &Transmission)> = entries
+        .iter()
+        .filter_map(|e| e.transmission.as_ref().ok().map(|t| (e, t)))
+        .filter(|(_, t)| t.thread.as_ref().is_some_and(|th| th.id == thread))
+        .collect();
+    members.sort_by_key(|(_, t)| t.thread.as_ref().and_then(|th| th.position));
+    members
+}
+
+/// The position after the highest one used in `thread` (1 for a new thread)
+pub fn next_position(entries: &[Entry], thread: &str) -> u32 {
+    in_thread(entries, thread)
+        .iter()
+        .filter_map(|(_, t)| t.thread.as_ref().and_then(|th| th.position))
+        .max()
+        .map_or(1, |p| p + 1)
+}
+
+// ============================================================================
+// new
+// ============================================================================
+
+/// Build the scaffold for a new transmission
+pub fn scaffold(args: &NewArgs, entries: &[Entry], date: &str) -> Transmission {
+    let position = next_position(entries, &args.thread);
+    let previous = in_thread(entries, &args.thread)
+        .last()
+        .map(|(_, t)| t.id.clone().unwrap_or_else(|| (position - 1).to_string()));
+
+    let mut t = Transmission::new(args.kind);
+    t.id = Some(format!("{}-{}-{}", date, args.thread, position));
+    t.from = Some(args.from.clone());
+    t.to = Some(args.to.clone());
+    t.date = Some(date.to_string());
+    t.priority = Some(args.priority);
+    t.priority_reason = Some("[Why this priority]".to_string());
+    t.thread = Some(Thread {
+        id: args.thread.clone(),
+        position: Some(position),
+        in_reply_to: previous,
+    });
+    t.context = Some(text_element(
+        "context",
+        "[Background the recipient needs to act on this without prior context]",
+    ));
+    t.payload = payload_template(args.kind);
+    t.response_spec = Some(
+        Element::new("response-spec")
+            .with_text_child("format", "[Expected response format]")
+            .with_text_child("delivery", "[How and where to deliver the response]")
+            .with_text_child(
+                "success-criteria",
+                "[How the sender knows the response was sufficient]",
+            ),
+    );
+    t.closing = Some(text_element(
+        "closing",
+        "[Relational acknowledgment, uncertainty disclosure, thanks]",
+    ));
+    t
+}
+
+fn text_element(name: &str, text: &str) -> Element {
+    let mut element = Element::new(name);
+    element.children.push(Node::Text(text.to_string()));
+    element
+}
+
+fn with_attribute(mut element: Element, name: &str, value: &str) -> Element {
+    element
+        .attributes
+        .push((name.to_string(), value.to_string()));
+    element
+}
+
+/// The content skeleton for each type, after the canon's examples
+fn payload_template(kind: TransmissionType) -> Element {
+    let content = Element::new("content");
+    let child = match kind {
+        TransmissionType::Instruction => with_attribute(Element::new("task"), "id", "1")
+            .with_text_child("description", "[What needs to be done]")
+            .with_text_child("deliverables", "[Specific outputs]")
+            .with_text_child("constraints", "[Limitations or requirements]")
+            .with_text_child("verification", "[How to confirm the task is complete]"),
+        TransmissionType::Report | TransmissionType::Response => {
+            with_attribute(Element::new("response"), "to", "[question or task id]")
+                .with_text_child("finding", "[The answer or synthesis]")
+                .with_text_child("confidence", "[Basis for confidence]")
+                .with_text_child("evidence", "[Supporting observations]")
+        }
+        TransmissionType::Query | TransmissionType::ResearchRequest => {
+            with_attribute(Element::new("question"), "id", "1")
+                .with_text_child("title", "[Short question]")
+                .with_text_child("body", "[The question, with context]")
+        }
+        TransmissionType::Proposal => {
+            Element::new("problem").with_text_child("statement", "[What problem are we solving]")
+        }
+        TransmissionType::Finding => with_attribute(Element::new("finding"), "id", "F-001")
+            .with_text_child("description", "[What was found]")
+            .with_text_child("risk", "[Why it matters]")
+            .with_text_child("remediation", "[How to fix]"),
+        TransmissionType::Decision => text_element("decision", "[What was decided, and why]"),
+        TransmissionType::BootArtifact => Element::new("mission")
+            .with_text_child("objective", "[What to accomplish]")
+            .with_text_child("success-criteria", "[How to know when complete]"),
+    };
+
+    let mut content = content;
+    content.children.push(Node::Element(child));
+    match kind {
+        TransmissionType::Proposal => {
+            let proposal = Element::new("proposal")
+                .with_text_child("approach", "[Recommended solution]")
+                .with_text_child("alternatives", "[Other options considered]")
+                .with_text_child("tradeoffs", "[Pros and cons]");
+            content.children.push(Node::Element(proposal));
+        }
+        TransmissionType::Report => {
+            content.children.push(Node::Element(text_element(
+                "open-questions",
+                "[What remains uncertain]",
+            )));
+        }
+        _ => {}
+    }
+    content
+}
+
+fn run_new(root: &Path, args: &NewArgs) -> Result<PathBuf> {
+    let entries = scan(root)?;
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let transmission = scaffold(args, &entries, &date);
+
+    let description = args.description.clone().unwrap_or_else(|| date.clone());
+    let file_name = transmission
+        .file_name(&description)
+        .expect("scaffold always has a thread");
+    if file_name.contains(['/', '\\']) {
+        bail!("Thread id and description cannot contain path separators");
+    }
+
+    let dir = args
+        .dir
+        .clone()
+        .unwrap_or_else(|| root.join(DEFAULT_OUTBOX));
+    let path = dir.join(&file_name);
+    let position = transmission
+        .thread
+        .as_ref()
+        .and_then(|t| t.position)
+        .unwrap_or(1);
+
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+    if args.dry_run {
+        println!(
+            "{} Would create {} (position {})",
+            "[dry-run]".blue(),
+            path.display(),
+            position
+        );
+        return Ok(path);
+    }
+
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    fs::write(&path, transmission.to_xml())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!(
+        "{} Created {} (thread {}, position {})",
+        "✓".green(),
+        path.display(),
+        args.thread,
+        position
+    );
+    Ok(path)
+}
+
+// ============================================================================
+// validate
+// ============================================================================
+
+/// Problems with one file; empty if it is valid
+pub fn problems(entry: &Entry) -> Vec<String> {
+    match &entry.transmission {
+        Err(e) => vec![e.clone()],
+        Ok(t) => match t.validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.0.iter().map(|e| e.to_string()).collect(),
+        },
+    }
+}
+
+fn run_validate(root: &Path, args: &ValidateArgs) -> Result<usize> {
+    let entries = if args.paths.is_empty() {
+        scan(root)?
+    } else {
+        let mut files = Vec::new();
+        for path in &args.paths {
+            if !path.exists() {
+                bail!("{} does not exist", path.display());
+            }
+            collect_xml(path, &mut files)?;
+        }
+        files.into_iter().map(load).collect()
+    };
+
+    if entries.is_empty() {
+        println!("{} No transmissions found", "→".yellow());
+        return Ok(0);
+    }
+
+    let mut invalid = 0;
+    for entry in &entries {
+        let problems = problems(entry);
+        if problems.is_empty() {
+            println!("{} {}", "✓".green(), entry.path.display());
+        } else {
+            invalid += 1;
+            println!("{} {}", "✗".red(), entry.path.display());
+            for problem in problems {
+                println!("    {}", problem);
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "{} checked, {} invalid",
+        entries.len(),
+        if invalid > 0 {
+            invalid.to_string().red()
+        } else {
+            invalid.to_string().green()
+        }
+    );
+    Ok(invalid)
+}
+
+// ============================================================================
+// list
+// ============================================================================
+
+fn matches(args: &ListArgs, entry: &Entry) -> bool {
+    if args.location.is_some_and(|l| l != entry.location) {
+        return false;
+    }
+    let Ok(t) = &entry.transmission else {
+        // Unparseable files only show up unfiltered, so they aren't missed
+        return args.kind.is_none() && args.thread.is_none() && args.party.is_none();
+    };
+    if args.kind.is_some_and(|k| k != t.kind) {
+        return false;
+    }
+    if let Some(thread) = &args.thread {
+        if t.thread.as_ref().is_none_or(|th| &th.id != thread) {
+            return false;
+        }
+    }
+    if let Some(party) = &args.party {
+        let party = party.to_lowercase();
+        let mentions = |field: &Option<String>| {
+            field
+                .as_ref()
+                .is_some_and(|f| f.to_lowercase().contains(&party))
+        };
+        if !mentions(&t.from) && !mentions(&t.to) {
+            return false;
+        }
+    }
+    true
+}
+
+fn run_list(root: &Path, args: &ListArgs) -> Result<()> {
+    let entries = scan(root)?;
+    let mut rows: Vec<[String; 6]> = entries
+        .iter()
+        .filter(|e| matches(args, e))
+        .map(|e| {
+            let file = e
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            match &e.transmission {
+                Ok(t) => {
+                    let (thread, position) = match &t.thread {
+                        Some(th) => (
+                            th.id.clone(),
+                            th.position.map(|p| p.to_string()).unwrap_or_default(),
+                        ),
+                        None => (String::new(), String::new()),
+                    };
+                    [
+                        e.location.as_str().to_string(),
+                        t.date.clone().unwrap_or_default(),
+                        t.kind.to_string(),
+                        thread,
+                        position,
+                        file,
+                    ]
+                }
+                Err(_) => [
+                    e.location.as_str().to_string(),
+                    String::new(),
+                    "invalid".to_string(),
+                    String::new(),
+                    String::new(),
+                    file,
+                ],
+            }
+        })
+        .collect();
+
+    if rows.is_empty() {
+        println!("{} No transmissions found", "→".yellow());
+        return Ok(());
+    }
+
+    rows.sort_by(|a, b| (&a[1], &a[3], &a[4]).cmp(&(&b[1], &b[3], &b[4])));
+    let header = ["LOCATION", "DATE", "TYPE", "THREAD", "POS", "FILE"];
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|r| r[i].len())
+                .chain([header[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let line = |cells: [&str; 6]| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    println!("{}", line(header).bold());
+    for row in &rows {
+        println!(
+            "{}",
+            line([&row[0], &row[1], &row[2], &row[3], &row[4], &row[5]])
+        );
+    }
+    Ok(())
+}
+
+// ============================================================================
+// thread
+// ============================================================================
+
+/// Gaps, duplicate positions and dangling `in-reply-to` references in a
+/// thread
+pub fn thread_problems(entries: &[Entry], thread: &str) -> Vec<String> {
+    let members = in_thread(entries, thread);
+    let mut problems = Vec::new();
+
+    let mut positions: BTreeMap<u32, usize> = BTreeMap::new();
+    for (entry, t) in &members {
+        match t.thread.as_ref().and_then(|th| th.position) {
+            Some(p) => *positions.entry(p).or_default() += 1,
+            None => problems.push(format!("{} has no position", entry.path.display())),
+        }
+    }
+    if let Some(&last) = positions.keys().last() {
+        let missing: Vec<String> = (1..=last)
+            .filter(|p| !positions.contains_key(p))
+            .map(|p| p.to_string())
+            .collect();
+        if !missing.is_empty() {
+            problems.push(format!("Gap: position {} missing", missing.join(", ")));
+        }
+    }
+    for (position, count) in &positions {
+        if *count > 1 {
+            problems.push(format!(
+                "Position {} used by {} transmissions",
+                position, count
+            ));
+        }
+    }
+
+    // A reply may name any transmission id, or a position in this thread
+    let ids: BTreeSet<&str> = entries
+        .iter()
+        .filter_map(|e| e.transmission.as_ref().ok())
+        .filter_map(|t| t.id.as_deref())
+        .collect();
+    for (entry, t) in &members {
+        let Some(reply_to) = t.thread.as_ref().and_then(|th| th.in_reply_to.as_deref()) else {
+            continue;
+        };
+        let known = ids.contains(reply_to)
+            || reply_to
+                .parse::<u32>()
+                .is_ok_and(|p| positions.contains_key(&p));
+        if !known {
+            problems.push(format!(
+                "{} replies to '{}', which is not among the known transmissions",
+                entry.path.display(),
+                reply_to
+            ));
+        }
+    }
+    problems
+}
+
+fn run_thread(root: &Path, args: &ThreadArgs) -> Result<()> {
+    let entries = scan(root)?;
+    let members = in_thread(&entries, &args.id);
+    if members.is_empty() {
+        bail!("No transmissions in thread '{}'", args.id);
+    }
+
+    println!("{} {}", "Thread".bold(), args.id.bold());
+    for (entry, t) in &members {
+        let thread = t.thread.as_ref();
+        let position = thread
+            .and_then(|th| th.position)
+            .map_or("?".to_string(), |p| p.to_string());
+        println!(
+            "  {}. [{}] {} {} → {}",
+            position,
+            t.kind,
+            t.date.as_deref().unwrap_or("????-??-??"),
+            t.from.as_deref().unwrap_or("?"),
+            t.to.as_deref().unwrap_or("?")
+        );
+        if let Some(reply_to) = thread.and_then(|th| th.in_reply_to.as_deref()) {
+            println!("     in reply to {}", reply_to);
+        }
+        println!("     {}", entry.path.display().to_string().dimmed());
+    }
+
+    let problems = thread_problems(&entries, &args.id);
+    if !problems.is_empty() {
+        println!();
+        for problem in problems {
+            println!("{} {}", "⚠".yellow(), problem);
+        }
+    }
+    Ok(())
+}
+
+/// Run from CLI arguments
+pub fn run_from_args(args: &Args) {
+    let result = match &args.command {
+        TransmissionCommand::New(new) => run_new(&args.root, new).map(|_| ()),
+        TransmissionCommand::Validate(validate) => match run_validate(&args.root, validate) {
+            Ok(0) => Ok(()),
+            Ok(_) => std::process::exit(1),
+            Err(e) => Err(e),
+        },
+        TransmissionCommand::List(list) => run_list(&args.root, list),
+        TransmissionCommand::Thread(thread) => run_thread(&args.root, thread),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{} {}", "Error:".red(), e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, dir: &str, name: &str, thread: &str, position: u32, reply: &str) {
+        let dir = root.join(dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(name),
+            format!(
+                r#"<transmission id="{thread}-{position}" schema="transmission-v1" type="report">
+  <metadata>
+    <from>Ops</from>
+    <to>Governance</to>
+    <date>2026-01-1{position}</date>
+    <thread id="{thread}"><position>{position}</position><in-reply-to>{reply}</in-reply-to></thread>
+  </metadata>
+  <content><summary>Done</summary></content>
+</transmission>"#
+            ),
+        )
+        .unwrap();
+    }
+
+    fn new_args(kind: TransmissionType, thread: &str) -> NewArgs {
+        NewArgs {
+            kind,
+            thread: thread.to_string(),
+            description: None,
+            from: "aiandi Agent".to_string(),
+            to: "Governance Committee".to_string(),
+            priority: Priority::Standard,
+            dir: None,
+            dry_run: false,
+        }
+    }
+
+    #[test]
+    fn test_location_of_path() {
+        assert_eq!(
+            Location::of(Path::new("governance/sessions/inbox/processed/x.xml")),
+            Location::Inbox
+        );
+        assert_eq!(
+            Location::of(Path::new("governance/sessions/archive/x.xml")),
+            Location::Archive
+        );
+    }
+
+    #[test]
+    fn test_scaffold_continues_thread() {
+        let temp = TempDir::new().unwrap();
+        write(
+            temp.path(),
+            "governance/sessions/outbox",
+            "a.xml",
+            "infra",
+            1,
+            "null",
+        );
+        write(
+            temp.path(),
+            "governance/sessions/archive",
+            "b.xml",
+            "infra",
+            2,
+            "infra-1",
+        );
+        let entries = scan(temp.path()).unwrap();
+
+        assert_eq!(next_position(&entries, "infra"), 3);
+        assert_eq!(next_position(&entries, "fresh"), 1);
+
+        let t = scaffold(
+            &new_args(TransmissionType::Instruction, "infra"),
+            &entries,
+            "2026-10-19",
+        );
+        let thread = t.thread.as_ref().unwrap();
+        assert_eq!(thread.position, Some(3));
+        assert_eq!(thread.in_reply_to.as_deref(), Some("infra-2"));
+        assert_eq!(
+            t.file_name("Next Steps").unwrap(),
+            "Transmission_infra_Instruction_Next-Steps.xml"
+        );
+
+        // Every scaffold is valid as written, and survives the round trip
+        for kind in TransmissionType::ALL {
+            let t = scaffold(&new_args(kind, "fresh"), &entries, "2026-10-19");
+            assert!(t.validate().is_ok(), "{} scaffold invalid", kind);
+            assert_eq!(aiandi_transmission::parse(&t.to_xml()).unwrap(), t);
+        }
+    }
+
+    #[test]
+    fn test_thread_problems() {
+        let temp = TempDir::new().unwrap();
+        let outbox = "governance/sessions/outbox";
+        write(temp.path(), outbox, "a.xml", "infra", 1, "null");
+        write(temp.path(), outbox, "c.xml", "infra", 3, "infra-2");
+        write(temp.path(), outbox, "d.xml", "infra", 3, "1");
+        let entries = scan(temp.path()).unwrap();
+
+        let problems = thread_problems(&entries, "infra");
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+        assert!(problems[0].contains("position 2 missing"));
+        assert!(problems[1].contains("Position 3 used by 2"));
+        assert!(problems[2].contains("replies to 'infra-2'"));
+    }
+
+    #[test]
+    fn test_validate_reports_problems() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("outbox");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("broken.xml"), "<transmission").unwrap();
+        fs::write(
+            dir.join("query.xml"),
+            r#"<transmission type="query"><metadata><from>a</from></metadata></transmission>"#,
+        )
+        .unwrap();
+
+        let entries = scan(temp.path()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(problems(&entries[0])[0].contains("well-formed"));
+        assert_eq!(problems(&entries[1]).len(), 3);
+    }
+}
@@ -0,0 +1,258 @@
+//! `aiandi vault` command implementation.
+//!
+//! Creates a second-brain vault from the embedded template and records its
+//! location in the aiandi config.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+use crate::config::{Config, VaultConfig};
+use crate::vault_template::{template_files, VAULT_DIRS};
+
+/// CLI arguments for the vault command
+#[derive(Debug, Clone, clap::Args)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: VaultCommand,
+}
+
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum VaultCommand {
+    /// Create a vault with the PARA folders and aiandi protocols
+    Init(InitArgs),
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct InitArgs {
+    /// Where to create the vault
+    pub path: PathBuf,
+
+    /// Overwrite existing template files
+    #[arg(long)]
+    pub force: bool,
+
+    /// Show what would be created without creating
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Don't record the vault path in the aiandi config
+    #[arg(long)]
+    pub no_config: bool,
+}
+
+/// Options for vault init (for testing)
+#[derive(Debug, Clone, Default)]
+pub struct VaultInitOptions {
+    /// Overwrite existing files
+    pub force: bool,
+    /// Show what would be created without creating
+    pub dry_run: bool,
+    /// Config file to record the vault in (None = don't record)
+    pub config: Option<PathBuf>,
+}
+
+impl From<&InitArgs> for VaultInitOptions {
+    fn from(args: &InitArgs) -> Self {
+        Self {
+            force: args.force,
+            dry_run: args.dry_run,
+            config: (!args.no_config).then(Config::default_path),
+        }
+    }
+}
+
+/// Result of vault init
+#[derive(Debug, Default)]
+pub struct VaultInitResult {
+    /// Folders and files created
+    pub created: usize,
+    /// Folders and files that already existed
+    pub skipped: usize,
+    /// Whether the config was changed
+    pub config_updated: bool,
+}
+
+/// Create the vault at `vault_path`
+pub fn run_in_directory(vault_path: &Path, options: &VaultInitOptions) -> Result<VaultInitResult> {
+    let mut result = VaultInitResult::default();
+
+    for dir in std::iter::once("").chain(VAULT_DIRS.iter().copied()) {
+        let path = vault_path.join(dir);
+        let label = if dir.is_empty() {
+            vault_path.display().to_string()
+        } else {
+            format!("{}/", dir)
+        };
+        if path.is_dir() {
+            result.skipped += 1;
+        } else if options.dry_run {
+            println!("{} Would create {}", "[dry-run]".blue(), label);
+        } else {
+            fs::create_dir_all(&path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            println!("Creating {}", label);
+            result.created += 1;
+        }
+    }
+
+    for file in template_files() {
+        let path = vault_path.join(file.path);
+        if path.exists() && !options.force {
+            println!(
+                "{} Skipped {} (already exists, use --force to overwrite)",
+                "→".yellow(),
+                file.path
+            );
+            result.skipped += 1;
+        } else if options.dry_run {
+            println!("{} Would write {}", "[dry-run]".blue(), file.path);
+        } else {
+            fs::write(&path, file.content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("{} Wrote {}", "✓".green(), file.path);
+            result.created += 1;
+        }
+    }
+
+    if let Some(config_path) = &options.config {
+        result.config_updated = record_vault(vault_path, config_path, options.dry_run)?;
+    }
+
+    println!();
+    if options.dry_run {
+        println!("Dry run complete. No files created.");
+    } else {
+        println!(
+            "Vault ready at {} ({} created, {} already present).",
+            vault_path.display(),
+            result.created,
+            result.skipped
+        );
+    }
+    Ok(result)
+}
+
+/// Point the config at the vault; returns whether it changed
+fn record_vault(vault_path: &Path, config_path: &Path, dry_run: bool) -> Result<bool> {
+    let absolute = if vault_path.exists() {
+        vault_path.canonicalize()?
+    } else {
+        std::path::absolute(vault_path)?
+    };
+
+    let mut config = Config::load(config_path)?;
+    if config.vault.as_ref().is_some_and(|v| v.path == absolute) {
+        println!(
+            "{} Vault already recorded in {}",
+            "→".yellow(),
+            config_path.display()
+        );
+        return Ok(false);
+    }
+    if dry_run {
+        println!(
+            "{} Would record vault in {}",
+            "[dry-run]".blue(),
+            config_path.display()
+        );
+        return Ok(false);
+    }
+
+    if let Some(previous) = &config.vault {
+        println!(
+            "{} Replacing recorded vault {}",
+            "→".yellow(),
+            previous.path.display()
+        );
+    }
+    config.vault = Some(VaultConfig { path: absolute });
+    config.save(config_path)?;
+    println!(
+        "{} Recorded vault in {}",
+        "✓".green(),
+        config_path.display()
+    );
+    Ok(true)
+}
+
+/// Run from CLI arguments
+pub fn run_from_args(args: &Args) {
+    let result = match &args.command {
+        VaultCommand::Init(init) => run_in_directory(&init.path, &VaultInitOptions::from(init)),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{} {}", "Error:".red(), e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn options(temp: &TempDir) -> VaultInitOptions {
+        VaultInitOptions {
+            config: Some(temp.path().join("config/aiandi/config.toml")),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_init_creates_vault_and_records_it() {
+        let temp = TempDir::new().unwrap();
+        let vault = temp.path().join("vault");
+
+        let result = run_in_directory(&vault, &options(&temp)).unwrap();
+        assert_eq!(
+            result.created,
+            1 + VAULT_DIRS.len() + template_files().len()
+        );
+        assert!(result.config_updated);
+        assert!(vault.join("00-inbox/alembic").is_dir());
+        assert!(vault.join("_aiandi/protocols/file-patterns.md").is_file());
+
+        let config = Config::load(&temp.path().join("config/aiandi/config.toml")).unwrap();
+        assert_eq!(config.vault.unwrap().path, vault.canonicalize().unwrap());
+    }
+
+    #[test]
+    fn test_init_is_idempotent() {
+        let temp = TempDir::new().unwrap();
+        let vault = temp.path().join("vault");
+        run_in_directory(&vault, &options(&temp)).unwrap();
+        fs::write(vault.join("README.md"), "mine").unwrap();
+
+        let again = run_in_directory(&vault, &options(&temp)).unwrap();
+        assert_eq!(again.created, 0);
+        assert!(!again.config_updated);
+        assert_eq!(fs::read_to_string(vault.join("README.md")).unwrap(), "mine");
+
+        let forced = VaultInitOptions {
+            force: true,
+            ..options(&temp)
+        };
+        let result = run_in_directory(&vault, &forced).unwrap();
+        assert_eq!(result.created, template_files().len());
+        assert_ne!(fs::read_to_string(vault.join("README.md")).unwrap(), "mine");
+    }
+
+    #[test]
+    fn test_init_dry_run_creates_nothing() {
+        let temp = TempDir::new().unwrap();
+        let vault = temp.path().join("vault");
+        let dry_run = VaultInitOptions {
+            dry_run: true,
+            ..options(&temp)
+        };
+
+        let result = run_in_directory(&vault, &dry_run).unwrap();
+        assert_eq!(result.created, 0);
+        assert!(!vault.exists());
+        assert!(!temp.path().join("config").exists());
+    }
+}
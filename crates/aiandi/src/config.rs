@@ -0,0 +1,83 @@
+//! aiandi configuration (`~/.config/aiandi/config.toml`).
+//!
+//! Only the settings commands need are typed; any other tables in the file
+//! are kept as they are when it is saved.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Overrides the config file location
+pub const CONFIG_ENV: &str = "AIANDI_CONFIG";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vault: Option<VaultConfig>,
+
+    /// Everything this version doesn't know about
+    #[serde(flatten)]
+    pub other: toml::Table,
+}
+
+/// The second-brain vault
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultConfig {
+    pub path: PathBuf,
+}
+
+impl Config {
+    /// `$AIANDI_CONFIG`, or `config.toml` in the aiandi config directory
+    pub fn default_path() -> PathBuf {
+        std::env::var_os(CONFIG_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| crate::commands::doctor::config_dir().join("config.toml"))
+    }
+
+    /// Read `path`; a missing file is an empty config
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("Invalid config in {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let text = toml::to_string_pretty(self).context("Failed to serialize config")?;
+        fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_round_trip_keeps_unknown_tables() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("aiandi/config.toml");
+        assert_eq!(Config::load(&path).unwrap(), Config::default());
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "[taskwarrior]\ndata = \"~/.task\"\n").unwrap();
+        let mut config = Config::load(&path).unwrap();
+        config.vault = Some(VaultConfig {
+            path: PathBuf::from("/home/me/vault"),
+        });
+        config.save(&path).unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.contains("[vault]\npath = \"/home/me/vault\""));
+        assert!(text.contains("[taskwarrior]\ndata = \"~/.task\""));
+        assert_eq!(Config::load(&path).unwrap(), config);
+    }
+}
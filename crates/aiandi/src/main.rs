@@ -2,9 +2,14 @@ use clap::{Parser, Subcommand};
 
 mod agents;
 mod commands;
+mod config;
+mod markdown;
 mod skills;
+mod vault_template;
 
-use commands::{doctor, inbox, init, serve};
+use commands::{
+    adr, doctor, governance, handoff, inbox, init, plan, route, serve, session, transmission, vault,
+};
 
 #[derive(Parser)]
 #[command(name = "aiandi")]
@@ -19,12 +24,28 @@ struct Cli {
 enum Commands {
     /// Capture items to GTD inbox
     Inbox(inbox::Args),
+    /// Route inbox items with the vault's routing rules
+    Route(route::Args),
+    /// Time-aware orientation: what to engage with now
+    Plan(plan::Args),
     /// Initialize aiandi for OpenCode
     Init(init::Args),
     /// Start MCP server for OpenCode integration
     Serve(serve::Args),
     /// Check aiandi installation and configuration
     Doctor,
+    /// Create, supersede, index and lint ADRs in docs/adr/
+    Adr(adr::Args),
+    /// Manage evolution proposals and the decision ledger
+    Governance(governance::Args),
+    /// Hand off context to the next session through inbox/
+    Handoff(handoff::Args),
+    /// Start, close, list and search governance sessions
+    Session(session::Args),
+    /// Work with transmissions (new, validate, list, thread)
+    Transmission(transmission::Args),
+    /// Create and configure a second-brain vault
+    Vault(vault::Args),
 }
 
 fn main() {
@@ -32,6 +53,8 @@ fn main() {
 
     match &cli.command {
         Commands::Inbox(args) => inbox::run_from_args(args),
+        Commands::Route(args) => route::run_from_args(args),
+        Commands::Plan(args) => plan::run_from_args(args),
         Commands::Init(args) => init::run_from_args(args),
         Commands::Serve(args) => {
             if let Err(e) = serve::run_from_args(args) {
@@ -40,5 +63,11 @@ fn main() {
             }
         }
         Commands::Doctor => doctor::run(),
+        Commands::Adr(args) => adr::run_from_args(args),
+        Commands::Governance(args) => governance::run_from_args(args),
+        Commands::Handoff(args) => handoff::run_from_args(args),
+        Commands::Session(args) => session::run_from_args(args),
+        Commands::Transmission(args) => transmission::run_from_args(args),
+        Commands::Vault(args) => vault::run_from_args(args),
     }
 }
@@ -0,0 +1,189 @@
+//! Markdown files with `---` front matter.
+//!
+//! The handoff, governance and session files only use flat `key: value`
+//! front matter, so this reads and edits it line by line instead of pulling
+//! in a YAML parser. Older documents (ADRs, proposals) put the same fields
+//! in `**Key:** value` lines under the title; those are handled here too.
+
+/// `key: value` pairs between leading `---` lines
+pub fn front_matter(content: &str) -> Vec<(String, String)> {
+    let mut lines = content.lines();
+    if lines.next().map(str::trim) != Some("---") {
+        return Vec::new();
+    }
+    lines
+        .take_while(|line| line.trim() != "---")
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+/// The value of `key` in the front matter, if present and non-empty
+pub fn field(content: &str, key: &str) -> Option<String> {
+    front_matter(content)
+        .into_iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v)
+        .filter(|v| !v.is_empty())
+}
+
+/// `content` without its front matter
+pub fn strip_front_matter(content: &str) -> &str {
+    let Some(rest) = content.strip_prefix("---\n") else {
+        return content;
+    };
+    match rest.find("\n---") {
+        Some(end) => {
+            let after = &rest[end + 4..];
+            after.split_once('\n').map_or("", |(_, body)| body)
+        }
+        None => content,
+    }
+}
+
+/// `content` with `key` set to `value`, replacing an existing line or
+/// appending one to the front matter (which is created if missing)
+pub fn set_field(content: &str, key: &str, value: &str) -> String {
+    let line = format!("{}: {}", key, value);
+    let Some(rest) = content.strip_prefix("---\n") else {
+        return format!("---\n{}\n---\n\n{}", line, content);
+    };
+    let Some(end) = rest.find("\n---") else {
+        return format!("---\n{}\n---\n\n{}", line, content);
+    };
+
+    let mut fields: Vec<String> = rest[..end].lines().map(String::from).collect();
+    match fields
+        .iter_mut()
+        .find(|l| l.split_once(':').is_some_and(|(k, _)| k.trim() == key))
+    {
+        Some(existing) => *existing = line,
+        None => fields.push(line),
+    }
+    format!("---\n{}{}", fields.join("\n"), &rest[end..])
+}
+
+/// The value of the first `**Key:** value` line (key matched
+/// case-insensitively)
+pub fn bold_field(content: &str, key: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let (name, value) = split_bold(line)?;
+        name.eq_ignore_ascii_case(key)
+            .then(|| value.trim().to_string())
+            .filter(|v| !v.is_empty())
+    })
+}
+
+/// Every `**Key:** value` line, in order
+pub fn bold_fields(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(split_bold)
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+fn split_bold(line: &str) -> Option<(&str, &str)> {
+    line.trim().strip_prefix("**")?.split_once(":**")
+}
+
+/// `content` with the first `**Key:**` line set to `value`
+///
+/// A missing line is inserted after the `**After:**` line, or else after the
+/// title. Lines keep the trailing double space that makes a markdown line
+/// break.
+pub fn set_bold_field(content: &str, key: &str, value: &str, after: &str) -> String {
+    let line = format!("**{}:** {}  ", key, value);
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let is_field =
+        |l: &str, k: &str| split_bold(l).is_some_and(|(name, _)| name.eq_ignore_ascii_case(k));
+
+    if let Some(existing) = lines.iter_mut().find(|l| is_field(l, key)) {
+        *existing = line;
+    } else if let Some(i) = lines.iter().position(|l| is_field(l, after)) {
+        lines.insert(i + 1, line);
+    } else {
+        let title = lines.iter().position(|l| l.starts_with("# "));
+        match title {
+            Some(i) => {
+                lines.insert(i + 1, String::new());
+                lines.insert(i + 2, line);
+            }
+            None => lines.insert(0, line),
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    updated
+}
+
+/// Lowercase words joined by `-`, for file names
+pub fn slugify(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "---\ntype: warning\npriority:\n---\n\n# Title\n";
+
+    #[test]
+    fn test_read_front_matter() {
+        assert_eq!(front_matter(DOC).len(), 2);
+        assert_eq!(field(DOC, "type").as_deref(), Some("warning"));
+        assert_eq!(field(DOC, "priority"), None);
+        assert_eq!(strip_front_matter(DOC), "\n# Title\n");
+        assert_eq!(strip_front_matter("# Title\n"), "# Title\n");
+        assert!(front_matter("# Title\n---\n").is_empty());
+    }
+
+    #[test]
+    fn test_set_field() {
+        let updated = set_field(DOC, "priority", "high");
+        assert_eq!(
+            updated,
+            "---\ntype: warning\npriority: high\n---\n\n# Title\n"
+        );
+        let updated = set_field(&updated, "status", "done");
+        assert_eq!(field(&updated, "status").as_deref(), Some("done"));
+        assert!(updated.ends_with("status: done\n---\n\n# Title\n"));
+
+        assert_eq!(
+            set_field("# Title\n", "id", "1"),
+            "---\nid: 1\n---\n\n# Title\n"
+        );
+    }
+
+    #[test]
+    fn test_bold_fields() {
+        let doc = "# ADR: Title\n\n**Date:** 2026-01-07  \n**Status:** Accepted  \n\nBody\n";
+        assert_eq!(bold_field(doc, "status").as_deref(), Some("Accepted"));
+        assert_eq!(bold_field(doc, "Supersedes"), None);
+        assert_eq!(bold_fields(doc).len(), 2);
+
+        let updated = set_bold_field(doc, "Status", "Superseded", "Date");
+        assert!(updated.contains("**Status:** Superseded  \n\nBody\n"));
+        let updated = set_bold_field(&updated, "Supersedes", "[Old](old.md)", "Status");
+        assert!(
+            updated.contains("**Status:** Superseded  \n**Supersedes:** [Old](old.md)  \n\nBody")
+        );
+        assert_eq!(
+            set_bold_field("# T\nBody", "Status", "Proposed", "Date"),
+            "# T\n\n**Status:** Proposed  \nBody"
+        );
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("AUR Package: Location!"), "aur-package-location");
+        assert_eq!(slugify("  "), "");
+    }
+}
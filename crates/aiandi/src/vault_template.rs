@@ -0,0 +1,109 @@
+//! Second-brain vault template embedded at compile time.
+//!
+//! Mirrors `packages/second-brain/vault-template/`: the PARA folder layout,
+//! the `_aiandi/` operational directory, and the vault protocols, so
+//! `aiandi vault init` works without the repo checked out.
+
+/// Folders of a new vault, parents before children
+pub const VAULT_DIRS: &[&str] = &[
+    "00-inbox",
+    "00-inbox/dumps",
+    "00-inbox/alembic",
+    "00-inbox/integration",
+    "01-projects",
+    "02-areas",
+    "02-areas/people",
+    "03-resources",
+    "03-resources/reference-notes",
+    "05-knowledge",
+    "05-knowledge/consolidated",
+    "05-knowledge/patterns",
+    "_aiandi",
+    "_aiandi/reference",
+    "_aiandi/protocols",
+    "_aiandi/evolution",
+    "archives",
+    "archives/daily-plans",
+    "archives/intelligence",
+    "archives/intelligence/sessions",
+    "archives/intelligence/intel",
+    "archives/intelligence/audit",
+    "archives/intelligence/checkins",
+    "archives/design",
+    "archives/sources",
+    "permanent-notes",
+    "templates",
+];
+
+/// Default auto-routing rules, also used when a vault has none
+pub const ROUTING_RULES: &str =
+    include_str!("../../../packages/second-brain/protocols/routing-rules.toml");
+
+/// A file extracted into a new vault
+#[derive(Debug, Clone)]
+pub struct TemplateFile {
+    /// Path relative to the vault root
+    pub path: &'static str,
+    pub content: &'static str,
+}
+
+/// Returns all template files
+pub fn template_files() -> Vec<TemplateFile> {
+    vec![
+        TemplateFile {
+            path: "README.md",
+            content: include_str!("../../../packages/second-brain/vault-template/README.md"),
+        },
+        TemplateFile {
+            path: "_aiandi/README.md",
+            content: include_str!(
+                "../../../packages/second-brain/vault-template/_aiandi/README.md"
+            ),
+        },
+        TemplateFile {
+            path: "_aiandi/protocols/file-patterns.md",
+            content: include_str!("../../../packages/second-brain/protocols/file-patterns.md"),
+        },
+        TemplateFile {
+            path: "_aiandi/protocols/routing-rules.md",
+            content: include_str!("../../../packages/second-brain/protocols/routing-rules.md"),
+        },
+        TemplateFile {
+            path: "_aiandi/protocols/routing-rules.toml",
+            content: ROUTING_RULES,
+        },
+        TemplateFile {
+            path: "_aiandi/protocols/gtd-mapping.md",
+            content: include_str!("../../../packages/second-brain/protocols/gtd-mapping.md"),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_files_land_in_vault_dirs() {
+        for file in template_files() {
+            assert!(!file.content.is_empty(), "{} is empty", file.path);
+            if let Some((dir, _)) = file.path.rsplit_once('/') {
+                assert!(VAULT_DIRS.contains(&dir), "{} has no folder", file.path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parents_listed_first() {
+        for (i, dir) in VAULT_DIRS.iter().enumerate() {
+            if let Some((parent, _)) = dir.rsplit_once('/') {
+                assert!(
+                    VAULT_DIRS[..i].contains(&parent),
+                    "{} before {}",
+                    dir,
+                    parent
+                );
+            }
+        }
+    }
+}
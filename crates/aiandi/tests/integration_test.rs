@@ -72,6 +72,17 @@ mod cli_help {
         assert!(out.contains("Start MCP server") || out.contains("serve"));
     }
 
+    #[test]
+    fn test_transmission_help() {
+        let output = aiandi(&["transmission", "--help"]);
+        assert!(output.status.success());
+
+        let out = stdout(&output);
+        for command in ["new", "validate", "list", "thread"] {
+            assert!(out.contains(command), "missing {}", command);
+        }
+    }
+
     #[test]
     fn test_version() {
         let output = aiandi(&["--version"]);
@@ -167,6 +178,83 @@ mod inbox_command {
         assert!(err.contains("empty") || err.contains("Error"));
     }
 
+    #[test]
+    fn test_inbox_vault_backend_uses_configured_vault() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let vault = temp.path().join("vault");
+        let config = temp.path().join("config.toml");
+        let init = Command::new(env!("CARGO_BIN_EXE_aiandi"))
+            .args(["vault", "init", vault.to_str().unwrap()])
+            .env("AIANDI_CONFIG", &config)
+            .output()
+            .unwrap();
+        assert!(init.status.success());
+
+        let output = Command::new(env!("CARGO_BIN_EXE_aiandi"))
+            .args([
+                "inbox",
+                "--backend",
+                "vault",
+                "-t",
+                "read",
+                "Check this link",
+            ])
+            .env("AIANDI_CONFIG", &config)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert!(stdout(&output).contains("Captured to vault: 00-inbox/capture-"));
+
+        let captures: Vec<_> = std::fs::read_dir(vault.join("00-inbox"))
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_file())
+            .collect();
+        assert_eq!(captures.len(), 1);
+        let content = std::fs::read_to_string(captures[0].path()).unwrap();
+        assert!(content.contains("tags: [read]"));
+        assert!(content.contains("source: cli"));
+    }
+
+    #[test]
+    fn test_inbox_vault_backend_without_vault_fails() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let output = Command::new(env!("CARGO_BIN_EXE_aiandi"))
+            .args(["inbox", "--backend", "vault", "Nowhere to go"])
+            .env("AIANDI_CONFIG", temp.path().join("config.toml"))
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert!(stderr(&output).contains("No vault configured"));
+    }
+
+    #[test]
+    fn test_route_dry_run_prints_plan() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let capture = temp
+            .path()
+            .join("00-inbox/capture-2026-01-01-090000-link.md");
+        std::fs::create_dir_all(capture.parent().unwrap()).unwrap();
+        std::fs::write(&capture, "https://example.com/post\n").unwrap();
+
+        let output = aiandi(&[
+            "route",
+            "--from",
+            "vault",
+            "--dry-run",
+            "--vault",
+            temp.path().to_str().unwrap(),
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        let out = stdout(&output);
+        assert!(out.contains("→ 01-projects/Recursive-Loop.md (bare-link)"));
+        assert!(
+            out.contains("- [ ] Review: example.com/post - <https://example.com/post> #someday")
+        );
+        assert!(capture.exists());
+        assert!(!temp.path().join("01-projects").exists());
+    }
+
     #[test]
     #[ignore] // Requires TaskWarrior
     fn test_inbox_captures_task() {
@@ -195,3 +283,144 @@ mod inbox_command {
         }
     }
 }
+
+mod transmission_command {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn run(dir: &std::path::Path, args: &[&str]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_aiandi"))
+            .arg("transmission")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("Failed to execute aiandi transmission")
+    }
+
+    #[test]
+    fn test_new_continues_thread() {
+        let temp = TempDir::new().unwrap();
+        let new = ["new", "--type", "report", "--thread", "infra", "-d"];
+
+        let first = run(temp.path(), &[&new[..], &["Kickoff"]].concat());
+        assert!(first.status.success(), "{}", stderr(&first));
+        let second = run(temp.path(), &[&new[..], &["Follow Up"]].concat());
+        assert!(second.status.success(), "{}", stderr(&second));
+        assert!(stdout(&second).contains("position 2"));
+
+        let outbox = temp.path().join("governance/sessions/outbox");
+        assert!(outbox
+            .join("Transmission_infra_Report_Follow-Up.xml")
+            .exists());
+
+        // Same description again would overwrite
+        let again = run(temp.path(), &[&new[..], &["Kickoff"]].concat());
+        assert!(!again.status.success());
+
+        assert!(run(temp.path(), &["validate"]).status.success());
+
+        let thread = run(temp.path(), &["thread", "infra"]);
+        let out = stdout(&thread);
+        assert!(out.contains("1. [report]"));
+        assert!(out.contains("2. [report]"));
+        assert!(!out.contains("Gap"));
+    }
+
+    #[test]
+    fn test_validate_fails_on_invalid() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("bad.xml"),
+            r#"<transmission type="query"><metadata/></transmission>"#,
+        )
+        .unwrap();
+
+        let output = run(temp.path(), &["validate", "bad.xml"]);
+        assert!(!output.status.success());
+        assert!(stdout(&output).contains("content/question"));
+    }
+}
+
+mod handoff_command {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn run(dir: &std::path::Path, args: &[&str]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_aiandi"))
+            .arg("handoff")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("Failed to execute aiandi handoff")
+    }
+
+    #[test]
+    fn test_write_read_ack() {
+        let temp = TempDir::new().unwrap();
+        let write = run(
+            temp.path(),
+            &[
+                "write",
+                "API change",
+                "--type",
+                "warning",
+                "--body",
+                "v1 is gone",
+            ],
+        );
+        assert!(write.status.success(), "{}", stderr(&write));
+        assert!(temp.path().join("inbox/warning-api-change.md").exists());
+
+        let read = stdout(&run(temp.path(), &["read"]));
+        assert!(read.contains("1 item pending (1 warning)"));
+        assert!(read.contains("v1 is gone"));
+
+        assert!(run(temp.path(), &["ack", "--all"]).status.success());
+        let read = stdout(&run(temp.path(), &["read"]));
+        assert!(read.contains("Inbox: empty"));
+    }
+}
+
+mod vault_command {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_vault_init_records_config() {
+        let temp = TempDir::new().unwrap();
+        let config = temp.path().join("config.toml");
+        let output = Command::new(env!("CARGO_BIN_EXE_aiandi"))
+            .args(["vault", "init", "vault"])
+            .env("AIANDI_CONFIG", &config)
+            .current_dir(temp.path())
+            .output()
+            .expect("Failed to execute aiandi vault init");
+
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert!(temp.path().join("vault/00-inbox").is_dir());
+        assert!(temp.path().join("vault/01-projects").is_dir());
+        let recorded = std::fs::read_to_string(&config).unwrap();
+        assert!(recorded.contains("[vault]"));
+    }
+}
+
+mod plan_command {
+    use super::*;
+
+    #[test]
+    fn test_plan_rejects_unknown_phase() {
+        let output = aiandi(&["plan", "--phase", "brunch"]);
+        assert!(!output.status.success());
+        assert!(stderr(&output).contains("morning"));
+    }
+
+    #[test]
+    #[ignore] // Requires TaskWarrior
+    fn test_plan_prints_orientation() {
+        let output = aiandi(&["plan", "--phase", "evening"]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        let out = stdout(&output);
+        assert!(out.starts_with("# Plan: Evening"));
+        assert!(out.contains("## Tomorrow's seeds"));
+    }
+}
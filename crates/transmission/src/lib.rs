@@ -0,0 +1,37 @@
+//! aiandi-transmission: typed model, parser and validator for transmissions
+//!
+//! Transmissions are the XML letters agents exchange through inbox and
+//! outbox directories. Two layouts are in use:
+//!
+//! - canonical (`governance/canon/transmission-protocol.md`):
+//!   `<metadata>`/`<context>`/`<content>`, `schema="transmission-v1"`
+//! - skill (`assets/skills/transmission/SKILL.md`):
+//!   `<header>`/`<payload>`/`<closing>`, priority on the root element
+//!
+//! [`parse`] reads both into a [`Transmission`],
+//! [`Transmission::validate`] checks what each type requires, and
+//! [`Transmission::to_xml`] writes the canonical layout.
+//!
+//! ```
+//! let t = aiandi_transmission::parse(
+//!     r#"<transmission type="query" schema="transmission-v1">
+//!          <metadata>
+//!            <from>Governance</from>
+//!            <to>Ops Agent</to>
+//!            <date>2026-01-15</date>
+//!          </metadata>
+//!          <content><question id="1">Is the inbox empty?</question></content>
+//!        </transmission>"#,
+//! )
+//! .unwrap();
+//! assert!(t.validate().is_ok());
+//! ```
+
+mod model;
+mod parse;
+mod validate;
+mod write;
+
+pub use model::{Element, Node, Priority, Shape, Thread, Transmission, TransmissionType};
+pub use parse::{parse, ParseError};
+pub use validate::{ValidationError, ValidationErrors};
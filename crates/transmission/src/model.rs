@@ -0,0 +1,311 @@
+//! The transmission model
+
+use std::fmt;
+use std::str::FromStr;
+
+/// What a transmission is for (the root `type` attribute)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransmissionType {
+    Query,
+    Instruction,
+    Report,
+    Response,
+    Proposal,
+    Decision,
+    Finding,
+    ResearchRequest,
+    BootArtifact,
+}
+
+impl TransmissionType {
+    pub const ALL: [TransmissionType; 9] = [
+        TransmissionType::Query,
+        TransmissionType::Instruction,
+        TransmissionType::Report,
+        TransmissionType::Response,
+        TransmissionType::Proposal,
+        TransmissionType::Decision,
+        TransmissionType::Finding,
+        TransmissionType::ResearchRequest,
+        TransmissionType::BootArtifact,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TransmissionType::Query => "query",
+            TransmissionType::Instruction => "instruction",
+            TransmissionType::Report => "report",
+            TransmissionType::Response => "response",
+            TransmissionType::Proposal => "proposal",
+            TransmissionType::Decision => "decision",
+            TransmissionType::Finding => "finding",
+            TransmissionType::ResearchRequest => "research-request",
+            TransmissionType::BootArtifact => "boot-artifact",
+        }
+    }
+
+    /// The `[Type]` segment of a transmission file name (`Report`,
+    /// `Research-Request`)
+    pub fn file_label(self) -> String {
+        self.as_str()
+            .split('-')
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+}
+
+impl fmt::Display for TransmissionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TransmissionType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|t| t.as_str() == s)
+            .ok_or_else(|| format!("unknown transmission type '{}'", s))
+    }
+}
+
+/// How urgent a transmission is
+///
+/// The canon uses `standard` where the skill uses `medium`; both parse to
+/// [`Priority::Standard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Low,
+    Standard,
+    High,
+    Critical,
+}
+
+impl Priority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Standard => "standard",
+            Priority::High => "high",
+            Priority::Critical => "critical",
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "low" => Ok(Priority::Low),
+            "standard" | "medium" => Ok(Priority::Standard),
+            "high" => Ok(Priority::High),
+            "critical" => Ok(Priority::Critical),
+            other => Err(format!("unknown priority '{}'", other)),
+        }
+    }
+}
+
+/// Which of the two observed layouts a transmission was written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    /// `<metadata>`/`<context>`/`<content>`, `schema="transmission-v1"`
+    /// (`governance/canon/transmission-protocol.md`)
+    Canonical,
+    /// `<header>`/`<payload>`/`<closing>` (the transmission skill)
+    Skill,
+}
+
+/// Where a transmission sits in its thread
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Thread {
+    pub id: String,
+    pub position: Option<u32>,
+    /// Id of the transmission (or position) this one answers
+    pub in_reply_to: Option<String>,
+}
+
+/// An XML element kept as written, for the free-form sections
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Element(Element),
+    /// Text with each line trimmed and blank lines at either end dropped
+    Text(String),
+}
+
+impl Element {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Add a child element holding only `text`
+    pub fn with_text_child(mut self, name: &str, text: &str) -> Self {
+        let mut child = Element::new(name);
+        child.children.push(Node::Text(text.to_string()));
+        self.children.push(Node::Element(child));
+        self
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Child elements, in order
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// Whether this element or any below it is called `name`
+    pub fn contains(&self, name: &str) -> bool {
+        self.name == name || self.elements().any(|e| e.contains(name))
+    }
+
+    /// All text below this element, lines joined with `\n`
+    pub fn text(&self) -> String {
+        let mut parts = Vec::new();
+        for node in &self.children {
+            match node {
+                Node::Text(text) => parts.push(text.clone()),
+                Node::Element(element) => parts.push(element.text()),
+            }
+        }
+        parts.retain(|p| !p.is_empty());
+        parts.join("\n")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+/// A parsed transmission
+///
+/// Header fields are typed; the body sections (`context`, the payload,
+/// `response-spec`, `closing`) are kept as element trees.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transmission {
+    pub id: Option<String>,
+    pub kind: TransmissionType,
+    pub priority: Option<Priority>,
+    /// Why the priority was chosen (canonical `<priority><reason>`)
+    pub priority_reason: Option<String>,
+    /// Skill-style category, e.g. `implementation-report`
+    pub classification: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// `YYYY-MM-DD`
+    pub date: Option<String>,
+    pub thread: Option<Thread>,
+    pub context: Option<Element>,
+    /// `<content>` (canonical) or `<payload>` (skill)
+    pub payload: Element,
+    /// Other top-level sections, e.g. a boot artifact's `<identity>` and
+    /// `<mission>`
+    pub extra: Vec<Element>,
+    pub response_spec: Option<Element>,
+    pub closing: Option<Element>,
+    /// The layout this was parsed from; serializing always writes canonical
+    pub shape: Shape,
+}
+
+impl Transmission {
+    /// An empty transmission of `kind`, to be filled in
+    pub fn new(kind: TransmissionType) -> Self {
+        Self {
+            id: None,
+            kind,
+            priority: None,
+            priority_reason: None,
+            classification: None,
+            from: None,
+            to: None,
+            date: None,
+            thread: None,
+            context: None,
+            payload: Element::new("content"),
+            extra: Vec::new(),
+            response_spec: None,
+            closing: None,
+            shape: Shape::Canonical,
+        }
+    }
+
+    /// `Transmission_[Thread-ID]_[Type]_[Description].xml`
+    ///
+    /// Whitespace in the description becomes `-`; `None` without a thread.
+    pub fn file_name(&self, description: &str) -> Option<String> {
+        let thread = self.thread.as_ref()?;
+        Some(format!(
+            "Transmission_{}_{}_{}.xml",
+            thread.id,
+            self.kind.file_label(),
+            description.split_whitespace().collect::<Vec<_>>().join("-")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_and_priority_names() {
+        assert_eq!(
+            "Research-Request".parse::<TransmissionType>(),
+            Ok(TransmissionType::ResearchRequest)
+        );
+        assert_eq!(
+            TransmissionType::ResearchRequest.file_label(),
+            "Research-Request"
+        );
+        assert!("memo".parse::<TransmissionType>().is_err());
+        assert_eq!("medium".parse::<Priority>(), Ok(Priority::Standard));
+    }
+
+    #[test]
+    fn test_file_name() {
+        let mut t = Transmission::new(TransmissionType::Report);
+        assert_eq!(t.file_name("Implementation"), None);
+        t.thread = Some(Thread {
+            id: "secrets-audit".to_string(),
+            position: Some(2),
+            in_reply_to: None,
+        });
+        assert_eq!(
+            t.file_name("Credential Exposure").unwrap(),
+            "Transmission_secrets-audit_Report_Credential-Exposure.xml"
+        );
+    }
+}
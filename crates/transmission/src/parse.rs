@@ -0,0 +1,235 @@
+//! Parsing both observed transmission layouts
+
+use crate::model::{Element, Node, Priority, Shape, Thread, Transmission, TransmissionType};
+
+/// Why a document could not be read as a transmission
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    #[error("Not well-formed XML: {0}")]
+    Xml(#[from] roxmltree::Error),
+    #[error("Root element is <{0}>, expected <transmission>")]
+    NotATransmission(String),
+    #[error("<transmission> has no type attribute")]
+    MissingType,
+    #[error("Invalid transmission: {0}")]
+    Invalid(String),
+    #[error("Neither <metadata> nor <header> found; unknown transmission layout")]
+    UnknownShape,
+    #[error("Thread position '{0}' is not a positive number")]
+    InvalidPosition(String),
+}
+
+/// Parse a transmission written in either layout
+pub fn parse(xml: &str) -> Result<Transmission, ParseError> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let root = convert(doc.root_element());
+    if root.name != "transmission" {
+        return Err(ParseError::NotATransmission(root.name));
+    }
+
+    let kind: TransmissionType = root
+        .attribute("type")
+        .ok_or(ParseError::MissingType)?
+        .parse()
+        .map_err(ParseError::Invalid)?;
+
+    let child = |name: &str| root.elements().find(|e| e.name == name).cloned();
+    let (shape, header) = match (child("metadata"), child("header")) {
+        (Some(metadata), _) => (Shape::Canonical, metadata),
+        (None, Some(header)) => (Shape::Skill, header),
+        (None, None) => return Err(ParseError::UnknownShape),
+    };
+    let field = |name: &str| {
+        header
+            .elements()
+            .find(|e| e.name == name)
+            .map(|e| e.text())
+            .filter(|t| !t.is_empty())
+    };
+
+    // Canonical: <priority level=".."><reason>..</reason></priority> in the
+    // metadata; skill: a priority attribute on the root
+    let priority_element = header.elements().find(|e| e.name == "priority");
+    let priority = priority_element
+        .and_then(|p| p.attribute("level"))
+        .or_else(|| root.attribute("priority"))
+        .map(str::parse::<Priority>)
+        .transpose()
+        .map_err(ParseError::Invalid)?;
+    let priority_reason = priority_element
+        .and_then(|p| p.elements().find(|e| e.name == "reason"))
+        .map(|r| r.text())
+        .filter(|t| !t.is_empty());
+
+    let thread = header
+        .elements()
+        .find(|e| e.name == "thread")
+        .map(parse_thread)
+        .transpose()?;
+
+    let payload = match shape {
+        Shape::Canonical => child("content"),
+        Shape::Skill => child("payload").or_else(|| child("content")),
+    }
+    .unwrap_or_else(|| Element::new("content"));
+
+    const KNOWN: [&str; 7] = [
+        "metadata",
+        "header",
+        "context",
+        "content",
+        "payload",
+        "response-spec",
+        "closing",
+    ];
+    let extra = root
+        .elements()
+        .filter(|e| !KNOWN.contains(&e.name.as_str()))
+        .cloned()
+        .collect();
+
+    Ok(Transmission {
+        id: root.attribute("id").map(String::from),
+        kind,
+        priority,
+        priority_reason,
+        classification: field("classification"),
+        from: field("from"),
+        to: field("to"),
+        date: field("date"),
+        thread,
+        context: child("context"),
+        payload,
+        extra,
+        response_spec: child("response-spec"),
+        closing: child("closing"),
+        shape,
+    })
+}
+
+/// `<thread id="..">` (canonical) or `<thread><id>..</id>` (skill)
+fn parse_thread(thread: &Element) -> Result<Thread, ParseError> {
+    let field = |name: &str| {
+        thread
+            .elements()
+            .find(|e| e.name == name)
+            .map(|e| e.text())
+            .filter(|t| !t.is_empty())
+    };
+
+    let position = field("position")
+        .map(|p| {
+            p.parse::<u32>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or(ParseError::InvalidPosition(p))
+        })
+        .transpose()?;
+
+    Ok(Thread {
+        id: thread
+            .attribute("id")
+            .map(String::from)
+            .or_else(|| field("id"))
+            .unwrap_or_default(),
+        position,
+        in_reply_to: field("in-reply-to").filter(|r| r != "null"),
+    })
+}
+
+fn convert(node: roxmltree::Node) -> Element {
+    let mut element = Element::new(node.tag_name().name());
+    element.attributes = node
+        .attributes()
+        .map(|a| (a.name().to_string(), a.value().to_string()))
+        .collect();
+
+    for child in node.children() {
+        if child.is_element() {
+            element.children.push(Node::Element(convert(child)));
+        } else if child.is_text() {
+            let text = normalize_text(child.text().unwrap_or_default());
+            if !text.is_empty() {
+                element.children.push(Node::Text(text));
+            }
+        }
+    }
+    element
+}
+
+/// Trim every line and drop blank lines at either end, so indentation
+/// doesn't survive into the model
+fn normalize_text(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let start = lines.iter().position(|l| !l.is_empty());
+    let end = lines.iter().rposition(|l| !l.is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].join("\n"),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_skill_shape() {
+        let xml = r#"<transmission type="report" priority="medium">
+  <header>
+    <classification>implementation-report</classification>
+    <from>aiandi Agent</from>
+    <to>Governance Committee</to>
+    <date>2026-01-15</date>
+    <thread>
+      <id>aiandi-transformation</id>
+      <position>3</position>
+      <in-reply-to>2</in-reply-to>
+    </thread>
+  </header>
+  <payload>
+    <summary>Done</summary>
+  </payload>
+</transmission>"#;
+
+        let t = parse(xml).unwrap();
+        assert_eq!(t.shape, Shape::Skill);
+        assert_eq!(t.priority, Some(Priority::Standard));
+        assert_eq!(t.classification.as_deref(), Some("implementation-report"));
+        let thread = t.thread.unwrap();
+        assert_eq!(thread.id, "aiandi-transformation");
+        assert_eq!(thread.position, Some(3));
+        assert_eq!(thread.in_reply_to.as_deref(), Some("2"));
+        assert_eq!(t.payload.name, "payload");
+        assert_eq!(t.payload.text(), "Done");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(parse("<transmission"), Err(ParseError::Xml(_))));
+        assert!(matches!(
+            parse("<report type=\"report\"/>"),
+            Err(ParseError::NotATransmission(_))
+        ));
+        assert!(matches!(
+            parse("<transmission><header/></transmission>"),
+            Err(ParseError::MissingType)
+        ));
+        assert!(matches!(
+            parse("<transmission type=\"report\"><body/></transmission>"),
+            Err(ParseError::UnknownShape)
+        ));
+        assert!(matches!(
+            parse(
+                "<transmission type=\"report\"><header><thread><position>x</position></thread></header></transmission>"
+            ),
+            Err(ParseError::InvalidPosition(_))
+        ));
+    }
+
+    #[test]
+    fn test_normalize_text() {
+        assert_eq!(normalize_text("\n    one\n      two  \n\n"), "one\ntwo");
+        assert_eq!(normalize_text("  \n "), "");
+    }
+}
@@ -0,0 +1,216 @@
+//! Required fields per transmission type
+//!
+//! Every transmission needs a sender, a recipient and a date; a thread,
+//! if given, needs an id and a position. On top of that:
+//!
+//! | Type | Payload must contain |
+//! |------|----------------------|
+//! | instruction | a `task`, and `success-criteria` (or per-task `verification`) |
+//! | report | something: findings, deliverable status, a summary |
+//! | proposal | a `problem` and a `proposal` |
+//! | finding | a `finding` |
+//! | query | a `question` |
+//!
+//! The other types only need the common fields.
+
+use crate::model::{Transmission, TransmissionType};
+use std::fmt;
+
+/// One missing or malformed field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Where, e.g. `metadata/date` or `content/task`
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Everything wrong with a transmission
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub struct ValidationErrors(pub Vec<ValidationError>);
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self.0.iter().map(|e| e.to_string()).collect();
+        f.write_str(&lines.join("\n"))
+    }
+}
+
+impl Transmission {
+    /// Check the fields this transmission's type requires
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = Vec::new();
+        let mut missing = |field: &str, message: &str| {
+            errors.push(ValidationError {
+                field: field.to_string(),
+                message: message.to_string(),
+            })
+        };
+
+        if self.from.is_none() {
+            missing("metadata/from", "sender is required");
+        }
+        if self.to.is_none() {
+            missing("metadata/to", "recipient is required");
+        }
+        match &self.date {
+            None => missing("metadata/date", "date is required"),
+            Some(date) if !is_iso_date(date) => missing("metadata/date", "date must be YYYY-MM-DD"),
+            Some(_) => {}
+        }
+        if let Some(thread) = &self.thread {
+            if thread.id.trim().is_empty() {
+                missing("metadata/thread", "thread id is required");
+            }
+            if thread.position.is_none() {
+                missing("metadata/thread/position", "thread position is required");
+            }
+        }
+
+        let payload = &self.payload;
+        match self.kind {
+            TransmissionType::Instruction => {
+                if !payload.contains("task") {
+                    missing("content/task", "an instruction needs at least one task");
+                }
+                let criteria = payload.contains("success-criteria")
+                    || payload.contains("verification")
+                    || self
+                        .response_spec
+                        .as_ref()
+                        .is_some_and(|r| r.contains("success-criteria"));
+                if !criteria {
+                    missing(
+                        "content/success-criteria",
+                        "an instruction needs success criteria or verification steps",
+                    );
+                }
+            }
+            TransmissionType::Report => {
+                if payload.is_empty() {
+                    missing("content", "a report needs findings or status");
+                }
+            }
+            TransmissionType::Proposal => {
+                if !payload.contains("problem") {
+                    missing("content/problem", "a proposal states the problem");
+                }
+                if !payload.contains("proposal") {
+                    missing("content/proposal", "a proposal needs the proposed approach");
+                }
+            }
+            TransmissionType::Finding => {
+                if !payload.contains("finding") {
+                    missing("content/finding", "at least one finding is required");
+                }
+            }
+            TransmissionType::Query => {
+                if !payload.contains("question") {
+                    missing("content/question", "a query needs at least one question");
+                }
+            }
+            TransmissionType::Response
+            | TransmissionType::Decision
+            | TransmissionType::ResearchRequest
+            | TransmissionType::BootArtifact => {}
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors(errors))
+        }
+    }
+}
+
+fn is_iso_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    let digits = |s: &str, n: usize| s.len() == n && s.bytes().all(|b| b.is_ascii_digit());
+    parts.len() == 3
+        && digits(parts[0], 4)
+        && digits(parts[1], 2)
+        && digits(parts[2], 2)
+        && (1..=12).contains(&parts[1].parse::<u32>().unwrap_or(0))
+        && (1..=31).contains(&parts[2].parse::<u32>().unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Element, Node, Thread};
+
+    fn transmission(kind: TransmissionType) -> Transmission {
+        let mut t = Transmission::new(kind);
+        t.from = Some("Governance".to_string());
+        t.to = Some("Ops Agent".to_string());
+        t.date = Some("2026-01-15".to_string());
+        t
+    }
+
+    fn fields(t: &Transmission) -> Vec<String> {
+        match t.validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.0.into_iter().map(|e| e.field).collect(),
+        }
+    }
+
+    #[test]
+    fn test_common_fields() {
+        let mut t = Transmission::new(TransmissionType::Decision);
+        t.date = Some("15 Jan 2026".to_string());
+        t.thread = Some(Thread {
+            id: "x".to_string(),
+            position: None,
+            in_reply_to: None,
+        });
+        assert_eq!(
+            fields(&t),
+            vec![
+                "metadata/from",
+                "metadata/to",
+                "metadata/date",
+                "metadata/thread/position"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_required_per_type() {
+        let instruction = transmission(TransmissionType::Instruction);
+        assert_eq!(
+            fields(&instruction),
+            vec!["content/task", "content/success-criteria"]
+        );
+
+        let mut instruction = instruction;
+        let mut task = Element::new("task").with_text_child("verification", "cargo test");
+        task.children.push(Node::Text("Ship it".to_string()));
+        instruction.payload.children.push(Node::Element(task));
+        assert!(instruction.validate().is_ok());
+
+        assert_eq!(
+            fields(&transmission(TransmissionType::Report)),
+            vec!["content"]
+        );
+        assert_eq!(
+            fields(&transmission(TransmissionType::Proposal)),
+            vec!["content/problem", "content/proposal"]
+        );
+        assert_eq!(
+            fields(&transmission(TransmissionType::Finding)),
+            vec!["content/finding"]
+        );
+        assert_eq!(
+            fields(&transmission(TransmissionType::Query)),
+            vec!["content/question"]
+        );
+        assert!(transmission(TransmissionType::BootArtifact)
+            .validate()
+            .is_ok());
+    }
+}
@@ -0,0 +1,162 @@
+//! Serializing to the canonical layout
+
+use crate::model::{Element, Node, Transmission};
+use std::fmt::Write;
+
+const SCHEMA: &str = "transmission-v1";
+
+impl Transmission {
+    /// The transmission as canonical XML (`<metadata>`/`<context>`/`<content>`,
+    /// `schema="transmission-v1"`), whatever layout it was parsed from
+    ///
+    /// A skill-layout `<payload>` is written as `<content>`, and `medium`
+    /// priority as `standard`.
+    pub fn to_xml(&self) -> String {
+        let mut x = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<transmission");
+        if let Some(id) = &self.id {
+            let _ = write!(x, "\n  id=\"{}\"", escape(id));
+        }
+        let _ = write!(
+            x,
+            "\n  schema=\"{}\"\n  type=\"{}\">\n\n",
+            SCHEMA, self.kind
+        );
+
+        let mut metadata = Element::new("metadata");
+        for (name, value) in [
+            ("classification", &self.classification),
+            ("from", &self.from),
+            ("to", &self.to),
+            ("date", &self.date),
+        ] {
+            if let Some(value) = value {
+                metadata = metadata.with_text_child(name, value);
+            }
+        }
+        if let Some(thread) = &self.thread {
+            let mut element = Element::new("thread");
+            element
+                .attributes
+                .push(("id".to_string(), thread.id.clone()));
+            if let Some(position) = thread.position {
+                element = element.with_text_child("position", &position.to_string());
+            }
+            element = element.with_text_child(
+                "in-reply-to",
+                thread.in_reply_to.as_deref().unwrap_or("null"),
+            );
+            metadata.children.push(Node::Element(element));
+        }
+        if let Some(priority) = self.priority {
+            let mut element = Element::new("priority");
+            element
+                .attributes
+                .push(("level".to_string(), priority.to_string()));
+            if let Some(reason) = &self.priority_reason {
+                element = element.with_text_child("reason", reason);
+            }
+            metadata.children.push(Node::Element(element));
+        }
+
+        let mut content = self.payload.clone();
+        content.name = "content".to_string();
+
+        let sections = [Some(&metadata), self.context.as_ref(), Some(&content)]
+            .into_iter()
+            .chain(self.extra.iter().map(Some))
+            .chain([self.response_spec.as_ref(), self.closing.as_ref()])
+            .flatten();
+
+        let mut first = true;
+        for section in sections {
+            if !first {
+                x.push('\n');
+            }
+            first = false;
+            write_element(&mut x, section, 1);
+        }
+        x.push_str("</transmission>\n");
+        x
+    }
+}
+
+fn write_element(x: &mut String, element: &Element, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let _ = write!(x, "{}<{}", indent, element.name);
+    for (name, value) in &element.attributes {
+        let _ = write!(x, " {}=\"{}\"", name, escape(value));
+    }
+
+    match element.children.as_slice() {
+        [] => x.push_str("/>\n"),
+        [Node::Text(text)] if !text.contains('\n') => {
+            let _ = writeln!(x, ">{}</{}>", escape(text), element.name);
+        }
+        children => {
+            x.push_str(">\n");
+            for child in children {
+                match child {
+                    Node::Element(child) => write_element(x, child, depth + 1),
+                    Node::Text(text) => {
+                        for line in text.lines() {
+                            if line.is_empty() {
+                                x.push('\n');
+                            } else {
+                                let _ = writeln!(x, "{}  {}", indent, escape(line));
+                            }
+                        }
+                    }
+                }
+            }
+            let _ = writeln!(x, "{}</{}>", indent, element.name);
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{Priority, Shape, TransmissionType};
+    use crate::parse;
+
+    #[test]
+    fn test_skill_shape_written_canonical() {
+        let xml = r#"<transmission type="finding" priority="medium">
+  <header>
+    <from>Auditor</from>
+    <to>Governance</to>
+    <date>2026-01-15</date>
+    <thread><id>secrets-audit</id><position>1</position></thread>
+  </header>
+  <payload>
+    <finding id="F-001" severity="critical">
+      <description>Token in &lt;config&gt;
+        committed to git</description>
+    </finding>
+  </payload>
+</transmission>"#;
+
+        let parsed = parse(xml).unwrap();
+        let canonical = parsed.to_xml();
+
+        assert!(canonical.contains("schema=\"transmission-v1\"\n  type=\"finding\">"));
+        assert!(canonical.contains("<thread id=\"secrets-audit\">"));
+        assert!(canonical.contains("<in-reply-to>null</in-reply-to>"));
+        assert!(canonical.contains("<priority level=\"standard\"/>"));
+        assert!(canonical.contains("  <content>\n    <finding id=\"F-001\""));
+        assert!(canonical.contains("Token in &lt;config&gt;\n"));
+
+        let reparsed = parse(&canonical).unwrap();
+        assert_eq!(reparsed.shape, Shape::Canonical);
+        assert_eq!(reparsed.kind, TransmissionType::Finding);
+        assert_eq!(reparsed.priority, Some(Priority::Standard));
+        assert_eq!(reparsed.thread, parsed.thread);
+        assert_eq!(reparsed.payload.children, parsed.payload.children);
+    }
+}
@@ -0,0 +1,51 @@
+//! Parse the transmissions committed under governance/
+
+use aiandi_transmission::{parse, Priority, Shape, TransmissionType};
+use std::path::PathBuf;
+
+fn outbox(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../governance/sessions/outbox")
+        .join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+#[test]
+fn test_infrastructure_build_report() {
+    let t = parse(&outbox(
+        "Report_Governance_Infrastructure_Build_2026_01_11.xml",
+    ))
+    .unwrap();
+
+    assert_eq!(t.shape, Shape::Canonical);
+    assert_eq!(t.kind, TransmissionType::Report);
+    assert_eq!(
+        t.id.as_deref(),
+        Some("gov-2026-01-11-infrastructure-build-report")
+    );
+    assert_eq!(t.priority, Some(Priority::High));
+    assert_eq!(t.date.as_deref(), Some("2026-01-11"));
+
+    let thread = t.thread.as_ref().unwrap();
+    assert_eq!(thread.id, "governance-infrastructure-build");
+    assert_eq!(thread.position, Some(2));
+    assert_eq!(
+        thread.in_reply_to.as_deref(),
+        Some("gov-2026-01-11-governance-infrastructure")
+    );
+    assert!(t.payload.contains("deliverable-status"));
+    assert!(t.validate().is_ok());
+}
+
+#[test]
+fn test_committed_transmissions_round_trip() {
+    for name in [
+        "Report_Governance_Infrastructure_Build_2026_01_11.xml",
+        "ops-2026-01-15-monorepo-structure-response.xml",
+    ] {
+        let original = parse(&outbox(name)).unwrap();
+        let written = original.to_xml();
+        let reparsed = parse(&written).unwrap_or_else(|e| panic!("{}: {}\n{}", name, e, written));
+        assert_eq!(reparsed, original, "{}", name);
+    }
+}
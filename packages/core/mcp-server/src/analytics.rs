@@ -0,0 +1,443 @@
+//! Mode analytics for the weekly review
+//!
+//! Built from the history log (see [`crate::history`]) plus the live
+//! attention stack:
+//!
+//! - time per mode per day and per ISO week
+//! - per session: time in modes and items handled
+//! - median time from an item being surfaced to it being handled
+//! - hot and waiting items that have kept their status longer than a
+//!   threshold
+//!
+//! Days and weeks are UTC, like every timestamp the server records. A stint
+//! that crosses midnight is split between the two days.
+
+use crate::history::HistoryEvent;
+use crate::state::{AttentionItem, AttentionStatus, Mode};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// What a report covers
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    /// Start of the window
+    pub since: DateTime<Utc>,
+    /// End of the window (usually now)
+    pub until: DateTime<Utc>,
+    /// Open items in the same status for this long are stale
+    pub stale_after: Duration,
+}
+
+/// Seconds per mode over one day or week
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PeriodTotals {
+    /// `2026-10-19` for a day, `2026-W42` for a week
+    pub period: String,
+    pub total_seconds: i64,
+    pub modes: BTreeMap<Mode, i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionSummary {
+    pub started_at: DateTime<Utc>,
+    /// Time in any mode within the window
+    pub seconds: i64,
+    pub handled: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StaleItem {
+    pub id: String,
+    pub description: String,
+    pub status: AttentionStatus,
+    pub status_since: DateTime<Utc>,
+    pub stale_seconds: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub days: Vec<PeriodTotals>,
+    pub weeks: Vec<PeriodTotals>,
+    pub sessions: Vec<SessionSummary>,
+    /// Items handled within the window
+    pub handled: usize,
+    /// Median surfaced-to-handled time of those items
+    pub median_seconds_to_handle: Option<i64>,
+    pub stale_after_seconds: i64,
+    /// Stale items, longest untouched first
+    pub stale: Vec<StaleItem>,
+}
+
+impl Report {
+    /// Build a report from history `events` and the current `attention` stack
+    ///
+    /// The running session's open stint is not in the log yet; include it in
+    /// `events` (see [`HistoryLog::mode_event`](crate::history::HistoryLog::mode_event)).
+    pub fn build(
+        events: &[HistoryEvent],
+        attention: &[AttentionItem],
+        options: &ReportOptions,
+    ) -> Self {
+        let mut days: BTreeMap<NaiveDate, BTreeMap<Mode, i64>> = BTreeMap::new();
+        let mut sessions: BTreeMap<DateTime<Utc>, SessionSummary> = BTreeMap::new();
+        let mut to_handle = Vec::new();
+
+        for event in events {
+            match event {
+                HistoryEvent::Mode {
+                    session,
+                    mode,
+                    entered_at,
+                    exited_at,
+                } => {
+                    let start = (*entered_at).max(options.since);
+                    let end = (*exited_at).min(options.until);
+                    if start >= end {
+                        continue;
+                    }
+                    for (day, seconds) in split_by_day(start, end) {
+                        *days
+                            .entry(day)
+                            .or_default()
+                            .entry(mode.clone())
+                            .or_default() += seconds;
+                    }
+                    session_entry(&mut sessions, *session).seconds += (end - start).num_seconds();
+                }
+                HistoryEvent::Handled {
+                    session,
+                    surfaced_at,
+                    handled_at,
+                    ..
+                } => {
+                    if *handled_at < options.since || *handled_at > options.until {
+                        continue;
+                    }
+                    session_entry(&mut sessions, *session).handled += 1;
+                    to_handle.push((*handled_at - *surfaced_at).num_seconds().max(0));
+                }
+            }
+        }
+
+        let mut weeks: BTreeMap<(i32, u32), BTreeMap<Mode, i64>> = BTreeMap::new();
+        for (day, modes) in &days {
+            let week = day.iso_week();
+            let totals = weeks.entry((week.year(), week.week())).or_default();
+            for (mode, seconds) in modes {
+                *totals.entry(mode.clone()).or_default() += seconds;
+            }
+        }
+
+        let mut stale: Vec<StaleItem> = attention
+            .iter()
+            .filter(|i| i.status != AttentionStatus::Handled)
+            .map(|i| StaleItem {
+                id: i.id.clone(),
+                description: i.description.clone(),
+                status: i.status.clone(),
+                status_since: i.status_changed_at(),
+                stale_seconds: (options.until - i.status_changed_at()).num_seconds(),
+            })
+            .filter(|i| i.stale_seconds >= options.stale_after.num_seconds())
+            .collect();
+        stale.sort_by_key(|i| std::cmp::Reverse(i.stale_seconds));
+
+        Self {
+            since: options.since,
+            until: options.until,
+            days: days
+                .into_iter()
+                .map(|(day, modes)| totals(day.to_string(), modes))
+                .collect(),
+            weeks: weeks
+                .into_iter()
+                .map(|((year, week), modes)| totals(format!("{}-W{:02}", year, week), modes))
+                .collect(),
+            handled: to_handle.len(),
+            median_seconds_to_handle: median(&mut to_handle),
+            sessions: sessions.into_values().collect(),
+            stale_after_seconds: options.stale_after.num_seconds(),
+            stale,
+        }
+    }
+
+    /// The report as Markdown, for pasting into the weekly review
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "# Mode Report: {} to {}\n",
+            self.since.format("%Y-%m-%d"),
+            self.until.format("%Y-%m-%d")
+        );
+
+        out.push_str("## Time by Mode\n\n");
+        if self.days.is_empty() {
+            out.push_str("No mode history in this period.\n\n");
+        } else {
+            let modes: Vec<&Mode> = {
+                let mut modes: Vec<&Mode> =
+                    self.weeks.iter().flat_map(|w| w.modes.keys()).collect();
+                modes.sort();
+                modes.dedup();
+                modes
+            };
+            totals_table(&mut out, "Week", &self.weeks, &modes);
+            totals_table(&mut out, "Day", &self.days, &modes);
+        }
+
+        out.push_str("## Sessions\n\n");
+        if self.sessions.is_empty() {
+            out.push_str("No sessions in this period.\n\n");
+        } else {
+            out.push_str("| Started | Time | Handled |\n|---|---|---|\n");
+            for session in &self.sessions {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} |",
+                    session.started_at.format("%Y-%m-%d %H:%M"),
+                    format_duration(session.seconds),
+                    session.handled
+                );
+            }
+            out.push('\n');
+        }
+
+        let _ = write!(out, "Handled: {} items", self.handled);
+        if let Some(median) = self.median_seconds_to_handle {
+            let _ = write!(
+                out,
+                ", median {} from surfaced to handled",
+                format_duration(median)
+            );
+        }
+        out.push_str("\n\n");
+
+        let _ = writeln!(
+            out,
+            "## Stale Items (same status for {}+)\n",
+            format_duration(self.stale_after_seconds)
+        );
+        if self.stale.is_empty() {
+            out.push_str("None.\n");
+        }
+        for item in &self.stale {
+            let status = match item.status {
+                AttentionStatus::Hot => "hot",
+                AttentionStatus::Waiting => "waiting",
+                AttentionStatus::Handled => "handled",
+            };
+            let _ = writeln!(
+                out,
+                "- **{}** ({}, {}): {}",
+                item.id,
+                status,
+                format_duration(item.stale_seconds),
+                item.description
+            );
+        }
+        out
+    }
+}
+
+fn session_entry(
+    sessions: &mut BTreeMap<DateTime<Utc>, SessionSummary>,
+    started_at: DateTime<Utc>,
+) -> &mut SessionSummary {
+    sessions.entry(started_at).or_insert(SessionSummary {
+        started_at,
+        seconds: 0,
+        handled: 0,
+    })
+}
+
+fn totals(period: String, modes: BTreeMap<Mode, i64>) -> PeriodTotals {
+    PeriodTotals {
+        period,
+        total_seconds: modes.values().sum(),
+        modes,
+    }
+}
+
+/// Seconds between `start` and `end` falling on each UTC day
+fn split_by_day(start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<(NaiveDate, i64)> {
+    let mut days = Vec::new();
+    let mut cursor = start;
+    while cursor < end {
+        let day = cursor.date_naive();
+        let midnight = day
+            .succ_opt()
+            .map(|next| next.and_time(NaiveTime::MIN).and_utc())
+            .unwrap_or(end);
+        let stop = midnight.min(end);
+        days.push((day, (stop - cursor).num_seconds()));
+        cursor = stop;
+    }
+    days
+}
+
+fn median(values: &mut [i64]) -> Option<i64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2
+    } else {
+        values[mid]
+    })
+}
+
+fn totals_table(out: &mut String, label: &str, rows: &[PeriodTotals], modes: &[&Mode]) {
+    let _ = write!(out, "| {} |", label);
+    for mode in modes {
+        let _ = write!(out, " {} |", mode);
+    }
+    out.push_str(" Total |\n|---|");
+    out.push_str(&"---|".repeat(modes.len() + 1));
+    out.push('\n');
+
+    for row in rows {
+        let _ = write!(out, "| {} |", row.period);
+        for mode in modes {
+            let seconds = row.modes.get(*mode).copied().unwrap_or(0);
+            let _ = write!(out, " {} |", format_duration(seconds));
+        }
+        let _ = writeln!(out, " {} |", format_duration(row.total_seconds));
+    }
+    out.push('\n');
+}
+
+/// `45m`, `3h 05m`, `2d 4h`
+fn format_duration(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Priority;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn stint(session: &str, mode: Mode, from: &str, to: &str) -> HistoryEvent {
+        HistoryEvent::Mode {
+            session: at(session),
+            mode,
+            entered_at: at(from),
+            exited_at: at(to),
+        }
+    }
+
+    fn handled(session: &str, surfaced: &str, handled: &str) -> HistoryEvent {
+        HistoryEvent::Handled {
+            session: at(session),
+            id: "item".to_string(),
+            surfaced_at: at(surfaced),
+            handled_at: at(handled),
+        }
+    }
+
+    fn options() -> ReportOptions {
+        ReportOptions {
+            since: at("2026-10-12T00:00:00Z"),
+            until: at("2026-10-19T12:00:00Z"),
+            stale_after: Duration::hours(72),
+        }
+    }
+
+    #[test]
+    fn test_time_per_mode_splits_days_and_clips_window() {
+        let s1 = "2026-10-11T22:00:00Z";
+        let s2 = "2026-10-18T23:00:00Z";
+        let events = vec![
+            // Starts before the window: only the part after since counts
+            stint(s1, Mode::OPS, s1, "2026-10-12T01:00:00Z"),
+            // Crosses midnight into the next ISO week
+            stint(s2, Mode::DEFAULT, s2, "2026-10-19T01:30:00Z"),
+        ];
+
+        let report = Report::build(&events, &[], &options());
+
+        let days: Vec<(&str, i64)> = report
+            .days
+            .iter()
+            .map(|d| (d.period.as_str(), d.total_seconds))
+            .collect();
+        assert_eq!(
+            days,
+            vec![
+                ("2026-10-12", 3600),
+                ("2026-10-18", 3600),
+                ("2026-10-19", 5400)
+            ]
+        );
+        assert_eq!(report.weeks[0].period, "2026-W42");
+        assert_eq!(report.weeks[0].modes[&Mode::OPS], 3600);
+        assert_eq!(report.weeks[1].period, "2026-W43");
+        assert_eq!(report.sessions.len(), 2);
+        assert_eq!(report.sessions[1].seconds, 9000);
+    }
+
+    #[test]
+    fn test_handled_counts_and_median() {
+        let s = "2026-10-13T09:00:00Z";
+        let events = vec![
+            handled(s, "2026-10-13T09:00:00Z", "2026-10-13T10:00:00Z"),
+            handled(s, "2026-10-13T09:00:00Z", "2026-10-13T12:00:00Z"),
+            handled(s, "2026-10-10T09:00:00Z", "2026-10-13T09:00:00Z"),
+            // Outside the window
+            handled(s, "2026-10-01T09:00:00Z", "2026-10-02T09:00:00Z"),
+        ];
+
+        let report = Report::build(&events, &[], &options());
+        assert_eq!(report.handled, 3);
+        assert_eq!(report.sessions[0].handled, 3);
+        assert_eq!(report.median_seconds_to_handle, Some(3 * 3600));
+    }
+
+    #[test]
+    fn test_stale_items_and_markdown() {
+        let item = |id: &str, status: AttentionStatus, since: &str| AttentionItem {
+            id: id.to_string(),
+            description: format!("About {}", id),
+            status,
+            surfaced_at: at("2026-10-01T00:00:00Z"),
+            // Edited recently, but the status hasn't moved
+            updated_at: at("2026-10-18T12:00:00Z"),
+            status_since: Some(at(since)),
+            notes: None,
+            priority: Priority::default(),
+            tags: Vec::new(),
+        };
+        let attention = vec![
+            item("fresh", AttentionStatus::Hot, "2026-10-18T00:00:00Z"),
+            item("old", AttentionStatus::Waiting, "2026-10-14T12:00:00Z"),
+            item("done", AttentionStatus::Handled, "2026-10-01T00:00:00Z"),
+        ];
+
+        let report = Report::build(&[], &attention, &options());
+        assert_eq!(report.stale.len(), 1);
+        assert_eq!(report.stale[0].id, "old");
+
+        let markdown = report.to_markdown();
+        assert!(markdown.starts_with("# Mode Report: 2026-10-12 to 2026-10-19"));
+        assert!(markdown.contains("## Stale Items (same status for 3d 0h+)"));
+        assert!(markdown.contains("- **old** (waiting, 5d 0h): About old"));
+    }
+}
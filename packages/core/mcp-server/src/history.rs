@@ -0,0 +1,175 @@
+//! Persisted mode history
+//!
+//! [`ModalState::mode_history`](crate::state::ModalState) only covers the
+//! running process. For analytics across sessions, each finished stint in a
+//! mode and each attention item marked handled is appended as one JSON line
+//! to [`default_history_path`]. A session is one run of the server,
+//! identified by when it started.
+//!
+//! The log is append-only; nothing here rewrites or prunes it.
+
+use crate::state::{AttentionItem, Mode, ModeTransition};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the history location
+pub const HISTORY_ENV: &str = "AIANDI_MODE_HISTORY";
+
+/// Where the history log is written
+///
+/// `$AIANDI_MODE_HISTORY`, else `$XDG_STATE_HOME/aiandi/mode-history.jsonl`,
+/// else `~/.local/state/aiandi/mode-history.jsonl`.
+pub fn default_history_path() -> PathBuf {
+    if let Ok(path) = std::env::var(HISTORY_ENV) {
+        return PathBuf::from(path);
+    }
+
+    let state_home = std::env::var("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".local/state")
+        });
+
+    state_home.join("aiandi").join("mode-history.jsonl")
+}
+
+/// One line of the history log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HistoryEvent {
+    /// Time spent in a mode
+    Mode {
+        session: DateTime<Utc>,
+        mode: Mode,
+        entered_at: DateTime<Utc>,
+        exited_at: DateTime<Utc>,
+    },
+    /// An attention item marked handled
+    Handled {
+        session: DateTime<Utc>,
+        id: String,
+        surfaced_at: DateTime<Utc>,
+        handled_at: DateTime<Utc>,
+    },
+}
+
+/// The history log, as written by one session
+#[derive(Debug, Clone)]
+pub struct HistoryLog {
+    path: PathBuf,
+    session: DateTime<Utc>,
+}
+
+impl HistoryLog {
+    pub fn new(path: impl Into<PathBuf>, session: DateTime<Utc>) -> Self {
+        Self {
+            path: path.into(),
+            session,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The event for a stint in a mode, ending at `now` if still open
+    pub fn mode_event(&self, transition: &ModeTransition, now: DateTime<Utc>) -> HistoryEvent {
+        HistoryEvent::Mode {
+            session: self.session,
+            mode: transition.mode.clone(),
+            entered_at: transition.entered_at,
+            exited_at: transition.exited_at.unwrap_or(now),
+        }
+    }
+
+    /// The event for an item that was just marked handled
+    pub fn handled_event(&self, item: &AttentionItem) -> HistoryEvent {
+        HistoryEvent::Handled {
+            session: self.session,
+            id: item.id.clone(),
+            surfaced_at: item.surfaced_at,
+            handled_at: item.status_changed_at(),
+        }
+    }
+
+    /// Append one event
+    pub fn append(&self, event: &HistoryEvent) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut line = serde_json::to_string(event).map_err(std::io::Error::other)?;
+        line.push('\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())
+    }
+
+    /// Every event in the log, oldest first; a missing log is empty
+    ///
+    /// Lines that don't parse (e.g. cut short by a crash) are skipped.
+    pub fn read(&self) -> std::io::Result<Vec<HistoryEvent>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut skipped = 0;
+        let events = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| {
+                serde_json::from_str(line)
+                    .inspect_err(|_| skipped += 1)
+                    .ok()
+            })
+            .collect();
+
+        if skipped > 0 {
+            tracing::warn!(
+                "Skipped {} unreadable lines in {}",
+                skipped,
+                self.path.display()
+            );
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_read() {
+        let dir = std::env::temp_dir().join(format!("aiandi-history-test-{}", std::process::id()));
+        let path = dir.join("mode-history.jsonl");
+        let session = Utc::now();
+        let log = HistoryLog::new(&path, session);
+
+        assert!(log.read().unwrap().is_empty());
+
+        let transition = ModeTransition {
+            mode: Mode::OPS,
+            entered_at: session,
+            exited_at: None,
+        };
+        let event = log.mode_event(&transition, session + chrono::Duration::minutes(5));
+        log.append(&event).unwrap();
+
+        // A line cut short by a crash
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(b"{\"event\": \"mo\n").unwrap();
+
+        assert_eq!(log.read().unwrap(), vec![event]);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,12 +1,22 @@
 //! aiandi-mode: MCP server for modal state management
 //!
 //! Tracks:
-//! - Current mode (ops, ceremonial, default)
+//! - Current mode (ops, ceremonial, default, or any mode in the registry;
+//!   see [`modes`])
 //! - Mode entry timestamp
 //! - Active context (what's being worked on)
 //! - Attention stack (hot, waiting, handled items)
+//! - Which tools each mode allows (see [`policy`])
+//! - Mode and attention history across sessions (see [`history`]), and
+//!   reports built from it (see [`analytics`])
+//! - Session close reports as transmissions (see [`transmission`])
 //!
 //! This server provides working memory for modal operation,
 //! enabling context persistence across conversation turns.
 
+pub mod analytics;
+pub mod history;
+pub mod modes;
+pub mod policy;
 pub mod state;
+pub mod transmission;
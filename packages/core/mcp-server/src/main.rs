@@ -1,26 +1,45 @@
 //! aiandi-mode: MCP server for modal state management
 //!
 //! Provides tools for:
-//! - Mode management (enter ops, ceremonial, or default mode)
+//! - Mode management (enter ops, ceremonial, default, or a configured mode)
 //! - Context tracking (what's being worked on)
-//! - Attention stack (hot, waiting, handled items)
+//! - Attention stack (hot, waiting, handled items with notes, priority
+//!   and tags)
 //! - Mode history and duration queries
+//! - Time-per-mode and attention reports across sessions
+//! - Session close: a transmission report written to the outbox
+//!
+//! Tools are listed and callable according to the current mode's
+//! [`policy::ToolPolicy`]; clients are told to re-list on every transition.
 
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use rmcp::{
-    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+    handler::server::{router::tool::ToolRouter, tool::ToolCallContext, wrapper::Parameters},
     model::*,
-    schemars, tool, tool_handler, tool_router, ServerHandler, ServiceExt,
+    schemars,
+    service::RequestContext,
+    tool, tool_router, ErrorData, RoleServer, ServerHandler, ServiceExt,
 };
 use serde::Serialize;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
+mod analytics;
+mod history;
+mod modes;
+mod policy;
 mod state;
+mod transmission;
 
-use state::{AttentionStatus, Mode, ModalState};
+use analytics::{Report, ReportOptions};
+use history::{HistoryEvent, HistoryLog};
+use modes::{ModeDefinition, ModeRegistry};
+use policy::{Snapshot, ToolPolicy};
+use state::{AttentionStatus, Mode, ModalState, Priority};
+use transmission::ReportHeader;
 
 // ============================================================================
 // Request types for each tool
@@ -28,7 +47,7 @@ use state::{AttentionStatus, Mode, ModalState};
 
 #[derive(serde::Deserialize, schemars::JsonSchema)]
 pub struct EnterModeRequest {
-    /// The mode to enter: "ops", "ceremonial", or "default"
+    /// The mode to enter: "ops", "ceremonial", "default", or a configured mode (see list_modes)
     mode: String,
 }
 
@@ -54,10 +73,76 @@ pub struct UpdateAttentionRequest {
     status: String,
 }
 
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct EditAttentionRequest {
+    /// The attention item ID to edit
+    id: String,
+    /// New description (omit to keep the current one)
+    description: Option<String>,
+    /// New notes (omit to keep, empty string to clear)
+    notes: Option<String>,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct SetPriorityRequest {
+    /// The attention item ID
+    id: String,
+    /// Priority: "low", "normal", "high", or "urgent"
+    priority: String,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct TagAttentionRequest {
+    /// The attention item ID
+    id: String,
+    /// Tags to add (e.g. ["health", "money"])
+    #[serde(default)]
+    add: Vec<String>,
+    /// Tags to remove
+    #[serde(default)]
+    remove: Vec<String>,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct RemoveAttentionRequest {
+    /// The attention item ID to remove
+    id: String,
+}
+
 #[derive(serde::Deserialize, schemars::JsonSchema)]
 pub struct GetAttentionRequest {
     /// Optional status filter: "hot", "waiting", "handled", or "all"
     status: Option<String>,
+    /// Optional tag filter
+    tag: Option<String>,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct ModeReportRequest {
+    /// Days to cover, ending now (default 7)
+    days: Option<u32>,
+    /// Hours after which an untouched hot or waiting item counts as stale (default 72)
+    stale_after_hours: Option<u32>,
+    /// "json" (default) or "markdown"
+    format: Option<String>,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct CloseSessionRequest {
+    /// Thread the report belongs to (e.g. "ops-weekly")
+    thread_id: String,
+    /// Position of the report in the thread, starting at 1
+    position: u32,
+    /// Id of the transmission this report answers, if any
+    in_reply_to: Option<String>,
+    /// Short description for the file name (default "Session Close")
+    description: Option<String>,
+    /// Background for the reader (default: a generic session-close line)
+    summary: Option<String>,
+    /// Sender (default "aiandi Agent")
+    from: Option<String>,
+    /// Recipient (default "Governance Committee")
+    to: Option<String>,
 }
 
 // ============================================================================
@@ -67,8 +152,12 @@ pub struct GetAttentionRequest {
 #[derive(Serialize)]
 struct ModeStatus {
     current_mode: String,
+    description: Option<String>,
     mode_entered_at: String,
     duration_seconds: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_budget_minutes: Option<u32>,
+    over_budget: bool,
     active_context: Option<String>,
     attention_counts: AttentionCounts,
 }
@@ -88,6 +177,8 @@ struct AttentionItemResponse {
     surfaced_at: String,
     updated_at: String,
     notes: Option<String>,
+    priority: String,
+    tags: Vec<String>,
 }
 
 impl From<&state::AttentionItem> for AttentionItemResponse {
@@ -103,6 +194,8 @@ impl From<&state::AttentionItem> for AttentionItemResponse {
             surfaced_at: item.surfaced_at.to_rfc3339(),
             updated_at: item.updated_at.to_rfc3339(),
             notes: item.notes.clone(),
+            priority: item.priority.to_string(),
+            tags: item.tags.clone(),
         }
     }
 }
@@ -115,14 +208,120 @@ impl From<&state::AttentionItem> for AttentionItemResponse {
 pub struct ModeServer {
     tool_router: ToolRouter<Self>,
     state: Arc<RwLock<ModalState>>,
+    snapshot_path: PathBuf,
+    policy: Arc<ToolPolicy>,
+    registry: Arc<ModeRegistry>,
+    history: Arc<HistoryLog>,
+}
+
+impl ModeServer {
+    /// Write the state snapshot; failures are logged, never surfaced to tools
+    fn snapshot(&self, state: &ModalState) {
+        let snapshot = Snapshot::new(state, &self.policy);
+        if let Err(e) = state::write_snapshot(&snapshot, &self.snapshot_path) {
+            tracing::warn!(
+                "Failed to write state snapshot to {}: {}",
+                self.snapshot_path.display(),
+                e
+            );
+        }
+    }
+
+    /// Append to the history log; failures are logged, never surfaced to tools
+    fn record(&self, event: &HistoryEvent) {
+        if let Err(e) = self.history.append(event) {
+            tracing::warn!(
+                "Failed to append to history {}: {}",
+                self.history.path().display(),
+                e
+            );
+        }
+    }
+
+    /// Record the stint `enter_mode` just closed
+    fn record_previous_stint(&self, state: &ModalState) {
+        if let Some(previous) = state.mode_history.iter().rev().nth(1) {
+            self.record(&self.history.mode_event(previous, Utc::now()));
+        }
+    }
+
+    /// Record the current stint, cut off now; called when the session ends
+    async fn record_open_stint(&self) {
+        let state = self.state.read().await;
+        if let Some(current) = state.mode_history.last() {
+            self.record(&self.history.mode_event(current, Utc::now()));
+        }
+    }
+
+    /// Prompts for leaving `previous` and entering `next`
+    fn transition_prompts(&self, previous: &Mode, next: &ModeDefinition) -> Vec<String> {
+        let mut prompts = Vec::new();
+        if let Some(exit) = self.registry.get(previous).and_then(|m| m.exit_prompt.clone()) {
+            prompts.push(exit);
+        }
+        if let Some(entry) = &next.entry_prompt {
+            prompts.push(entry.clone());
+        }
+        if let Some(budget) = next.time_budget_minutes {
+            prompts.push(format!("Time budget: {} minutes", budget));
+        }
+        prompts
+    }
 }
 
 #[tool_router]
 impl ModeServer {
     fn new() -> Self {
-        Self {
+        let snapshot_path = state::default_snapshot_path();
+
+        let mut state = ModalState::default();
+        let history = HistoryLog::new(history::default_history_path(), state.mode_entered_at);
+        match state.restore_attention(&snapshot_path) {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("Restored {} attention items", n),
+            Err(e) => tracing::warn!(
+                "Failed to restore attention stack from {}: {}",
+                snapshot_path.display(),
+                e
+            ),
+        }
+
+        let registry = ModeRegistry::load(&modes::default_modes_path()).unwrap_or_else(|e| {
+            tracing::warn!("{}; using the built-in modes", e);
+            ModeRegistry::default()
+        });
+
+        let policy_path = policy::default_policy_path();
+        let policy = ToolPolicy::load(&policy_path).unwrap_or_else(|e| {
+            tracing::warn!("{}; using the default policy", e);
+            ToolPolicy::default()
+        });
+        for mode in policy.modes.keys().filter(|m| registry.get(m).is_none()) {
+            tracing::warn!("Policy names unknown mode '{}'", mode);
+        }
+
+        let server = Self {
             tool_router: Self::tool_router(),
-            state: Arc::new(RwLock::new(ModalState::default())),
+            state: Arc::new(RwLock::new(state)),
+            snapshot_path,
+            policy: Arc::new(policy),
+            registry: Arc::new(registry),
+            history: Arc::new(history),
+        };
+
+        // The previous session's snapshot still names its mode and blocked
+        // tools; replace it before anyone reads it
+        {
+            let state = server.state.try_read().expect("state is not shared yet");
+            server.snapshot(&state);
+        }
+        server
+    }
+
+    /// Tell the client the tool list changed with the mode
+    async fn notify_tools_changed(ctx: &RequestContext<RoleServer>) {
+        if let Err(e) = ctx.peer.notify_tool_list_changed().await {
+            tracing::warn!("Failed to send tools/list_changed: {}", e);
         }
     }
 
@@ -134,11 +333,16 @@ impl ModeServer {
     async fn mode_status(&self) -> String {
         let state = self.state.read().await;
         let duration = state.mode_duration();
+        let definition = self.registry.get(&state.current_mode);
+        let budget = definition.and_then(|m| m.time_budget_minutes);
 
         let status = ModeStatus {
             current_mode: state.current_mode.to_string(),
+            description: definition.map(|m| m.description.clone()),
             mode_entered_at: state.mode_entered_at.to_rfc3339(),
             duration_seconds: duration.num_seconds(),
+            time_budget_minutes: budget,
+            over_budget: budget.is_some_and(|b| duration.num_minutes() >= i64::from(b)),
             active_context: state.active_context.clone(),
             attention_counts: AttentionCounts {
                 hot: state.hot_items().len(),
@@ -150,37 +354,56 @@ impl ModeServer {
         serde_json::to_string_pretty(&status).unwrap_or_else(|e| format!("Error: {}", e))
     }
 
-    #[tool(description = "Enter a mode: 'ops' (trusted steward), 'ceremonial' (/open ritual), or 'default' (coding).")]
-    async fn enter_mode(&self, Parameters(req): Parameters<EnterModeRequest>) -> String {
-        let mode = match req.mode.to_lowercase().as_str() {
-            "ops" => Mode::Ops,
-            "ceremonial" => Mode::Ceremonial,
-            "default" => Mode::Default,
-            other => return format!("Unknown mode '{}'. Use: ops, ceremonial, or default", other),
+    #[tool(description = "List the modes that can be entered, with their descriptions, prompts, and time budgets.")]
+    async fn list_modes(&self) -> String {
+        serde_json::to_string_pretty(self.registry.modes())
+            .unwrap_or_else(|e| format!("Error: {}", e))
+    }
+
+    #[tool(description = "Enter a mode: 'ops' (trusted steward), 'ceremonial' (/open ritual), 'default' (coding), or a configured mode (see list_modes).")]
+    async fn enter_mode(
+        &self,
+        Parameters(req): Parameters<EnterModeRequest>,
+        ctx: RequestContext<RoleServer>,
+    ) -> String {
+        let next = match self.registry.resolve(&req.mode) {
+            Ok(definition) => definition,
+            Err(e) => return e.to_string(),
         };
 
         let mut state = self.state.write().await;
-        let previous = state.current_mode;
-        state.enter_mode(mode);
-
-        format!(
-            "Mode changed: {} -> {}",
-            previous,
-            state.current_mode
-        )
+        let previous = state.current_mode.clone();
+        state.enter_mode(next.name.clone());
+        self.record_previous_stint(&state);
+        self.snapshot(&state);
+        drop(state);
+
+        Self::notify_tools_changed(&ctx).await;
+        let mut lines = vec![format!("Mode changed: {} -> {}", previous, next.name)];
+        lines.extend(self.transition_prompts(&previous, next));
+        lines.join("\n")
     }
 
     #[tool(description = "Exit current mode, returning to default. Alias for entering 'default' mode.")]
-    async fn exit_mode(&self) -> String {
+    async fn exit_mode(&self, ctx: RequestContext<RoleServer>) -> String {
         let mut state = self.state.write().await;
-        let previous = state.current_mode;
+        let previous = state.current_mode.clone();
 
-        if previous == Mode::Default {
+        if previous == Mode::DEFAULT {
             return "Already in default mode".to_string();
         }
 
-        state.enter_mode(Mode::Default);
-        format!("Exited {} mode, returned to default", previous)
+        state.enter_mode(Mode::DEFAULT);
+        self.record_previous_stint(&state);
+        self.snapshot(&state);
+        drop(state);
+
+        Self::notify_tools_changed(&ctx).await;
+        let mut lines = vec![format!("Exited {} mode, returned to default", previous)];
+        if let Some(default) = self.registry.get(&Mode::DEFAULT) {
+            lines.extend(self.transition_prompts(&previous, default));
+        }
+        lines.join("\n")
     }
 
     // =========================================================================
@@ -193,6 +416,7 @@ impl ModeServer {
         let context = req.context.filter(|s| !s.trim().is_empty());
 
         state.set_context(context.clone());
+        self.snapshot(&state);
 
         match context {
             Some(ctx) => format!("Context set: {}", ctx),
@@ -217,13 +441,13 @@ impl ModeServer {
     async fn add_attention(&self, Parameters(req): Parameters<AddAttentionRequest>) -> String {
         let mut state = self.state.write().await;
 
-        // Check for duplicate
-        if state.attention_stack.iter().any(|i| i.id == req.id) {
-            return format!("Attention item '{}' already exists", req.id);
-        }
+        let message = match state.add_attention(req.id, req.description) {
+            Ok(item) => format!("Added attention item: {} - {}", item.id, item.description),
+            Err(e) => return e.to_string(),
+        };
 
-        state.add_attention(req.id.clone(), req.description.clone());
-        format!("Added attention item: {} - {}", req.id, req.description)
+        self.snapshot(&state);
+        message
     }
 
     #[tool(description = "Update an attention item's status: 'hot' (being worked), 'waiting', or 'handled'.")]
@@ -236,36 +460,127 @@ impl ModeServer {
         };
 
         let mut state = self.state.write().await;
+        let was_handled = state
+            .attention_stack
+            .iter()
+            .any(|i| i.id == req.id && i.status == AttentionStatus::Handled);
 
-        let result = match status {
-            AttentionStatus::Hot => state.mark_hot(&req.id),
-            AttentionStatus::Waiting => {
-                if let Some(item) = state.attention_stack.iter_mut().find(|i| i.id == req.id) {
-                    item.status = AttentionStatus::Waiting;
-                    item.updated_at = Utc::now();
-                    Some(&*item)
-                } else {
-                    None
+        let message = match state.set_status(&req.id, status) {
+            Ok(item) => {
+                if item.status == AttentionStatus::Handled && !was_handled {
+                    self.record(&self.history.handled_event(item));
                 }
+                format!("Updated '{}' to {}", item.id, req.status)
+            }
+            Err(e) => return e.to_string(),
+        };
+
+        self.snapshot(&state);
+        message
+    }
+
+    #[tool(description = "Edit an attention item's description and/or notes. An empty notes string clears the notes.")]
+    async fn edit_attention(&self, Parameters(req): Parameters<EditAttentionRequest>) -> String {
+        if req.description.is_none() && req.notes.is_none() {
+            return "Nothing to edit. Provide description and/or notes".to_string();
+        }
+
+        let mut state = self.state.write().await;
+
+        if let Some(description) = req.description
+            && let Err(e) = state.set_description(&req.id, description)
+        {
+            return e.to_string();
+        }
+        if let Some(notes) = req.notes
+            && let Err(e) = state.set_notes(&req.id, Some(notes))
+        {
+            return e.to_string();
+        }
+
+        self.snapshot(&state);
+        format!("Edited attention item '{}'", req.id)
+    }
+
+    #[tool(description = "Set an attention item's priority: 'low', 'normal', 'high', or 'urgent'. Lists show higher priorities first.")]
+    async fn set_attention_priority(&self, Parameters(req): Parameters<SetPriorityRequest>) -> String {
+        let priority = match req.priority.to_lowercase().as_str() {
+            "low" => Priority::Low,
+            "normal" => Priority::Normal,
+            "high" => Priority::High,
+            "urgent" => Priority::Urgent,
+            other => {
+                return format!(
+                    "Unknown priority '{}'. Use: low, normal, high, or urgent",
+                    other
+                )
             }
-            AttentionStatus::Handled => state.mark_handled(&req.id),
         };
 
-        match result {
-            Some(item) => format!("Updated '{}' to {}", item.id, req.status),
-            None => format!("Attention item '{}' not found", req.id),
+        let mut state = self.state.write().await;
+
+        let message = match state.set_priority(&req.id, priority) {
+            Ok(item) => format!("Set '{}' priority to {}", item.id, item.priority),
+            Err(e) => return e.to_string(),
+        };
+
+        self.snapshot(&state);
+        message
+    }
+
+    #[tool(description = "Add and/or remove tags on an attention item.")]
+    async fn tag_attention(&self, Parameters(req): Parameters<TagAttentionRequest>) -> String {
+        if req.add.is_empty() && req.remove.is_empty() {
+            return "No tags given. Provide tags to add and/or remove".to_string();
+        }
+
+        let mut state = self.state.write().await;
+
+        let message = match state.tag_attention(&req.id, &req.add, &req.remove) {
+            Ok(item) if item.tags.is_empty() => format!("'{}' has no tags", item.id),
+            Ok(item) => format!("'{}' tags: {}", item.id, item.tags.join(", ")),
+            Err(e) => return e.to_string(),
+        };
+
+        self.snapshot(&state);
+        message
+    }
+
+    #[tool(description = "Remove an item from the attention stack entirely (use update_attention to mark it handled instead).")]
+    async fn remove_attention(&self, Parameters(req): Parameters<RemoveAttentionRequest>) -> String {
+        let mut state = self.state.write().await;
+
+        let message = match state.remove_attention(&req.id) {
+            Ok(item) => format!("Removed attention item: {} - {}", item.id, item.description),
+            Err(e) => return e.to_string(),
+        };
+
+        self.snapshot(&state);
+        message
+    }
+
+    #[tool(description = "Remove all handled items from the attention stack.")]
+    async fn clear_handled(&self) -> String {
+        let mut state = self.state.write().await;
+
+        let cleared = state.clear_handled();
+        if cleared.is_empty() {
+            return "No handled items to clear".to_string();
         }
+
+        self.snapshot(&state);
+        format!("Cleared {} handled item(s)", cleared.len())
     }
 
-    #[tool(description = "List attention items. Optionally filter by status: 'hot', 'waiting', 'handled', or 'all'.")]
+    #[tool(description = "List attention items, highest priority first. Optionally filter by status ('hot', 'waiting', 'handled', or 'all') and tag.")]
     async fn list_attention(&self, Parameters(req): Parameters<GetAttentionRequest>) -> String {
         let state = self.state.read().await;
 
-        let items: Vec<AttentionItemResponse> = match req.status.as_deref() {
-            Some("hot") => state.hot_items().into_iter().map(Into::into).collect(),
-            Some("waiting") => state.waiting_items().into_iter().map(Into::into).collect(),
-            Some("handled") => state.handled_items().into_iter().map(Into::into).collect(),
-            Some("all") | None => state.attention_stack.iter().map(Into::into).collect(),
+        let status = match req.status.as_deref() {
+            Some("hot") => Some(AttentionStatus::Hot),
+            Some("waiting") => Some(AttentionStatus::Waiting),
+            Some("handled") => Some(AttentionStatus::Handled),
+            Some("all") | None => None,
             Some(other) => {
                 return format!(
                     "Unknown status '{}'. Use: hot, waiting, handled, or all",
@@ -273,6 +588,15 @@ impl ModeServer {
                 )
             }
         };
+        let tag = req.tag.as_deref().map(|t| t.trim().trim_start_matches('#'));
+
+        let items: Vec<AttentionItemResponse> = state
+            .prioritized()
+            .into_iter()
+            .filter(|i| status.as_ref().is_none_or(|s| &i.status == s))
+            .filter(|i| tag.is_none_or(|t| i.tags.iter().any(|it| it == t)))
+            .map(Into::into)
+            .collect();
 
         if items.is_empty() {
             return "No attention items".to_string();
@@ -303,16 +627,117 @@ impl ModeServer {
 
         serde_json::to_string_pretty(&history).unwrap_or_else(|e| format!("Error: {}", e))
     }
+
+    // =========================================================================
+    // Session Close
+    // =========================================================================
+
+    #[tool(description = "Close the session: write a transmission report (type=\"report\") of the mode history, context changes, and handled and outstanding attention items to the outbox as Transmission_[Thread-ID]_Report_[Desc].xml. Never overwrites an existing report.")]
+    async fn close_session(&self, Parameters(req): Parameters<CloseSessionRequest>) -> String {
+        let header = ReportHeader {
+            thread_id: req.thread_id.trim().to_string(),
+            position: req.position,
+            in_reply_to: req.in_reply_to.filter(|s| !s.trim().is_empty()),
+            description: req.description.unwrap_or_else(|| "Session Close".to_string()),
+            summary: req.summary.filter(|s| !s.trim().is_empty()),
+            from: req.from.unwrap_or_else(|| "aiandi Agent".to_string()),
+            to: req.to.unwrap_or_else(|| "Governance Committee".to_string()),
+        };
+
+        let state = self.state.read().await;
+        match transmission::write_report(&transmission::default_outbox(), &header, &state, Utc::now()) {
+            Ok(path) => format!("Wrote session report to {}", path.display()),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[tool(description = "Report time per mode per day and week, attention items handled per session, median time from surfaced to handled, and stale hot/waiting items. Covers all sessions, not just this one. Format: 'json' (default) or 'markdown' for the weekly review.")]
+    async fn mode_report(&self, Parameters(req): Parameters<ModeReportRequest>) -> String {
+        let markdown = match req.format.as_deref().map(str::to_lowercase).as_deref() {
+            None | Some("json") => false,
+            Some("markdown") | Some("md") => true,
+            Some(other) => return format!("Unknown format '{}'. Use: json or markdown", other),
+        };
+
+        let mut events = match self.history.read() {
+            Ok(events) => events,
+            Err(e) => {
+                return format!(
+                    "Failed to read history {}: {}",
+                    self.history.path().display(),
+                    e
+                )
+            }
+        };
+
+        let now = Utc::now();
+        let options = ReportOptions {
+            since: now - Duration::days(i64::from(req.days.unwrap_or(7))),
+            until: now,
+            stale_after: Duration::hours(i64::from(req.stale_after_hours.unwrap_or(72))),
+        };
+
+        let state = self.state.read().await;
+        if let Some(current) = state.mode_history.last() {
+            events.push(self.history.mode_event(current, now));
+        }
+        let report = Report::build(&events, &state.attention_stack, &options);
+
+        if markdown {
+            report.to_markdown()
+        } else {
+            serde_json::to_string_pretty(&report).unwrap_or_else(|e| format!("Error: {}", e))
+        }
+    }
 }
 
-#[tool_handler]
 impl ServerHandler for ModeServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_tool_list_changed()
+                .build(),
             ..Default::default()
         }
     }
+
+    // Hand-written instead of #[tool_handler] so the policy applies to the
+    // whole router, including any routers merged into it
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        let mode = self.state.read().await.current_mode.clone();
+        let tools = self
+            .tool_router
+            .list_all()
+            .into_iter()
+            .filter(|tool| self.policy.allows(&mode, &tool.name))
+            .collect();
+
+        Ok(ListToolsResult {
+            tools,
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mode = self.state.read().await.current_mode.clone();
+        if let Err(e) = self.policy.check(&mode, &request.name) {
+            return Ok(CallToolResult::error(vec![Content::text(e.to_string())]));
+        }
+
+        let tcc = ToolCallContext::new(self, request, context);
+        self.tool_router.call(tcc).await
+    }
 }
 
 #[tokio::main]
@@ -327,10 +752,12 @@ async fn main() -> Result<()> {
 
     // Create server and run with stdio transport
     let transport = (tokio::io::stdin(), tokio::io::stdout());
-    let service = ModeServer::new().serve(transport).await?;
+    let server = ModeServer::new();
+    let service = server.clone().serve(transport).await?;
 
     tracing::info!("Server initialized, waiting for requests");
     service.waiting().await?;
+    server.record_open_stint().await;
 
     Ok(())
 }
@@ -0,0 +1,278 @@
+//! Mode registry - the modes that can be entered
+//!
+//! `default`, `ops` and `ceremonial` are built in. A JSON file adds modes,
+//! or redefines a built-in by using its name:
+//!
+//! ```json
+//! {
+//!   "modes": [
+//!     {
+//!       "name": "review",
+//!       "description": "Weekly review: get clear, get current, get creative",
+//!       "entry_prompt": "Review open. Start with the inbox.",
+//!       "exit_prompt": "Anything left unprocessed goes back to the inbox.",
+//!       "time_budget_minutes": 90
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Mode names are lowercase: letters, digits, `-` and `_`, starting with a
+//! letter. Mode history stores these names, so renaming a mode in the file
+//! leaves older history under the old name.
+
+use crate::state::Mode;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the registry file location
+pub const MODES_ENV: &str = "AIANDI_MODES";
+
+/// Where the registry file is looked up
+///
+/// `$AIANDI_MODES`, else `$XDG_CONFIG_HOME/aiandi/modes.json`,
+/// else `~/.config/aiandi/modes.json`.
+pub fn default_modes_path() -> PathBuf {
+    if let Ok(path) = std::env::var(MODES_ENV) {
+        return PathBuf::from(path);
+    }
+
+    let config_home = std::env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".config")
+        });
+
+    config_home.join("aiandi").join("modes.json")
+}
+
+/// Why the registry could not be loaded, or a mode name was rejected
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    #[error("Failed to read modes {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid modes {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error(
+        "Invalid mode name '{0}': use lowercase letters, digits, '-' or '_', starting with a letter"
+    )]
+    InvalidName(String),
+    #[error("Mode '{0}' is defined more than once")]
+    Duplicate(String),
+    #[error("Unknown mode '{name}'. Use: {known}")]
+    UnknownMode { name: String, known: String },
+}
+
+/// One mode as defined in the registry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModeDefinition {
+    pub name: Mode,
+    /// What the mode is for
+    pub description: String,
+    /// Shown when the mode is entered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_prompt: Option<String>,
+    /// Shown when the mode is left
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_prompt: Option<String>,
+    /// How long a stint in this mode should take
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_budget_minutes: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct RegistryFile {
+    modes: Vec<ModeDefinition>,
+}
+
+/// Every mode that can be entered, built-ins first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModeRegistry {
+    modes: Vec<ModeDefinition>,
+}
+
+impl Default for ModeRegistry {
+    fn default() -> Self {
+        let builtin = |name: Mode, description: &str, entry: Option<&str>, exit: Option<&str>| {
+            ModeDefinition {
+                name,
+                description: description.to_string(),
+                entry_prompt: entry.map(String::from),
+                exit_prompt: exit.map(String::from),
+                time_budget_minutes: None,
+            }
+        };
+
+        Self {
+            modes: vec![
+                builtin(
+                    Mode::DEFAULT,
+                    "Coding - build what is specified; technical collaboration",
+                    None,
+                    None,
+                ),
+                builtin(
+                    Mode::OPS,
+                    "Trusted steward managing life logistics; exercises judgment",
+                    Some("Ops active. What needs handling?"),
+                    Some("Summarize what was handled. Anything else before standing down?"),
+                ),
+                builtin(
+                    Mode::CEREMONIAL,
+                    "Build within the sacred container; full opening and closing liturgy",
+                    None,
+                    None,
+                ),
+            ],
+        }
+    }
+}
+
+impl ModeRegistry {
+    /// Built-ins plus the modes in `path`; a missing file gives the built-ins
+    pub fn load(path: &Path) -> Result<Self, RegistryError> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(RegistryError::Read {
+                    path: path.to_path_buf(),
+                    source,
+                });
+            }
+        };
+
+        let file: RegistryFile =
+            serde_json::from_str(&json).map_err(|source| RegistryError::Parse {
+                path: path.to_path_buf(),
+                source,
+            })?;
+
+        let mut registry = Self::default();
+        let mut seen: Vec<&Mode> = Vec::new();
+        for mode in &file.modes {
+            if !valid_name(mode.name.as_str()) {
+                return Err(RegistryError::InvalidName(mode.name.to_string()));
+            }
+            if seen.contains(&&mode.name) {
+                return Err(RegistryError::Duplicate(mode.name.to_string()));
+            }
+            seen.push(&mode.name);
+        }
+
+        for mode in file.modes {
+            match registry.modes.iter_mut().find(|m| m.name == mode.name) {
+                Some(existing) => *existing = mode,
+                None => registry.modes.push(mode),
+            }
+        }
+        Ok(registry)
+    }
+
+    /// All modes, built-ins first
+    pub fn modes(&self) -> &[ModeDefinition] {
+        &self.modes
+    }
+
+    /// The definition of `mode`, if it is registered
+    pub fn get(&self, mode: &Mode) -> Option<&ModeDefinition> {
+        self.modes.iter().find(|m| &m.name == mode)
+    }
+
+    /// Look up a mode by name as a user typed it (case-insensitive)
+    pub fn resolve(&self, name: &str) -> Result<&ModeDefinition, RegistryError> {
+        let wanted = name.trim().to_lowercase();
+        self.modes
+            .iter()
+            .find(|m| m.name.as_str() == wanted)
+            .ok_or_else(|| RegistryError::UnknownMode {
+                name: name.to_string(),
+                known: self
+                    .modes
+                    .iter()
+                    .map(|m| m.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            })
+    }
+}
+
+fn valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_registry(json: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!(
+            "aiandi-modes-test-{}-{}",
+            std::process::id(),
+            json.len()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("modes.json");
+        std::fs::write(&path, json).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn test_builtins() {
+        let registry = ModeRegistry::default();
+        assert_eq!(registry.resolve("OPS").unwrap().name, Mode::OPS);
+        assert!(matches!(
+            registry.resolve("review"),
+            Err(RegistryError::UnknownMode { .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_adds_and_overrides_modes() {
+        let (dir, path) = write_registry(
+            r#"{"modes": [
+                {"name": "review", "description": "Weekly review", "time_budget_minutes": 90},
+                {"name": "ops", "description": "Ops, our way", "entry_prompt": "Ops."}
+            ]}"#,
+        );
+
+        let registry = ModeRegistry::load(&path).unwrap();
+        let review = registry.resolve("review").unwrap();
+        assert_eq!(review.time_budget_minutes, Some(90));
+        assert_eq!(review.name.as_str(), "review");
+
+        let ops = registry.get(&Mode::OPS).unwrap();
+        assert_eq!(ops.entry_prompt.as_deref(), Some("Ops."));
+        assert_eq!(registry.modes().len(), 4);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_load_rejects_bad_names() {
+        let (dir, path) =
+            write_registry(r#"{"modes": [{"name": "Deep Work", "description": "x"}]}"#);
+        assert!(matches!(
+            ModeRegistry::load(&path),
+            Err(RegistryError::InvalidName(_))
+        ));
+        std::fs::remove_dir_all(&dir).ok();
+
+        let (dir, path) = write_registry(
+            r#"{"modes": [{"name": "writing", "description": "x"}, {"name": "writing", "description": "y"}]}"#,
+        );
+        assert!(matches!(
+            ModeRegistry::load(&path),
+            Err(RegistryError::Duplicate(_))
+        ));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
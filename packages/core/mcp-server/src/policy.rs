@@ -0,0 +1,326 @@
+//! Mode-scoped tool policy
+//!
+//! Declares, per mode, which tool groups are available. A group is a named
+//! set of tool names (e.g. `email.send` = `send_email`); tools that belong to
+//! no group are always available, so the mode tools themselves can never be
+//! locked out.
+//!
+//! The policy is enforced on whatever router the mode server serves, so
+//! routers merged into it are covered too. Servers in other processes (pim)
+//! enforce it through the state snapshot, which lists the tools blocked in
+//! the current mode (see [`Snapshot`]).
+//!
+//! The default keeps outbound and calendar-writing tools to ops mode. It can
+//! be replaced with a JSON file:
+//!
+//! ```json
+//! {
+//!   "groups": {
+//!     "email.send": ["send_email"],
+//!     "calendar.write": ["create_event"]
+//!   },
+//!   "modes": {
+//!     "ops": ["email.send", "calendar.write"],
+//!     "default": [],
+//!     "ceremonial": []
+//!   }
+//! }
+//! ```
+
+use crate::state::{ModalState, Mode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the policy file location
+pub const POLICY_ENV: &str = "AIANDI_MODE_POLICY";
+
+/// Where the policy file is looked up
+///
+/// `$AIANDI_MODE_POLICY`, else `$XDG_CONFIG_HOME/aiandi/mode-policy.json`,
+/// else `~/.config/aiandi/mode-policy.json`.
+pub fn default_policy_path() -> PathBuf {
+    if let Ok(path) = std::env::var(POLICY_ENV) {
+        return PathBuf::from(path);
+    }
+
+    let config_home = std::env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".config")
+        });
+
+    config_home.join("aiandi").join("mode-policy.json")
+}
+
+/// Why a policy could not be loaded, or a tool call was refused
+#[derive(Debug, thiserror::Error)]
+pub enum PolicyError {
+    #[error("Failed to read policy {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid policy {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("Policy allows unknown group '{group}' in {mode} mode")]
+    UnknownGroup { mode: Mode, group: String },
+    #[error("'{tool}' ({group}) is not available in {mode} mode. Allowed in: {allowed_in}")]
+    Blocked {
+        tool: String,
+        group: String,
+        mode: Mode,
+        allowed_in: String,
+    },
+}
+
+/// Which tool groups each mode allows
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolPolicy {
+    /// Group name -> tool names in it
+    pub groups: BTreeMap<String, Vec<String>>,
+    /// Mode -> groups allowed in it (a missing mode allows none)
+    pub modes: BTreeMap<Mode, Vec<String>>,
+}
+
+impl Default for ToolPolicy {
+    fn default() -> Self {
+        let group = |name: &str, tools: &[&str]| {
+            (
+                name.to_string(),
+                tools.iter().map(|t| t.to_string()).collect(),
+            )
+        };
+
+        Self {
+            groups: BTreeMap::from([
+                group("email.send", &["send_email"]),
+                group("calendar.write", &["create_event"]),
+            ]),
+            modes: BTreeMap::from([
+                (
+                    Mode::OPS,
+                    vec!["email.send".to_string(), "calendar.write".to_string()],
+                ),
+                (Mode::DEFAULT, Vec::new()),
+                (Mode::CEREMONIAL, Vec::new()),
+            ]),
+        }
+    }
+}
+
+impl ToolPolicy {
+    /// Load a policy file; a missing file gives the default policy
+    pub fn load(path: &Path) -> Result<Self, PolicyError> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(PolicyError::Read {
+                    path: path.to_path_buf(),
+                    source,
+                });
+            }
+        };
+
+        let policy: Self = serde_json::from_str(&json).map_err(|source| PolicyError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    /// Every group a mode allows must be defined
+    fn validate(&self) -> Result<(), PolicyError> {
+        for (mode, groups) in &self.modes {
+            if let Some(group) = groups.iter().find(|g| !self.groups.contains_key(*g)) {
+                return Err(PolicyError::UnknownGroup {
+                    mode: mode.clone(),
+                    group: group.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Groups `tool` belongs to
+    fn groups_of<'a>(&'a self, tool: &'a str) -> impl Iterator<Item = &'a String> + 'a {
+        self.groups
+            .iter()
+            .filter(move |(_, tools)| tools.iter().any(|t| t == tool))
+            .map(|(group, _)| group)
+    }
+
+    fn allows_group(&self, mode: &Mode, group: &str) -> bool {
+        self.modes
+            .get(mode)
+            .is_some_and(|groups| groups.iter().any(|g| g == group))
+    }
+
+    /// Whether `tool` may be listed and called in `mode`
+    pub fn allows(&self, mode: &Mode, tool: &str) -> bool {
+        self.check(mode, tool).is_ok()
+    }
+
+    /// `Ok` if `tool` is available in `mode`, else why not
+    ///
+    /// A tool in several groups is available if any of them is allowed.
+    pub fn check(&self, mode: &Mode, tool: &str) -> Result<(), PolicyError> {
+        let mut groups = self.groups_of(tool).peekable();
+        if groups.peek().is_none() {
+            return Ok(());
+        }
+
+        let mut denied = None;
+        for group in groups {
+            if self.allows_group(mode, group) {
+                return Ok(());
+            }
+            denied.get_or_insert(group);
+        }
+
+        let group = denied.expect("tool has at least one group").clone();
+        let allowed_in: Vec<String> = self
+            .modes
+            .iter()
+            .filter(|(_, groups)| groups.contains(&group))
+            .map(|(mode, _)| mode.to_string())
+            .collect();
+
+        Err(PolicyError::Blocked {
+            tool: tool.to_string(),
+            group,
+            mode: mode.clone(),
+            allowed_in: if allowed_in.is_empty() {
+                "no mode".to_string()
+            } else {
+                allowed_in.join(", ")
+            },
+        })
+    }
+
+    /// Every grouped tool that is not available in `mode`, sorted
+    pub fn blocked_tools(&self, mode: &Mode) -> Vec<String> {
+        let mut blocked: Vec<String> = self
+            .groups
+            .values()
+            .flatten()
+            .filter(|tool| !self.allows(mode, tool))
+            .cloned()
+            .collect();
+        blocked.sort();
+        blocked.dedup();
+        blocked
+    }
+}
+
+/// The state snapshot other servers read
+///
+/// The modal state plus `blocked_tools`: tools those servers must refuse
+/// in the current mode. `owner_pid` is the writing server's process id, so
+/// readers can ignore a snapshot left behind by a server that is gone.
+#[derive(Debug, Serialize)]
+pub struct Snapshot<'a> {
+    #[serde(flatten)]
+    pub state: &'a ModalState,
+    pub blocked_tools: Vec<String>,
+    pub owner_pid: u32,
+}
+
+impl<'a> Snapshot<'a> {
+    pub fn new(state: &'a ModalState, policy: &ToolPolicy) -> Self {
+        Self {
+            state,
+            blocked_tools: policy.blocked_tools(&state.current_mode),
+            owner_pid: std::process::id(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_keeps_writes_to_ops() {
+        let policy = ToolPolicy::default();
+
+        assert!(policy.allows(&Mode::OPS, "send_email"));
+        assert!(policy.allows(&Mode::OPS, "create_event"));
+        assert!(!policy.allows(&Mode::DEFAULT, "send_email"));
+        assert!(!policy.allows(&Mode::CEREMONIAL, "create_event"));
+
+        // Ungrouped tools are always available
+        assert!(policy.allows(&Mode::DEFAULT, "list_events"));
+        assert!(policy.allows(&Mode::DEFAULT, "enter_mode"));
+
+        assert_eq!(
+            policy.blocked_tools(&Mode::DEFAULT),
+            vec!["create_event".to_string(), "send_email".to_string()]
+        );
+        assert!(policy.blocked_tools(&Mode::OPS).is_empty());
+    }
+
+    #[test]
+    fn test_blocked_error_names_allowed_modes() {
+        let err = ToolPolicy::default()
+            .check(&Mode::DEFAULT, "send_email")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "'send_email' (email.send) is not available in default mode. Allowed in: ops"
+        );
+    }
+
+    #[test]
+    fn test_load_policy_file() {
+        let dir = std::env::temp_dir().join(format!("aiandi-policy-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("mode-policy.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "groups": {"email.send": ["send_email"], "contacts.write": ["create_contact"]},
+                "modes": {"ops": ["email.send", "contacts.write"], "ceremonial": ["contacts.write"]}
+            }"#,
+        )
+        .unwrap();
+        let policy = ToolPolicy::load(&path).unwrap();
+        assert!(policy.allows(&Mode::CEREMONIAL, "create_contact"));
+        assert!(!policy.allows(&Mode::DEFAULT, "create_contact"));
+        // Not grouped in this policy
+        assert!(policy.allows(&Mode::DEFAULT, "create_event"));
+
+        std::fs::write(&path, r#"{"groups": {}, "modes": {"ops": ["email.send"]}}"#).unwrap();
+        assert!(matches!(
+            ToolPolicy::load(&path),
+            Err(PolicyError::UnknownGroup { .. })
+        ));
+
+        assert_eq!(
+            ToolPolicy::load(&dir.join("missing.json")).unwrap(),
+            ToolPolicy::default()
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_snapshot_lists_blocked_tools() {
+        let mut state = ModalState::default();
+        let policy = ToolPolicy::default();
+
+        let json = serde_json::to_value(Snapshot::new(&state, &policy)).unwrap();
+        assert_eq!(json["current_mode"], "default");
+        assert_eq!(json["blocked_tools"][1], "send_email");
+
+        state.enter_mode(Mode::OPS);
+        let json = serde_json::to_value(Snapshot::new(&state, &policy)).unwrap();
+        assert_eq!(json["blocked_tools"].as_array().unwrap().len(), 0);
+    }
+}
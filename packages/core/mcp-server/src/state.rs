@@ -1,33 +1,73 @@
 //! Modal state management
 //!
 //! Holds the in-memory state for the current mode session.
-//! State is ephemeral - it exists for the lifetime of the MCP server process.
+//! Mode and context are ephemeral - they exist for the lifetime of the MCP
+//! server process.
+//!
+//! After every change the server writes a JSON snapshot of the state to
+//! [`default_snapshot_path`], so other servers (e.g. pim's daily briefing)
+//! can read the attention stack without talking to this process. On startup
+//! the attention stack is restored from that snapshot
+//! ([`ModalState::restore_attention`]), so open items survive a restart.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-/// The three operational modes
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
-#[serde(rename_all = "lowercase")]
-pub enum Mode {
+/// Environment variable overriding the snapshot location
+pub const SNAPSHOT_ENV: &str = "AIANDI_MODE_STATE";
+
+/// Where the state snapshot is written
+///
+/// `$AIANDI_MODE_STATE`, else `$XDG_STATE_HOME/aiandi/mode-state.json`,
+/// else `~/.local/state/aiandi/mode-state.json`.
+pub fn default_snapshot_path() -> PathBuf {
+    if let Ok(path) = std::env::var(SNAPSHOT_ENV) {
+        return PathBuf::from(path);
+    }
+
+    let state_home = std::env::var("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".local/state")
+        });
+
+    state_home.join("aiandi").join("mode-state.json")
+}
+
+/// An operating mode, identified by name
+///
+/// Three modes are built in; more are defined in the mode registry
+/// (see [`crate::modes`]). Serialized as the bare name.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Mode(Cow<'static, str>);
+
+impl Mode {
     /// Default coding mode - technical collaboration
-    #[default]
-    Default,
+    pub const DEFAULT: Mode = Mode(Cow::Borrowed("default"));
     /// Ops mode - trusted steward for life logistics
-    Ops,
+    pub const OPS: Mode = Mode(Cow::Borrowed("ops"));
     /// Ceremonial mode - full ritual container (/open)
-    Ceremonial,
+    pub const CEREMONIAL: Mode = Mode(Cow::Borrowed("ceremonial"));
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::DEFAULT
+    }
 }
 
 impl std::fmt::Display for Mode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Mode::Default => write!(f, "default"),
-            Mode::Ops => write!(f, "ops"),
-            Mode::Ceremonial => write!(f, "ceremonial"),
-        }
+        f.write_str(&self.0)
     }
 }
 
@@ -43,6 +83,45 @@ pub enum AttentionStatus {
     Handled,
 }
 
+/// Priority of an attention item, ordered from lowest to highest
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Priority::Low => write!(f, "low"),
+            Priority::Normal => write!(f, "normal"),
+            Priority::High => write!(f, "high"),
+            Priority::Urgent => write!(f, "urgent"),
+        }
+    }
+}
+
+/// Why an attention stack operation was rejected
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AttentionError {
+    #[error("Attention item '{0}' not found")]
+    NotFound(String),
+    #[error("Attention item '{0}' already exists")]
+    Duplicate(String),
+    #[error("Attention item ID cannot be empty")]
+    EmptyId,
+    #[error("Description cannot be empty")]
+    EmptyDescription,
+    #[error("Invalid tag '{0}': tags must be non-empty and contain no whitespace")]
+    InvalidTag(String),
+}
+
 /// An item in the attention stack
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AttentionItem {
@@ -54,10 +133,28 @@ pub struct AttentionItem {
     pub status: AttentionStatus,
     /// When this item was surfaced
     pub surfaced_at: DateTime<Utc>,
-    /// When status last changed
+    /// When the item last changed (status, description, notes, ...)
     pub updated_at: DateTime<Utc>,
+    /// When the item entered its current status (missing in snapshots
+    /// written before it was tracked; see [`AttentionItem::status_changed_at`])
+    #[serde(default)]
+    pub status_since: Option<DateTime<Utc>>,
     /// Optional context/notes
     pub notes: Option<String>,
+    /// How urgently this needs attention
+    #[serde(default)]
+    pub priority: Priority,
+    /// Free-form labels (e.g. "health", "money")
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl AttentionItem {
+    /// When the item entered its current status, falling back to its last
+    /// update for items that predate `status_since`
+    pub fn status_changed_at(&self) -> DateTime<Utc> {
+        self.status_since.unwrap_or(self.updated_at)
+    }
 }
 
 /// A mode transition record
@@ -71,6 +168,15 @@ pub struct ModeTransition {
     pub exited_at: Option<DateTime<Utc>>,
 }
 
+/// A change of the active context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextChange {
+    /// The new context (None if it was cleared)
+    pub context: Option<String>,
+    /// When it changed
+    pub changed_at: DateTime<Utc>,
+}
+
 /// The complete modal state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModalState {
@@ -84,21 +190,25 @@ pub struct ModalState {
     pub attention_stack: Vec<AttentionItem>,
     /// History of mode transitions this session
     pub mode_history: Vec<ModeTransition>,
+    /// Changes of the active context this session
+    #[serde(default)]
+    pub context_history: Vec<ContextChange>,
 }
 
 impl Default for ModalState {
     fn default() -> Self {
         let now = Utc::now();
         Self {
-            current_mode: Mode::Default,
+            current_mode: Mode::DEFAULT,
             mode_entered_at: now,
             active_context: None,
             attention_stack: Vec::new(),
             mode_history: vec![ModeTransition {
-                mode: Mode::Default,
+                mode: Mode::DEFAULT,
                 entered_at: now,
                 exited_at: None,
             }],
+            context_history: Vec::new(),
         }
     }
 }
@@ -108,7 +218,7 @@ impl ModalState {
     pub fn new(mode: Mode) -> Self {
         let now = Utc::now();
         Self {
-            current_mode: mode,
+            current_mode: mode.clone(),
             mode_entered_at: now,
             active_context: None,
             attention_stack: Vec::new(),
@@ -117,6 +227,7 @@ impl ModalState {
                 entered_at: now,
                 exited_at: None,
             }],
+            context_history: Vec::new(),
         }
     }
 
@@ -132,7 +243,7 @@ impl ModalState {
         }
 
         // Update current mode
-        self.current_mode = mode;
+        self.current_mode = mode.clone();
         self.mode_entered_at = now;
 
         // Add to history
@@ -145,44 +256,149 @@ impl ModalState {
 
     /// Set the active context
     pub fn set_context(&mut self, context: Option<String>) {
+        self.context_history.push(ContextChange {
+            context: context.clone(),
+            changed_at: Utc::now(),
+        });
         self.active_context = context;
     }
 
     /// Add an item to the attention stack
-    pub fn add_attention(&mut self, id: String, description: String) -> &AttentionItem {
+    pub fn add_attention(
+        &mut self,
+        id: String,
+        description: String,
+    ) -> Result<&AttentionItem, AttentionError> {
+        let id = id.trim().to_string();
+        if id.is_empty() {
+            return Err(AttentionError::EmptyId);
+        }
+        if self.attention_stack.iter().any(|i| i.id == id) {
+            return Err(AttentionError::Duplicate(id));
+        }
+        let description = validate_description(description)?;
+
         let now = Utc::now();
         let item = AttentionItem {
-            id: id.clone(),
+            id,
             description,
             status: AttentionStatus::Waiting,
             surfaced_at: now,
             updated_at: now,
+            status_since: Some(now),
             notes: None,
+            priority: Priority::default(),
+            tags: Vec::new(),
         };
         self.attention_stack.push(item);
-        self.attention_stack.last().unwrap()
+        Ok(self.attention_stack.last().unwrap())
     }
 
-    /// Mark an attention item as hot (being worked on)
-    pub fn mark_hot(&mut self, id: &str) -> Option<&AttentionItem> {
-        if let Some(item) = self.attention_stack.iter_mut().find(|i| i.id == id) {
-            item.status = AttentionStatus::Hot;
-            item.updated_at = Utc::now();
-            Some(item)
-        } else {
-            None
-        }
+    /// Apply `change` to an item and bump its `updated_at`
+    fn update_item(
+        &mut self,
+        id: &str,
+        change: impl FnOnce(&mut AttentionItem),
+    ) -> Result<&AttentionItem, AttentionError> {
+        let item = self
+            .attention_stack
+            .iter_mut()
+            .find(|i| i.id == id)
+            .ok_or_else(|| AttentionError::NotFound(id.to_string()))?;
+        change(item);
+        item.updated_at = Utc::now();
+        Ok(item)
     }
 
-    /// Mark an attention item as handled
-    pub fn mark_handled(&mut self, id: &str) -> Option<&AttentionItem> {
-        if let Some(item) = self.attention_stack.iter_mut().find(|i| i.id == id) {
-            item.status = AttentionStatus::Handled;
-            item.updated_at = Utc::now();
-            Some(item)
-        } else {
-            None
-        }
+    /// Move an attention item to `status`
+    pub fn set_status(
+        &mut self,
+        id: &str,
+        status: AttentionStatus,
+    ) -> Result<&AttentionItem, AttentionError> {
+        self.update_item(id, |item| {
+            if item.status != status {
+                item.status = status;
+                item.status_since = Some(Utc::now());
+            }
+        })
+    }
+
+    /// Replace an item's description
+    pub fn set_description(
+        &mut self,
+        id: &str,
+        description: String,
+    ) -> Result<&AttentionItem, AttentionError> {
+        let description = validate_description(description)?;
+        self.update_item(id, |item| item.description = description)
+    }
+
+    /// Replace an item's notes (`None` or blank clears them)
+    pub fn set_notes(
+        &mut self,
+        id: &str,
+        notes: Option<String>,
+    ) -> Result<&AttentionItem, AttentionError> {
+        let notes = notes.filter(|n| !n.trim().is_empty());
+        self.update_item(id, |item| item.notes = notes)
+    }
+
+    /// Change an item's priority
+    pub fn set_priority(
+        &mut self,
+        id: &str,
+        priority: Priority,
+    ) -> Result<&AttentionItem, AttentionError> {
+        self.update_item(id, |item| item.priority = priority)
+    }
+
+    /// Add and remove tags on an item
+    ///
+    /// Tags are trimmed and a leading `#` is dropped; adding a tag the item
+    /// already has is a no-op. Nothing changes if any tag is invalid.
+    pub fn tag_attention(
+        &mut self,
+        id: &str,
+        add: &[String],
+        remove: &[String],
+    ) -> Result<&AttentionItem, AttentionError> {
+        let add = add
+            .iter()
+            .map(|t| normalize_tag(t))
+            .collect::<Result<Vec<_>, _>>()?;
+        let remove = remove
+            .iter()
+            .map(|t| normalize_tag(t))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.update_item(id, |item| {
+            item.tags.retain(|t| !remove.contains(t));
+            for tag in add {
+                if !item.tags.contains(&tag) {
+                    item.tags.push(tag);
+                }
+            }
+        })
+    }
+
+    /// Remove an item from the stack
+    pub fn remove_attention(&mut self, id: &str) -> Result<AttentionItem, AttentionError> {
+        let index = self
+            .attention_stack
+            .iter()
+            .position(|i| i.id == id)
+            .ok_or_else(|| AttentionError::NotFound(id.to_string()))?;
+        Ok(self.attention_stack.remove(index))
+    }
+
+    /// Remove every handled item, returning them
+    pub fn clear_handled(&mut self) -> Vec<AttentionItem> {
+        let (handled, open) = std::mem::take(&mut self.attention_stack)
+            .into_iter()
+            .partition(|i| i.status == AttentionStatus::Handled);
+        self.attention_stack = open;
+        handled
     }
 
     /// Get items by status
@@ -208,10 +424,64 @@ impl ModalState {
         self.get_by_status(AttentionStatus::Handled)
     }
 
+    /// Attention items, highest priority first (stack order within a priority)
+    pub fn prioritized(&self) -> Vec<&AttentionItem> {
+        let mut items: Vec<&AttentionItem> = self.attention_stack.iter().collect();
+        items.sort_by_key(|i| std::cmp::Reverse(i.priority));
+        items
+    }
+
     /// Get duration in current mode
     pub fn mode_duration(&self) -> chrono::Duration {
         Utc::now() - self.mode_entered_at
     }
+
+    /// Take the attention stack from the snapshot at `path`
+    ///
+    /// Mode and context start fresh. Returns how many items were restored;
+    /// a missing snapshot restores nothing.
+    pub fn restore_attention(&mut self, path: &Path) -> std::io::Result<usize> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+
+        let saved: ModalState = serde_json::from_str(&json).map_err(std::io::Error::other)?;
+        self.attention_stack = saved.attention_stack;
+        Ok(self.attention_stack.len())
+    }
+}
+
+/// Write `value` as JSON to `path`
+///
+/// Writes to a temporary file first and renames it into place, so readers
+/// never see a partially written snapshot.
+pub fn write_snapshot<T: Serialize>(value: &T, path: &Path) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let json = serde_json::to_string_pretty(value).map_err(std::io::Error::other)?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, path)
+}
+
+fn validate_description(description: String) -> Result<String, AttentionError> {
+    let description = description.trim();
+    if description.is_empty() {
+        return Err(AttentionError::EmptyDescription);
+    }
+    Ok(description.to_string())
+}
+
+fn normalize_tag(tag: &str) -> Result<String, AttentionError> {
+    let normalized = tag.trim().trim_start_matches('#');
+    if normalized.is_empty() || normalized.contains(char::is_whitespace) {
+        return Err(AttentionError::InvalidTag(tag.to_string()));
+    }
+    Ok(normalized.to_string())
 }
 
 /// Thread-safe state wrapper
@@ -234,10 +504,10 @@ mod tests {
     #[test]
     fn test_mode_transitions() {
         let mut state = ModalState::default();
-        assert_eq!(state.current_mode, Mode::Default);
+        assert_eq!(state.current_mode, Mode::DEFAULT);
 
-        state.enter_mode(Mode::Ops);
-        assert_eq!(state.current_mode, Mode::Ops);
+        state.enter_mode(Mode::OPS);
+        assert_eq!(state.current_mode, Mode::OPS);
         assert_eq!(state.mode_history.len(), 2);
         assert!(state.mode_history[0].exited_at.is_some());
         assert!(state.mode_history[1].exited_at.is_none());
@@ -247,20 +517,174 @@ mod tests {
     fn test_attention_stack() {
         let mut state = ModalState::default();
 
-        state.add_attention("ins-1".to_string(), "Follow up on insurance".to_string());
-        state.add_attention("cal-1".to_string(), "Schedule doctor appointment".to_string());
+        state
+            .add_attention("ins-1".to_string(), "Follow up on insurance".to_string())
+            .unwrap();
+        state
+            .add_attention(
+                "cal-1".to_string(),
+                "Schedule doctor appointment".to_string(),
+            )
+            .unwrap();
 
         assert_eq!(state.attention_stack.len(), 2);
         assert_eq!(state.waiting_items().len(), 2);
         assert_eq!(state.hot_items().len(), 0);
 
-        state.mark_hot("ins-1");
+        state.set_status("ins-1", AttentionStatus::Hot).unwrap();
         assert_eq!(state.hot_items().len(), 1);
         assert_eq!(state.waiting_items().len(), 1);
 
-        state.mark_handled("ins-1");
+        state.set_status("ins-1", AttentionStatus::Handled).unwrap();
         assert_eq!(state.handled_items().len(), 1);
         assert_eq!(state.hot_items().len(), 0);
+
+        state.set_status("ins-1", AttentionStatus::Waiting).unwrap();
+        assert_eq!(state.waiting_items().len(), 2);
+
+        assert!(state.set_status("missing", AttentionStatus::Hot).is_err());
+    }
+
+    #[test]
+    fn test_attention_validation() {
+        let mut state = ModalState::default();
+        state
+            .add_attention("a".to_string(), "First".to_string())
+            .unwrap();
+
+        assert_eq!(
+            state
+                .add_attention("a".to_string(), "Again".to_string())
+                .unwrap_err(),
+            AttentionError::Duplicate("a".to_string())
+        );
+        assert_eq!(
+            state
+                .add_attention(" ".to_string(), "x".to_string())
+                .unwrap_err(),
+            AttentionError::EmptyId
+        );
+        assert_eq!(
+            state.set_description("a", "  ".to_string()).unwrap_err(),
+            AttentionError::EmptyDescription
+        );
+        assert_eq!(
+            state.set_priority("nope", Priority::High).unwrap_err(),
+            AttentionError::NotFound("nope".to_string())
+        );
+        assert_eq!(
+            state
+                .tag_attention("a", &["ok".to_string(), "two words".to_string()], &[])
+                .unwrap_err(),
+            AttentionError::InvalidTag("two words".to_string())
+        );
+        assert!(state.attention_stack[0].tags.is_empty());
+    }
+
+    #[test]
+    fn test_attention_edits() {
+        let mut state = ModalState::default();
+        state
+            .add_attention("a".to_string(), "Call insurer".to_string())
+            .unwrap();
+        state
+            .add_attention("b".to_string(), "Renew passport".to_string())
+            .unwrap();
+
+        state
+            .set_description("a", "Call insurer re: claim".to_string())
+            .unwrap();
+        state.set_notes("a", Some("Ref 1234".to_string())).unwrap();
+        state.set_priority("b", Priority::Urgent).unwrap();
+        state
+            .tag_attention("a", &["#health".to_string(), "money".to_string()], &[])
+            .unwrap();
+        let item = state
+            .tag_attention("a", &["health".to_string()], &["money".to_string()])
+            .unwrap();
+        assert_eq!(item.tags, vec!["health".to_string()]);
+        assert_eq!(item.description, "Call insurer re: claim");
+        assert_eq!(item.notes.as_deref(), Some("Ref 1234"));
+
+        let order: Vec<&str> = state.prioritized().iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(order, vec!["b", "a"]);
+
+        state.set_notes("a", Some(String::new())).unwrap();
+        assert!(state.attention_stack[0].notes.is_none());
+    }
+
+    #[test]
+    fn test_status_since_tracks_status_changes_only() {
+        let mut state = ModalState::default();
+        state
+            .add_attention("a".to_string(), "Call insurer".to_string())
+            .unwrap();
+        let added = state.attention_stack[0].status_changed_at();
+
+        state.set_notes("a", Some("Ref 1234".to_string())).unwrap();
+        state.set_status("a", AttentionStatus::Waiting).unwrap();
+        assert_eq!(state.attention_stack[0].status_changed_at(), added);
+
+        state.set_status("a", AttentionStatus::Hot).unwrap();
+        let item = &state.attention_stack[0];
+        assert!(item.status_changed_at() >= added);
+        assert!(item.status_since.is_some());
+
+        // Snapshots from before status_since fall back to updated_at
+        let mut old = serde_json::to_value(item).unwrap();
+        old.as_object_mut().unwrap().remove("status_since");
+        let old: AttentionItem = serde_json::from_value(old).unwrap();
+        assert_eq!(old.status_changed_at(), old.updated_at);
+    }
+
+    #[test]
+    fn test_remove_and_clear_handled() {
+        let mut state = ModalState::default();
+        for id in ["a", "b", "c"] {
+            state
+                .add_attention(id.to_string(), format!("Item {}", id))
+                .unwrap();
+        }
+
+        assert_eq!(state.remove_attention("b").unwrap().id, "b");
+        assert!(state.remove_attention("b").is_err());
+
+        state.set_status("a", AttentionStatus::Handled).unwrap();
+        let cleared = state.clear_handled();
+        assert_eq!(cleared.len(), 1);
+        assert_eq!(state.attention_stack.len(), 1);
+        assert_eq!(state.attention_stack[0].id, "c");
+    }
+
+    #[test]
+    fn test_write_snapshot() {
+        let dir = std::env::temp_dir().join(format!("aiandi-mode-test-{}", std::process::id()));
+        let path = dir.join("nested").join("mode-state.json");
+
+        let mut state = ModalState::default();
+        state.enter_mode(Mode::OPS);
+        state
+            .add_attention("ins-1".to_string(), "Follow up on insurance".to_string())
+            .unwrap();
+        state.set_priority("ins-1", Priority::High).unwrap();
+        write_snapshot(&state, &path).expect("snapshot should be written");
+
+        let json = std::fs::read_to_string(&path).unwrap();
+        let restored: ModalState = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.current_mode, Mode::OPS);
+        assert_eq!(restored.attention_stack.len(), 1);
+        assert_eq!(restored.attention_stack[0].status, AttentionStatus::Waiting);
+
+        let mut fresh = ModalState::default();
+        assert_eq!(fresh.restore_attention(&path).unwrap(), 1);
+        assert_eq!(fresh.current_mode, Mode::DEFAULT);
+        assert_eq!(fresh.attention_stack[0].priority, Priority::High);
+        assert_eq!(
+            fresh.restore_attention(&dir.join("missing.json")).unwrap(),
+            0
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
     }
 
     #[test]
@@ -276,5 +700,7 @@ mod tests {
 
         state.set_context(None);
         assert!(state.active_context.is_none());
+        assert_eq!(state.context_history.len(), 2);
+        assert!(state.context_history[1].context.is_none());
     }
 }
@@ -0,0 +1,407 @@
+//! Session close reports as transmissions
+//!
+//! Builds a `<transmission type="report">` from the modal state with
+//! `aiandi-transmission`, following
+//! `governance/canon/transmission-protocol.md` (schema `transmission-v1`):
+//! mode history, context changes, handled items, and outstanding items as
+//! open questions. The file goes to the outbox, named as the transmission
+//! skill asks:
+//!
+//! ```text
+//! Transmission_[Thread-ID]_Report_[Desc].xml
+//! ```
+
+use crate::state::{AttentionItem, AttentionStatus, ModalState};
+use aiandi_transmission::{Element, Node, Priority, Thread, Transmission, TransmissionType};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the outbox location
+pub const OUTBOX_ENV: &str = "AIANDI_OUTBOX";
+
+/// Where reports are written: `$AIANDI_OUTBOX`, else `outbox/` in the
+/// working directory
+pub fn default_outbox() -> PathBuf {
+    std::env::var(OUTBOX_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("outbox"))
+}
+
+/// Why a report could not be written
+#[derive(Debug, thiserror::Error)]
+pub enum TransmissionError {
+    #[error("Invalid thread id '{0}': use letters, digits, '-', '_' or '.'")]
+    InvalidThreadId(String),
+    #[error("Invalid description '{0}': use letters, digits, spaces, '-', '_' or '.'")]
+    InvalidDescription(String),
+    #[error("Thread position must be 1 or more")]
+    InvalidPosition,
+    #[error("Report is not a valid transmission:\n{0}")]
+    Invalid(aiandi_transmission::ValidationErrors),
+    #[error("{} already exists; use another description or position", .0.display())]
+    Exists(PathBuf),
+    #[error("Failed to write {}: {source}", path.display())]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Who the report is from and where it sits in its thread
+#[derive(Debug, Clone)]
+pub struct ReportHeader {
+    pub thread_id: String,
+    pub position: u32,
+    /// Id of the transmission this answers, if any
+    pub in_reply_to: Option<String>,
+    /// Short description for the file name (e.g. "Session Close")
+    pub description: String,
+    pub from: String,
+    pub to: String,
+    /// Background for the reader; a generic line if omitted
+    pub summary: Option<String>,
+}
+
+impl ReportHeader {
+    fn validate(&self) -> Result<(), TransmissionError> {
+        if !valid_segment(&self.thread_id) {
+            return Err(TransmissionError::InvalidThreadId(self.thread_id.clone()));
+        }
+        if self.position == 0 {
+            return Err(TransmissionError::InvalidPosition);
+        }
+        if !valid_segment(&file_description(&self.description)) {
+            return Err(TransmissionError::InvalidDescription(
+                self.description.clone(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// `Transmission_[Thread-ID]_Report_[Desc].xml`, spaces in the
+    /// description becoming `-`
+    pub fn file_name(&self) -> Result<String, TransmissionError> {
+        self.validate()?;
+        Ok(format!(
+            "Transmission_{}_Report_{}.xml",
+            self.thread_id,
+            file_description(&self.description)
+        ))
+    }
+}
+
+/// Render and write the report into `outbox`, returning its path
+///
+/// Never overwrites: an existing file with the same name is an error.
+pub fn write_report(
+    outbox: &Path,
+    header: &ReportHeader,
+    state: &ModalState,
+    now: DateTime<Utc>,
+) -> Result<PathBuf, TransmissionError> {
+    let path = outbox.join(header.file_name()?);
+    let report = build_report(header, state, now);
+    report.validate().map_err(TransmissionError::Invalid)?;
+    let xml = report.to_xml();
+
+    let write = || -> std::io::Result<()> {
+        std::fs::create_dir_all(outbox)?;
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?
+            .write_all(xml.as_bytes())
+    };
+    match write() {
+        Ok(()) => Ok(path),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+            Err(TransmissionError::Exists(path))
+        }
+        Err(source) => Err(TransmissionError::Write { path, source }),
+    }
+}
+
+/// The report as a `transmission-v1` document
+pub fn build_report(header: &ReportHeader, state: &ModalState, now: DateTime<Utc>) -> Transmission {
+    let started = state
+        .mode_history
+        .first()
+        .map(|t| t.entered_at)
+        .unwrap_or(state.mode_entered_at);
+    let (handled, outstanding): (Vec<&AttentionItem>, Vec<&AttentionItem>) = state
+        .prioritized()
+        .into_iter()
+        .partition(|i| i.status == AttentionStatus::Handled);
+
+    let mut t = Transmission::new(TransmissionType::Report);
+    t.id = Some(format!(
+        "session-{}-{}-{}",
+        now.format("%Y-%m-%d"),
+        header.thread_id,
+        header.position
+    ));
+    t.from = Some(clean(&header.from));
+    t.to = Some(clean(&header.to));
+    t.date = Some(now.format("%Y-%m-%d").to_string());
+    t.thread = Some(Thread {
+        id: header.thread_id.clone(),
+        position: Some(header.position),
+        in_reply_to: header.in_reply_to.as_deref().map(clean),
+    });
+    t.priority = Some(Priority::Standard);
+    t.priority_reason = Some("Session close report".to_string());
+
+    let summary = header.summary.clone().unwrap_or_else(|| {
+        format!(
+            "Closing report for the session that began {}: the modes it moved \
+             through, what it was working on, and the attention stack as it \
+             stands at close.",
+            timestamp(started)
+        )
+    });
+    t.context = Some(text_element("context", &[], &summary));
+
+    let content = &mut t.payload;
+    content.children.push(Node::Element(text_element(
+        "summary",
+        &[],
+        &format!(
+            "Mode stints: {}. Context changes: {}. Handled: {}. Outstanding: {}. Closed in {} mode.",
+            state.mode_history.len(),
+            state.context_history.len(),
+            handled.len(),
+            outstanding.len(),
+            state.current_mode.as_str()
+        ),
+    )));
+
+    let stints = state.mode_history.iter().map(|stint| {
+        let exited = stint.exited_at.unwrap_or(now);
+        let mut attributes = vec![
+            ("mode", stint.mode.as_str().to_string()),
+            ("entered-at", timestamp(stint.entered_at)),
+            ("exited-at", timestamp(exited)),
+            (
+                "minutes",
+                (exited - stint.entered_at).num_minutes().to_string(),
+            ),
+        ];
+        if stint.exited_at.is_none() {
+            attributes.push(("current", "true".to_string()));
+        }
+        element("stint", &attributes)
+    });
+    content.children.push(section("mode-history", stints));
+
+    let changes = state
+        .context_history
+        .iter()
+        .map(|change| match &change.context {
+            Some(context) => {
+                text_element("change", &[("at", timestamp(change.changed_at))], context)
+            }
+            None => element(
+                "change",
+                &[
+                    ("at", timestamp(change.changed_at)),
+                    ("cleared", "true".to_string()),
+                ],
+            ),
+        });
+    content.children.push(section("context-changes", changes));
+
+    content.children.push(section(
+        "attention-handled",
+        handled.iter().map(|item| attention_item(item)),
+    ));
+    content.children.push(section(
+        "open-questions",
+        outstanding.iter().map(|item| attention_item(item)),
+    ));
+
+    t.response_spec = Some(
+        Element::new("response-spec")
+            .with_text_child(
+                "format",
+                "No reply required; send type=\"instruction\" to redirect outstanding items",
+            )
+            .with_text_child("delivery", "Read from the outbox")
+            .with_text_child(
+                "success-criteria",
+                "The next session can pick up the open questions without this one's context",
+            ),
+    );
+    t.closing = Some(text_element(
+        "closing",
+        &[],
+        if outstanding.is_empty() {
+            "Nothing left open. The attention stack is clear."
+        } else {
+            "Open questions stay on the attention stack for the next session."
+        },
+    ));
+    t
+}
+
+fn element(name: &str, attributes: &[(&str, String)]) -> Element {
+    let mut element = Element::new(name);
+    element.attributes = attributes
+        .iter()
+        .map(|(name, value)| (name.to_string(), clean(value)))
+        .collect();
+    element
+}
+
+fn text_element(name: &str, attributes: &[(&str, String)], text: &str) -> Element {
+    let mut element = element(name, attributes);
+    element.children.push(Node::Text(clean(text)));
+    element
+}
+
+/// `<name>` holding `children`; `<name/>` if there are none
+fn section(name: &str, children: impl Iterator<Item = Element>) -> Node {
+    let mut section = Element::new(name);
+    section.children = children.map(Node::Element).collect();
+    Node::Element(section)
+}
+
+fn attention_item(item: &AttentionItem) -> Element {
+    let status = match item.status {
+        AttentionStatus::Hot => "hot",
+        AttentionStatus::Waiting => "waiting",
+        AttentionStatus::Handled => "handled",
+    };
+    let mut element = element(
+        "item",
+        &[
+            ("id", item.id.clone()),
+            ("status", status.to_string()),
+            ("priority", item.priority.to_string()),
+            ("surfaced-at", timestamp(item.surfaced_at)),
+            ("updated-at", timestamp(item.updated_at)),
+        ],
+    )
+    .with_text_child("description", &clean(&item.description));
+    if let Some(notes) = &item.notes {
+        element = element.with_text_child("notes", &clean(notes));
+    }
+    if !item.tags.is_empty() {
+        let tags = item.tags.iter().map(|tag| text_element("tag", &[], tag));
+        element.children.push(section("tags", tags));
+    }
+    element
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Drop characters XML 1.0 does not allow at all; escaping is left to
+/// the writer
+fn clean(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t'))
+        .collect()
+}
+
+fn file_description(description: &str) -> String {
+    description.split_whitespace().collect::<Vec<_>>().join("-")
+}
+
+fn valid_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && !segment.starts_with('.')
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Mode;
+
+    fn header(thread_id: &str, description: &str) -> ReportHeader {
+        ReportHeader {
+            thread_id: thread_id.to_string(),
+            position: 3,
+            in_reply_to: Some("gov-2026-10-19-ops".to_string()),
+            description: description.to_string(),
+            from: "aiandi Agent".to_string(),
+            to: "Governance Committee".to_string(),
+            summary: None,
+        }
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(
+            header("ops-weekly", "Session Close").file_name().unwrap(),
+            "Transmission_ops-weekly_Report_Session-Close.xml"
+        );
+        assert!(matches!(
+            header("../etc", "x").file_name(),
+            Err(TransmissionError::InvalidThreadId(_))
+        ));
+        assert!(matches!(
+            header("ops", "a/b").file_name(),
+            Err(TransmissionError::InvalidDescription(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_report() {
+        let mut state = ModalState::default();
+        state.enter_mode(Mode::OPS);
+        state.set_context(Some("Insurance & claims".to_string()));
+        state
+            .add_attention("ins-1".to_string(), "Call <insurer>".to_string())
+            .unwrap();
+        state
+            .add_attention("cal-1".to_string(), "Book dentist".to_string())
+            .unwrap();
+        state.set_status("cal-1", AttentionStatus::Handled).unwrap();
+
+        let xml = build_report(&header("ops-weekly", "Close"), &state, Utc::now()).to_xml();
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<transmission"));
+        assert!(xml.contains("schema=\"transmission-v1\"\n  type=\"report\">"));
+        assert!(xml.contains("<thread id=\"ops-weekly\">\n      <position>3</position>"));
+        assert!(xml.contains("<in-reply-to>gov-2026-10-19-ops</in-reply-to>"));
+        assert!(xml.contains("<stint mode=\"ops\""));
+        assert!(xml.contains("current=\"true\"/>"));
+        assert!(xml.contains(">Insurance &amp; claims</change>"));
+        assert!(xml.contains("<description>Call &lt;insurer&gt;</description>"));
+
+        let handled = xml.find("<attention-handled>").unwrap();
+        let open = xml.find("<open-questions>").unwrap();
+        assert!(xml[handled..open].contains("id=\"cal-1\""));
+        assert!(xml[open..].contains("id=\"ins-1\""));
+        assert!(xml.ends_with("</transmission>\n"));
+
+        // Reads back as a valid canonical report
+        let parsed = aiandi_transmission::parse(&xml).expect("report should parse");
+        assert_eq!(parsed.kind, TransmissionType::Report);
+        assert_eq!(parsed.shape, aiandi_transmission::Shape::Canonical);
+        assert_eq!(parsed.thread.as_ref().unwrap().position, Some(3));
+        assert!(parsed.payload.contains("open-questions"));
+        parsed.validate().expect("report should validate");
+    }
+
+    #[test]
+    fn test_write_report_never_overwrites() {
+        let dir = std::env::temp_dir().join(format!("aiandi-outbox-test-{}", std::process::id()));
+        let state = ModalState::default();
+        let header = header("ops-weekly", "Close");
+
+        let path = write_report(&dir, &header, &state, Utc::now()).unwrap();
+        assert!(path.ends_with("Transmission_ops-weekly_Report_Close.xml"));
+        assert!(matches!(
+            write_report(&dir, &header, &state, Utc::now()),
+            Err(TransmissionError::Exists(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
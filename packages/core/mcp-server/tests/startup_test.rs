@@ -0,0 +1,58 @@
+//! Starting the server replaces the previous session's snapshot
+
+use aiandi_mode::policy::{Snapshot, ToolPolicy};
+use aiandi_mode::state::{self, ModalState, Mode};
+use std::process::{Command, Stdio};
+
+#[test]
+fn test_startup_rewrites_stale_snapshot() {
+    let dir = std::env::temp_dir().join(format!("aiandi-mode-startup-{}", std::process::id()));
+    let path = dir.join("mode-state.json");
+
+    // Left behind by a session that ended in ops mode
+    let mut stale = ModalState::default();
+    stale.enter_mode(Mode::OPS);
+    stale
+        .add_attention("ins-1".to_string(), "Follow up on insurance".to_string())
+        .unwrap();
+    state::write_snapshot(&Snapshot::new(&stale, &ToolPolicy::default()), &path).unwrap();
+
+    // stdin is closed at once, so the server exits after starting up
+    let status = Command::new(env!("CARGO_BIN_EXE_aiandi-mode"))
+        .env("HOME", &dir)
+        .env("XDG_CONFIG_HOME", dir.join("config"))
+        .env("XDG_STATE_HOME", dir.join("state"))
+        .env("AIANDI_MODE_STATE", &path)
+        .env_remove("AIANDI_MODE_POLICY")
+        .env_remove("AIANDI_MODES")
+        .env_remove("AIANDI_MODE_HISTORY")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .expect("aiandi-mode should run");
+    assert!(
+        status.code().is_some(),
+        "aiandi-mode was killed: {}",
+        status
+    );
+
+    let json = std::fs::read_to_string(&path).unwrap();
+    let snapshot: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(snapshot["current_mode"], "default", "{}", json);
+    assert_eq!(
+        snapshot["blocked_tools"],
+        serde_json::json!(["create_event", "send_email"]),
+        "{}",
+        json
+    );
+    assert_eq!(snapshot["attention_stack"][0]["id"], "ins-1", "{}", json);
+    assert_ne!(
+        snapshot["owner_pid"],
+        serde_json::json!(std::process::id()),
+        "{}",
+        json
+    );
+
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,388 @@
+//! Birthday calendar - derived from the contacts vdir
+//!
+//! khal only knows about events in calendar vdirs, but our vCards carry
+//! `BDAY` and `ANNIVERSARY`. This module reads those properties straight from
+//! the contacts vdir and projects them onto a virtual "birthdays" calendar
+//! that can be merged into event listings.
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use std::path::PathBuf;
+
+/// Name of the virtual calendar, shown like a khal calendar name
+pub const BIRTHDAY_CALENDAR: &str = "birthdays";
+
+/// Default number of days `upcoming_birthdays` looks ahead
+pub const DEFAULT_LOOKAHEAD_DAYS: u32 = 30;
+
+/// What kind of yearly occasion a contact date represents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OccasionKind {
+    Birthday,
+    Anniversary,
+}
+
+impl std::fmt::Display for OccasionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OccasionKind::Birthday => write!(f, "Birthday"),
+            OccasionKind::Anniversary => write!(f, "Anniversary"),
+        }
+    }
+}
+
+/// A yearly recurring date read from a vCard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occasion {
+    /// Contact display name (FN)
+    pub name: String,
+    pub kind: OccasionKind,
+    pub month: u32,
+    pub day: u32,
+    /// Original year, if the vCard records one
+    pub year: Option<i32>,
+}
+
+/// A single occurrence of an occasion within a date range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occurrence {
+    pub date: NaiveDate,
+    pub name: String,
+    pub kind: OccasionKind,
+    /// Age or number of years, if the original year is known
+    pub years: Option<i32>,
+}
+
+impl Occurrence {
+    /// `Birthday: Name (turns N)`
+    fn summary(&self) -> String {
+        let suffix = match (self.kind, self.years) {
+            (OccasionKind::Birthday, Some(n)) => format!(" (turns {})", n),
+            (OccasionKind::Anniversary, Some(n)) => format!(" ({} years)", n),
+            (_, None) => String::new(),
+        };
+        format!("{}: {}{}", self.kind, self.name, suffix)
+    }
+
+    /// Format as a calendar line: `[birthdays] YYYY-MM-DD Birthday: Name (turns N)`
+    pub fn to_line(&self) -> String {
+        format!(
+            "[{}] {} {}",
+            BIRTHDAY_CALENDAR,
+            self.date.format("%Y-%m-%d"),
+            self.summary()
+        )
+    }
+
+    /// Format like a khal all-day event inside a day listing:
+    /// `[birthdays] Birthday: Name (turns N)`
+    pub fn to_event_line(&self) -> String {
+        format!("[{}] {}", BIRTHDAY_CALENDAR, self.summary())
+    }
+}
+
+/// Parse a vCard date value into (month, day, year)
+///
+/// Accepts `YYYYMMDD`, `YYYY-MM-DD`, `--MMDD` and `--MM-DD`, with an optional
+/// trailing time component (`T...`) which is ignored.
+pub fn parse_vcard_date(value: &str) -> Option<(u32, u32, Option<i32>)> {
+    let date = value.trim().split('T').next()?;
+
+    if let Some(rest) = date.strip_prefix("--") {
+        let digits: String = rest.chars().filter(|c| *c != '-').collect();
+        if digits.len() != 4 {
+            return None;
+        }
+        let month = digits[0..2].parse().ok()?;
+        let day = digits[2..4].parse().ok()?;
+        // Validate against a leap year so Feb 29 is accepted
+        NaiveDate::from_ymd_opt(2000, month, day)?;
+        return Some((month, day, None));
+    }
+
+    let digits: String = date.chars().filter(|c| *c != '-').collect();
+    if digits.len() != 8 {
+        return None;
+    }
+    let parsed = NaiveDate::parse_from_str(&digits, "%Y%m%d").ok()?;
+    Some((parsed.month(), parsed.day(), Some(parsed.year())))
+}
+
+/// Unfold vCard content lines (RFC 6350 §3.2)
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        let raw = raw.trim_end_matches('\r');
+        if let Some(cont) = raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t'))
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(cont);
+            continue;
+        }
+        lines.push(raw.to_string());
+    }
+    lines
+}
+
+/// Extract birthday and anniversary occasions from a vCard
+pub fn parse_vcard_occasions(content: &str) -> Vec<Occasion> {
+    let mut name: Option<String> = None;
+    let mut dates: Vec<(OccasionKind, u32, u32, Option<i32>)> = Vec::new();
+
+    for line in unfold(content) {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        let mut parts = key.split(';');
+        let property = parts.next().unwrap_or_default().to_uppercase();
+        let omit_year = parts.any(|p| p.to_uppercase().starts_with("X-APPLE-OMIT-YEAR"));
+
+        let kind = match property.as_str() {
+            "FN" => {
+                name = Some(value.trim().to_string());
+                continue;
+            }
+            "BDAY" => OccasionKind::Birthday,
+            "ANNIVERSARY" | "X-ANNIVERSARY" => OccasionKind::Anniversary,
+            _ => continue,
+        };
+
+        if let Some((month, day, year)) = parse_vcard_date(value) {
+            let year = if omit_year { None } else { year };
+            dates.push((kind, month, day, year));
+        }
+    }
+
+    let Some(name) = name.filter(|n| !n.is_empty()) else {
+        return Vec::new();
+    };
+
+    dates
+        .into_iter()
+        .map(|(kind, month, day, year)| Occasion {
+            name: name.clone(),
+            kind,
+            month,
+            day,
+            year,
+        })
+        .collect()
+}
+
+/// Project an occasion onto a given year
+///
+/// Feb 29 occasions fall on Feb 28 in non-leap years.
+fn date_in_year(occasion: &Occasion, year: i32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, occasion.month, occasion.day).or_else(|| {
+        if occasion.month == 2 && occasion.day == 29 {
+            NaiveDate::from_ymd_opt(year, 2, 28)
+        } else {
+            None
+        }
+    })
+}
+
+/// All occurrences of `occasions` in `[start, start + days)`, sorted by date
+pub fn occurrences_in_range(
+    occasions: &[Occasion],
+    start: NaiveDate,
+    days: u32,
+) -> Vec<Occurrence> {
+    let end = start + Duration::days(i64::from(days));
+    let mut result = Vec::new();
+
+    for occasion in occasions {
+        for year in start.year()..=end.year() {
+            let Some(date) = date_in_year(occasion, year) else {
+                continue;
+            };
+            if date < start || date >= end {
+                continue;
+            }
+            let years = occasion.year.map(|y| year - y).filter(|n| *n > 0);
+            result.push(Occurrence {
+                date,
+                name: occasion.name.clone(),
+                kind: occasion.kind,
+                years,
+            });
+        }
+    }
+
+    result.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.name.cmp(&b.name)));
+    result
+}
+
+/// Virtual calendar built from the contacts vdir
+#[derive(Clone)]
+pub struct BirthdayCalendar {
+    contacts_dir: PathBuf,
+}
+
+impl BirthdayCalendar {
+    pub fn new(contacts_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            contacts_dir: contacts_dir.into(),
+        }
+    }
+
+    /// Read every `.vcf` file in the contacts vdir and collect its occasions
+    ///
+    /// Unreadable files are skipped; a missing directory yields no occasions.
+    pub async fn load_occasions(&self) -> Vec<Occasion> {
+        let mut occasions = Vec::new();
+
+        let Ok(mut entries) = tokio::fs::read_dir(&self.contacts_dir).await else {
+            return occasions;
+        };
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("vcf") {
+                continue;
+            }
+            if let Ok(content) = tokio::fs::read_to_string(&path).await {
+                occasions.extend(parse_vcard_occasions(&content));
+            }
+        }
+
+        occasions
+    }
+
+    /// Occurrences in `[start, start + days)`
+    pub async fn occurrences(&self, start: NaiveDate, days: u32) -> Vec<Occurrence> {
+        let occasions = self.load_occasions().await;
+        occurrences_in_range(&occasions, start, days)
+    }
+
+    /// List birthdays and anniversaries coming up from today
+    pub async fn upcoming(&self, days: Option<u32>) -> String {
+        let days = days.unwrap_or(DEFAULT_LOOKAHEAD_DAYS);
+        let today = Local::now().date_naive();
+        let occurrences = self.occurrences(today, days).await;
+
+        if occurrences.is_empty() {
+            return format!("No birthdays or anniversaries in the next {} days.", days);
+        }
+
+        let lines: Vec<String> = occurrences.iter().map(Occurrence::to_line).collect();
+        format!(
+            "Birthdays and anniversaries in the next {} days:\n\n{}",
+            days,
+            lines.join("\n")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_vcard_date_formats() {
+        assert_eq!(parse_vcard_date("19850412"), Some((4, 12, Some(1985))));
+        assert_eq!(parse_vcard_date("1985-04-12"), Some((4, 12, Some(1985))));
+        assert_eq!(parse_vcard_date("--0412"), Some((4, 12, None)));
+        assert_eq!(parse_vcard_date("--04-12"), Some((4, 12, None)));
+        assert_eq!(
+            parse_vcard_date("1985-04-12T00:00:00Z"),
+            Some((4, 12, Some(1985)))
+        );
+        assert_eq!(parse_vcard_date("--0229"), Some((2, 29, None)));
+        assert_eq!(parse_vcard_date("not a date"), None);
+    }
+
+    #[test]
+    fn test_parse_vcard_occasions() {
+        let vcard = "BEGIN:VCARD\r\n\
+                     VERSION:4.0\r\n\
+                     FN:Jane Smith\r\n\
+                     BDAY:19850412\r\n\
+                     ANNIVERSARY:2010-06-05\r\n\
+                     END:VCARD\r\n";
+        let occasions = parse_vcard_occasions(vcard);
+
+        assert_eq!(occasions.len(), 2);
+        assert_eq!(occasions[0].name, "Jane Smith");
+        assert_eq!(occasions[0].kind, OccasionKind::Birthday);
+        assert_eq!((occasions[0].month, occasions[0].day), (4, 12));
+        assert_eq!(occasions[1].kind, OccasionKind::Anniversary);
+        assert_eq!(occasions[1].year, Some(2010));
+    }
+
+    #[test]
+    fn test_parse_vcard_occasions_params_and_omit_year() {
+        let vcard = "BEGIN:VCARD\n\
+                     FN:John Doe\n\
+                     BDAY;VALUE=date;X-APPLE-OMIT-YEAR=1604:1604-03-01\n\
+                     END:VCARD\n";
+        let occasions = parse_vcard_occasions(vcard);
+
+        assert_eq!(occasions.len(), 1);
+        assert_eq!(occasions[0].year, None);
+    }
+
+    #[test]
+    fn test_parse_vcard_without_dates() {
+        let vcard = "BEGIN:VCARD\nFN:No Dates\nEMAIL:x@example.com\nEND:VCARD\n";
+        assert!(parse_vcard_occasions(vcard).is_empty());
+    }
+
+    #[test]
+    fn test_occurrences_across_year_boundary() {
+        let occasions = vec![
+            Occasion {
+                name: "New Year Baby".to_string(),
+                kind: OccasionKind::Birthday,
+                month: 1,
+                day: 2,
+                year: Some(2000),
+            },
+            Occasion {
+                name: "Outside Range".to_string(),
+                kind: OccasionKind::Birthday,
+                month: 6,
+                day: 1,
+                year: None,
+            },
+        ];
+        let result = occurrences_in_range(&occasions, date(2025, 12, 28), 7);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].date, date(2026, 1, 2));
+        assert_eq!(result[0].years, Some(26));
+        assert_eq!(
+            result[0].to_line(),
+            "[birthdays] 2026-01-02 Birthday: New Year Baby (turns 26)"
+        );
+        assert_eq!(
+            result[0].to_event_line(),
+            "[birthdays] Birthday: New Year Baby (turns 26)"
+        );
+    }
+
+    #[test]
+    fn test_leap_day_in_non_leap_year() {
+        let occasions = vec![Occasion {
+            name: "Leapling".to_string(),
+            kind: OccasionKind::Birthday,
+            month: 2,
+            day: 29,
+            year: None,
+        }];
+        let result = occurrences_in_range(&occasions, date(2026, 2, 1), 28);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].date, date(2026, 2, 28));
+    }
+
+    #[tokio::test]
+    async fn test_missing_contacts_dir_is_empty() {
+        let calendar = BirthdayCalendar::new("/nonexistent/contacts/dir");
+        assert!(calendar.load_occasions().await.is_empty());
+    }
+}
@@ -0,0 +1,368 @@
+//! Daily briefing - one document for the morning (or evening) check-in
+//!
+//! Gathers today's and tomorrow's events, unread/flagged threads, due and
+//! overdue tasks, and the hot/waiting attention items written by aiandi-mode
+//! into a single JSON document. Each section fails independently: a missing
+//! tool shows up as an `error` in its section instead of sinking the briefing.
+//!
+//! Time-of-day phases follow PLAN in
+//! `packages/second-brain/protocols/gtd-mapping.md`.
+
+use anyhow::{Context, Result};
+use chrono::{Duration, Local, NaiveDate, Timelike};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::calendar::{CalendarTools, EventLines};
+use crate::config::PimConfig;
+use crate::email::{EmailTools, ThreadSummary};
+use crate::tasks::{Task, TaskTools};
+
+/// notmuch query for threads that need a look
+pub const EMAIL_QUERY: &str = "tag:unread or tag:flagged";
+
+/// Maximum number of threads in a briefing
+pub const EMAIL_LIMIT: u32 = 20;
+
+/// PLAN time-of-day phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DayPhase {
+    /// 05:00-11:59
+    Morning,
+    /// 12:00-16:59
+    Afternoon,
+    /// 17:00-20:59
+    Evening,
+    /// 21:00-04:59
+    Night,
+}
+
+impl DayPhase {
+    /// Phase for an hour of the day (0-23)
+    pub fn from_hour(hour: u32) -> Self {
+        match hour {
+            5..=11 => DayPhase::Morning,
+            12..=16 => DayPhase::Afternoon,
+            17..=20 => DayPhase::Evening,
+            _ => DayPhase::Night,
+        }
+    }
+
+    /// Phase for the current local time
+    pub fn now() -> Self {
+        Self::from_hour(Local::now().hour())
+    }
+
+    /// Parse a phase name (case-insensitive)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "morning" => Some(DayPhase::Morning),
+            "afternoon" => Some(DayPhase::Afternoon),
+            "evening" => Some(DayPhase::Evening),
+            "night" => Some(DayPhase::Night),
+            _ => None,
+        }
+    }
+
+    /// What the phase is for
+    pub fn focus(&self) -> &'static str {
+        match self {
+            DayPhase::Morning => "Full day planning",
+            DayPhase::Afternoon => "Remaining runway regroup",
+            DayPhase::Evening => "Close + tomorrow seed",
+            DayPhase::Night => "Tomorrow focus",
+        }
+    }
+
+    /// Whether the phase looks ahead to tomorrow's tasks
+    pub fn seeds_tomorrow(&self) -> bool {
+        matches!(self, DayPhase::Evening | DayPhase::Night)
+    }
+}
+
+/// A briefing section: its items, why they could not be gathered, or both
+/// when only part of it could be
+#[derive(Debug, Serialize)]
+pub struct Section<T> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<T>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl<T> From<Result<Vec<T>>> for Section<T> {
+    fn from(result: Result<Vec<T>>) -> Self {
+        match result {
+            Ok(items) => Section {
+                items: Some(items),
+                error: None,
+            },
+            Err(e) => Section {
+                items: None,
+                error: Some(format!("{:#}", e)),
+            },
+        }
+    }
+}
+
+impl From<EventLines> for Section<String> {
+    fn from(events: EventLines) -> Self {
+        match events.error {
+            None => Section {
+                items: Some(events.lines),
+                error: None,
+            },
+            Some(e) => Section {
+                items: (!events.lines.is_empty()).then_some(events.lines),
+                error: Some(format!("{:#}", e)),
+            },
+        }
+    }
+}
+
+/// An attention item as found in the aiandi-mode state snapshot
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AttentionEntry {
+    pub id: String,
+    pub description: String,
+    pub status: String,
+    #[serde(default)]
+    pub surfaced_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ModeSnapshot {
+    #[serde(default)]
+    attention_stack: Vec<AttentionEntry>,
+}
+
+/// Hot and waiting attention items from a snapshot file
+///
+/// A missing snapshot means aiandi-mode has not run yet: no items, no error.
+pub async fn load_attention(path: &Path) -> Result<Vec<AttentionEntry>> {
+    let content = match tokio::fs::read_to_string(path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+        }
+    };
+
+    let snapshot: ModeSnapshot = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    let mut items: Vec<AttentionEntry> = snapshot
+        .attention_stack
+        .into_iter()
+        .filter(|i| i.status == "hot" || i.status == "waiting")
+        .collect();
+    // Hot first, then waiting; stable within each group
+    items.sort_by_key(|i| i.status != "hot");
+    Ok(items)
+}
+
+/// Calendar part of the briefing
+#[derive(Debug, Serialize)]
+pub struct EventsBriefing {
+    pub today: Section<String>,
+    pub tomorrow: Section<String>,
+}
+
+/// Task part of the briefing
+#[derive(Debug, Serialize)]
+pub struct TasksBriefing {
+    pub overdue: Section<Task>,
+    pub due_today: Section<Task>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_tomorrow: Option<Section<Task>>,
+}
+
+/// The complete daily briefing
+#[derive(Debug, Serialize)]
+pub struct DailyBriefing {
+    pub generated_at: String,
+    pub date: String,
+    pub phase: DayPhase,
+    pub focus: &'static str,
+    pub events: EventsBriefing,
+    pub email: Section<ThreadSummary>,
+    pub tasks: TasksBriefing,
+    pub attention: Section<AttentionEntry>,
+}
+
+#[derive(Clone)]
+pub struct BriefingTools {
+    calendar: CalendarTools,
+    email: EmailTools,
+    tasks: TaskTools,
+    /// aiandi-mode state snapshot (see `PimConfig::mode_state`)
+    mode_state: PathBuf,
+}
+
+impl BriefingTools {
+    pub fn new(
+        calendar: CalendarTools,
+        email: EmailTools,
+        tasks: TaskTools,
+        config: &PimConfig,
+    ) -> Self {
+        Self {
+            calendar,
+            email,
+            tasks,
+            mode_state: config.mode_state.clone(),
+        }
+    }
+
+    /// Gather the briefing for `today` in the given phase
+    pub async fn gather(&self, today: NaiveDate, phase: DayPhase) -> DailyBriefing {
+        let tomorrow = today + Duration::days(1);
+
+        let (events_today, events_tomorrow, email, overdue, due_today, due_tomorrow, attention) = tokio::join!(
+            self.calendar.event_lines(today, 1),
+            self.calendar.event_lines(tomorrow, 1),
+            self.email.thread_summaries(EMAIL_QUERY, EMAIL_LIMIT),
+            self.tasks.pending(&["+OVERDUE"]),
+            self.tasks.pending(&["+TODAY"]),
+            async {
+                if phase.seeds_tomorrow() {
+                    Some(self.tasks.pending(&["+TOMORROW"]).await)
+                } else {
+                    None
+                }
+            },
+            load_attention(&self.mode_state),
+        );
+
+        DailyBriefing {
+            generated_at: Local::now().to_rfc3339(),
+            date: today.format("%Y-%m-%d").to_string(),
+            phase,
+            focus: phase.focus(),
+            events: EventsBriefing {
+                today: events_today.into(),
+                tomorrow: events_tomorrow.into(),
+            },
+            email: email.into(),
+            tasks: TasksBriefing {
+                overdue: overdue.into(),
+                due_today: due_today.into(),
+                due_tomorrow: due_tomorrow.map(Into::into),
+            },
+            attention: attention.into(),
+        }
+    }
+
+    /// Daily briefing as pretty JSON
+    ///
+    /// `time_of_day` is one of morning/afternoon/evening/night; when omitted
+    /// the phase is detected from the current local time.
+    pub async fn daily_briefing(&self, time_of_day: Option<String>) -> String {
+        let phase = match time_of_day.as_deref() {
+            Some(s) => match DayPhase::parse(s) {
+                Some(phase) => phase,
+                None => {
+                    return format!(
+                        "Unknown time of day '{}'. Use: morning, afternoon, evening, or night",
+                        s
+                    );
+                }
+            },
+            None => DayPhase::now(),
+        };
+
+        let briefing = self.gather(Local::now().date_naive(), phase).await;
+        serde_json::to_string_pretty(&briefing).unwrap_or_else(|e| format!("Error: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_from_hour_boundaries() {
+        assert_eq!(DayPhase::from_hour(4), DayPhase::Night);
+        assert_eq!(DayPhase::from_hour(5), DayPhase::Morning);
+        assert_eq!(DayPhase::from_hour(11), DayPhase::Morning);
+        assert_eq!(DayPhase::from_hour(12), DayPhase::Afternoon);
+        assert_eq!(DayPhase::from_hour(16), DayPhase::Afternoon);
+        assert_eq!(DayPhase::from_hour(17), DayPhase::Evening);
+        assert_eq!(DayPhase::from_hour(20), DayPhase::Evening);
+        assert_eq!(DayPhase::from_hour(21), DayPhase::Night);
+        assert_eq!(DayPhase::from_hour(0), DayPhase::Night);
+    }
+
+    #[test]
+    fn test_phase_parse() {
+        assert_eq!(DayPhase::parse("Morning"), Some(DayPhase::Morning));
+        assert_eq!(DayPhase::parse(" night "), Some(DayPhase::Night));
+        assert_eq!(DayPhase::parse("noon"), None);
+        assert!(DayPhase::Evening.seeds_tomorrow());
+        assert!(!DayPhase::Morning.seeds_tomorrow());
+    }
+
+    #[test]
+    fn test_section_serialization() {
+        let ok: Section<String> = Ok(vec!["[robbie] 09:00-10:00 Standup".to_string()]).into();
+        let err: Section<String> = Err(anyhow::anyhow!("khal not found")).into();
+
+        assert_eq!(
+            serde_json::to_value(&ok).unwrap(),
+            serde_json::json!({"items": ["[robbie] 09:00-10:00 Standup"]})
+        );
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            serde_json::json!({"error": "khal not found"})
+        );
+
+        let partial: Section<String> = EventLines {
+            lines: vec!["[birthdays] Birthday: Jane Smith".to_string()],
+            error: Some(anyhow::anyhow!("khal not found")),
+        }
+        .into();
+        assert_eq!(
+            serde_json::to_value(&partial).unwrap(),
+            serde_json::json!({
+                "items": ["[birthdays] Birthday: Jane Smith"],
+                "error": "khal not found"
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_attention_filters_and_orders() {
+        let path =
+            std::env::temp_dir().join(format!("pim-briefing-test-{}.json", std::process::id()));
+        let snapshot = serde_json::json!({
+            "current_mode": "ops",
+            "attention_stack": [
+                {"id": "a", "description": "Waiting one", "status": "waiting",
+                 "surfaced_at": "2026-01-07T09:00:00Z", "updated_at": "2026-01-07T09:00:00Z", "notes": null},
+                {"id": "b", "description": "Done", "status": "handled",
+                 "surfaced_at": "2026-01-07T09:00:00Z", "updated_at": "2026-01-07T09:00:00Z", "notes": null},
+                {"id": "c", "description": "Hot one", "status": "hot",
+                 "surfaced_at": "2026-01-07T09:00:00Z", "updated_at": "2026-01-07T09:00:00Z", "notes": "call back"}
+            ]
+        });
+        tokio::fs::write(&path, snapshot.to_string()).await.unwrap();
+
+        let items = load_attention(&path).await.unwrap();
+        tokio::fs::remove_file(&path).await.ok();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].id, "c");
+        assert_eq!(items[1].id, "a");
+    }
+
+    #[tokio::test]
+    async fn test_load_attention_missing_snapshot() {
+        let items = load_attention(Path::new("/nonexistent/mode-state.json"))
+            .await
+            .unwrap();
+        assert!(items.is_empty());
+    }
+}
@@ -5,16 +5,159 @@
 //! - meli (read-only) - Wife's calendar (shared)
 //! - dennis (read-only) - Son's calendar (shared)
 //! - holidays (read-only) - Hungarian holidays
+//! - birthdays (virtual, read-only) - derived from contacts, see `birthdays`
 
-use crate::cli::run_command_stdout;
-use chrono::Local;
+use crate::birthdays::{BIRTHDAY_CALENDAR, BirthdayCalendar, Occurrence};
+use crate::cli::{Access, CommandBuilder, run_command_stdout};
+use crate::config::PimConfig;
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
 
-#[derive(Clone, Default)]
-pub struct CalendarTools;
+/// Date formats tried on khal's day headers, whatever `longdateformat` is
+const HEADER_DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%d.%m.%Y", "%d/%m/%Y", "%m/%d/%Y", "%Y/%m/%d"];
+
+/// One day of a khal listing: its header (if khal printed one) and events
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayListing {
+    pub header: Option<String>,
+    pub date: Option<NaiveDate>,
+    pub events: Vec<String>,
+}
+
+/// Events from `event_lines`, and khal's error if it failed
+#[derive(Debug)]
+pub struct EventLines {
+    pub lines: Vec<String>,
+    pub error: Option<anyhow::Error>,
+}
+
+/// Resolve a khal-style start date: `YYYY-MM-DD`, today, tomorrow,
+/// yesterday, or a weekday name (the next one, today included)
+pub fn resolve_date(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let input = input.trim().to_lowercase();
+    match input.as_str() {
+        "today" | "now" => return Some(today),
+        "tomorrow" => return Some(today + Duration::days(1)),
+        "yesterday" => return Some(today - Duration::days(1)),
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        return Some(date);
+    }
+    let weekday: Weekday = input.parse().ok()?;
+    let ahead = (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    Some(today + Duration::days(i64::from(ahead)))
+}
+
+/// The date in a khal day header such as `Today, 2026-01-07` or
+/// `Wednesday, 07.01.2026`, preferring a reading inside `[start, start + days)`
+fn header_date(header: &str, start: NaiveDate, days: u32) -> Option<NaiveDate> {
+    let end = start + Duration::days(i64::from(days));
+    let candidates: Vec<NaiveDate> = header
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .flat_map(|token| {
+            HEADER_DATE_FORMATS
+                .iter()
+                .filter_map(move |f| NaiveDate::parse_from_str(token, f).ok())
+        })
+        .collect();
+    candidates
+        .iter()
+        .find(|d| **d >= start && **d < end)
+        .or(candidates.first())
+        .copied()
+}
+
+/// Split khal `list` output into days; event lines start with `[calendar]`
+pub fn parse_listing(output: &str, start: NaiveDate, days: u32) -> Vec<DayListing> {
+    let mut listing: Vec<DayListing> = Vec::new();
+    for line in output.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.trim_start().starts_with('[') {
+            if listing.is_empty() {
+                listing.push(DayListing {
+                    header: None,
+                    date: None,
+                    events: Vec::new(),
+                });
+            }
+            if let Some(day) = listing.last_mut() {
+                day.events.push(line.to_string());
+            }
+        } else {
+            listing.push(DayListing {
+                header: Some(line.to_string()),
+                date: header_date(line, start, days),
+                events: Vec::new(),
+            });
+        }
+    }
+    listing
+}
+
+/// Put each birthday under its day, ahead of timed events like khal's
+/// all-day events; days khal had nothing for are inserted in date order
+pub fn merge_birthdays(listing: &mut Vec<DayListing>, occurrences: &[Occurrence]) {
+    let birthday_prefix = format!("[{}]", BIRTHDAY_CALENDAR);
+    for occurrence in occurrences {
+        let line = occurrence.to_event_line();
+        if let Some(day) = listing.iter_mut().find(|d| d.date == Some(occurrence.date)) {
+            let at = day
+                .events
+                .iter()
+                .rposition(|e| e.starts_with(&birthday_prefix))
+                .map_or(0, |i| i + 1);
+            day.events.insert(at, line);
+            continue;
+        }
+        let at = listing
+            .iter()
+            .position(|d| d.date.is_some_and(|date| date > occurrence.date))
+            .unwrap_or(listing.len());
+        listing.insert(
+            at,
+            DayListing {
+                header: Some(occurrence.date.format("%Y-%m-%d").to_string()),
+                date: Some(occurrence.date),
+                events: vec![line],
+            },
+        );
+    }
+}
+
+/// Render days back in khal's layout
+fn render_listing(listing: &[DayListing]) -> String {
+    let mut lines = Vec::new();
+    for day in listing {
+        lines.extend(day.header.iter().cloned());
+        lines.extend(day.events.iter().cloned());
+    }
+    lines.join("\n")
+}
+
+#[derive(Clone)]
+pub struct CalendarTools {
+    khal: String,
+    birthdays: BirthdayCalendar,
+}
+
+impl Default for CalendarTools {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl CalendarTools {
     pub fn new() -> Self {
-        Self
+        Self::from_config(&PimConfig::from_env())
+    }
+
+    pub fn from_config(config: &PimConfig) -> Self {
+        Self {
+            khal: config.khal.clone(),
+            birthdays: BirthdayCalendar::new(&config.contacts_dir),
+        }
     }
 
     /// Format a date range string for display
@@ -24,27 +167,107 @@ impl CalendarTools {
     }
 
     /// List calendar events for a date range
+    ///
+    /// Birthdays and anniversaries from contacts are merged into khal's day
+    /// listing, and still listed when khal itself fails.
     pub async fn list_events(&self, start_date: Option<String>, days: Option<u32>) -> String {
-        let start = start_date.unwrap_or_else(|| Local::now().format("%Y-%m-%d").to_string());
+        let today = Local::now().date_naive();
+        let start = start_date.unwrap_or_else(|| today.format("%Y-%m-%d").to_string());
         let days = days.unwrap_or(7);
         let range = format!("{}d", days);
 
         // Format: [calendar] time-time title
         // This makes calendar source visible in output
         let format = "[{calendar}] {start-end-time-style} {title}";
-        
-        match run_command_stdout("khal", &["list", "-f", format, &start, &range]).await {
-            Ok(output) => {
-                if output.trim().is_empty() {
-                    format!("No events found from {} for {} days.", start, days)
-                } else {
-                    format!("Events from {} ({} days):\n\n{}", start, days, output)
+
+        // Birthdays come from contacts, not khal
+        let start_day = resolve_date(&start, today);
+        let birthdays = match start_day {
+            Some(start_day) => self.birthdays.occurrences(start_day, days).await,
+            None => Vec::new(),
+        };
+        let skipped = match start_day {
+            Some(_) => String::new(),
+            None => format!(
+                "\n(Birthdays skipped: could not resolve start date '{}'.)\n",
+                start
+            ),
+        };
+
+        let output = match run_command_stdout(&self.khal, &["list", "-f", format, &start, &range])
+            .await
+        {
+            Ok(output) => output,
+            Err(e) => {
+                let mut message = format!("Error listing events: {}", e);
+                if !birthdays.is_empty() {
+                    let lines: Vec<String> = birthdays.iter().map(Occurrence::to_line).collect();
+                    message.push_str(&format!(
+                        "\n\nBirthdays and anniversaries from {} ({} days):\n\n{}\n",
+                        start,
+                        days,
+                        lines.join("\n")
+                    ));
                 }
+                return message + &skipped;
             }
-            Err(e) => format!("Error listing events: {}", e),
+        };
+
+        let anchor = start_day.unwrap_or(today);
+        let mut listing = parse_listing(&output, anchor, days);
+        merge_birthdays(&mut listing, &birthdays);
+
+        if listing.is_empty() {
+            format!(
+                "No events found from {} for {} days.{}",
+                start, days, skipped
+            )
+        } else {
+            format!(
+                "Events from {} ({} days):\n\n{}\n{}",
+                start,
+                days,
+                render_listing(&listing),
+                skipped
+            )
+        }
+    }
+
+    /// Event lines for a date range, one per event, without khal's day headers
+    ///
+    /// Birthdays from contacts are merged in by day. If khal fails, the
+    /// birthdays are still returned alongside the error.
+    pub async fn event_lines(&self, start: NaiveDate, days: u32) -> EventLines {
+        let start_str = start.format("%Y-%m-%d").to_string();
+        let range = format!("{}d", days);
+        let format = "[{calendar}] {start-end-time-style} {title}";
+        let birthdays = self.birthdays.occurrences(start, days).await;
+
+        // Day headers are kept here so birthdays can be placed by date
+        let mut listing =
+            match run_command_stdout(&self.khal, &["list", "-f", format, &start_str, &range]).await
+            {
+                Ok(output) => parse_listing(&output, start, days),
+                Err(e) => {
+                    return EventLines {
+                        lines: birthdays.iter().map(Occurrence::to_event_line).collect(),
+                        error: Some(e.into()),
+                    };
+                }
+            };
+        merge_birthdays(&mut listing, &birthdays);
+
+        EventLines {
+            lines: listing.into_iter().flat_map(|day| day.events).collect(),
+            error: None,
         }
     }
 
+    /// List upcoming birthdays and anniversaries from contacts
+    pub async fn upcoming_birthdays(&self, days: Option<u32>) -> String {
+        self.birthdays.upcoming(days).await
+    }
+
     /// Create a new calendar event
     pub async fn create_event(
         &self,
@@ -95,7 +318,13 @@ impl CalendarTools {
             args.push(&location_str);
         }
 
-        match run_command_stdout("khal", &args).await {
+        // khal writes into the vdir: one writer at a time
+        let created = CommandBuilder::new(&self.khal)
+            .args(&args)
+            .access(Access::Write)
+            .run_stdout()
+            .await;
+        match created {
             Ok(output) => {
                 let result = if output.trim().is_empty() {
                     "Event created successfully.".to_string()
@@ -119,6 +348,81 @@ mod tests {
         assert_eq!(result, "2026-01-07 (7 days)");
     }
 
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn birthday(date: NaiveDate, name: &str) -> Occurrence {
+        Occurrence {
+            date,
+            name: name.to_string(),
+            kind: crate::birthdays::OccasionKind::Birthday,
+            years: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_date() {
+        // 2026-01-07 is a Wednesday
+        let today = date(2026, 1, 7);
+        assert_eq!(resolve_date("2026-02-01", today), Some(date(2026, 2, 1)));
+        assert_eq!(resolve_date("Today", today), Some(today));
+        assert_eq!(resolve_date("tomorrow", today), Some(date(2026, 1, 8)));
+        assert_eq!(resolve_date("wednesday", today), Some(today));
+        assert_eq!(resolve_date("fri", today), Some(date(2026, 1, 9)));
+        assert_eq!(resolve_date("next week", today), None);
+    }
+
+    #[test]
+    fn test_header_date_formats() {
+        let start = date(2026, 1, 1);
+        assert_eq!(
+            header_date("Today, 2026-01-07", start, 30),
+            Some(date(2026, 1, 7))
+        );
+        assert_eq!(
+            header_date("Wednesday, 07.01.2026", start, 30),
+            Some(date(2026, 1, 7))
+        );
+        // 02/01/2026 reads as Feb 1 or Jan 2; the range decides
+        assert_eq!(header_date("02/01/2026", start, 7), Some(date(2026, 1, 2)));
+        assert_eq!(header_date("Tomorrow", start, 7), None);
+    }
+
+    #[test]
+    fn test_merge_birthdays_by_day() {
+        let output = "Today, 2026-01-07\n\
+                      [holidays] Vizkereszt\n\
+                      [robbie] 09:00-09:30 Standup\n\
+                      Friday, 2026-01-09\n\
+                      [robbie] 10:00-11:00 Review\n";
+        let mut listing = parse_listing(output, date(2026, 1, 7), 7);
+        merge_birthdays(
+            &mut listing,
+            &[
+                birthday(date(2026, 1, 7), "Ann"),
+                birthday(date(2026, 1, 7), "Bob"),
+                birthday(date(2026, 1, 8), "Cy"),
+                birthday(date(2026, 1, 12), "Di"),
+            ],
+        );
+
+        assert_eq!(
+            render_listing(&listing),
+            "Today, 2026-01-07\n\
+             [birthdays] Birthday: Ann\n\
+             [birthdays] Birthday: Bob\n\
+             [holidays] Vizkereszt\n\
+             [robbie] 09:00-09:30 Standup\n\
+             2026-01-08\n\
+             [birthdays] Birthday: Cy\n\
+             Friday, 2026-01-09\n\
+             [robbie] 10:00-11:00 Review\n\
+             2026-01-12\n\
+             [birthdays] Birthday: Di"
+        );
+    }
+
     #[test]
     fn test_default_days() {
         // Verify default is 7 days
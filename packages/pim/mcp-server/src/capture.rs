@@ -0,0 +1,334 @@
+//! Capture tools - turn emails and calendar events into TaskWarrior inbox tasks
+//!
+//! Bridges `EmailTools`/`CalendarTools` to the same `task add +inbox` path
+//! that `aiandi inbox` uses, so "reply to this by Friday" lives in the task
+//! list instead of only in the mailbox.
+
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveDate};
+
+use crate::calendar::CalendarTools;
+use crate::email::EmailTools;
+use crate::tasks::{AddedTask, NewTask, TaskTools};
+
+/// Default number of days between a prep task and its event
+pub const DEFAULT_PREP_DAYS: u32 = 1;
+
+/// Split comma-separated tags into a list
+fn split_tags(tags: Option<String>) -> Vec<String> {
+    tags.map(|t| {
+        t.split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Build the inbox task for an email thread
+pub fn email_task(
+    subject: &str,
+    message_id: &str,
+    due: Option<String>,
+    project: Option<String>,
+    tags: Vec<String>,
+) -> NewTask {
+    let subject = subject.trim();
+    let description = if subject.is_empty() {
+        "(no subject)".to_string()
+    } else {
+        subject.to_string()
+    };
+
+    let mut all_tags = vec!["email".to_string()];
+    all_tags.extend(tags);
+
+    NewTask {
+        description,
+        tags: all_tags,
+        project,
+        due,
+        annotations: vec![format!("Message-ID: <{}>", message_id)],
+    }
+}
+
+/// An event as listed by `CalendarTools::event_lines`
+#[derive(Debug, PartialEq, Eq)]
+pub struct ListedEvent<'a> {
+    pub title: &'a str,
+    pub start_time: Option<&'a str>,
+}
+
+/// Parse a `[calendar] HH:MM-HH:MM Title` line; all-day events have no time
+pub fn parse_event_line(line: &str) -> ListedEvent<'_> {
+    let rest = match line.trim().strip_prefix('[') {
+        Some(rest) => rest.split_once("] ").map_or(rest, |(_, r)| r),
+        None => line.trim(),
+    };
+    let span = rest
+        .split_whitespace()
+        .next()
+        .and_then(|s| s.split_once('-'));
+    match span {
+        Some((start, _)) if start.len() == 5 && start.as_bytes()[2] == b':' => ListedEvent {
+            title: rest.split_once(' ').map_or("", |(_, t)| t.trim()),
+            start_time: Some(start),
+        },
+        _ => ListedEvent {
+            title: rest.trim(),
+            start_time: None,
+        },
+    }
+}
+
+/// Build the prep task for a calendar event, due `days_before` the event
+pub fn event_prep_task(
+    title: &str,
+    event_date: NaiveDate,
+    start_time: Option<&str>,
+    days_before: u32,
+    project: Option<String>,
+    tags: Vec<String>,
+) -> NewTask {
+    let due = event_date - Duration::days(i64::from(days_before));
+
+    let mut all_tags = vec!["calendar".to_string()];
+    all_tags.extend(tags);
+
+    let when = match start_time {
+        Some(time) => format!("{} {}", event_date.format("%Y-%m-%d"), time),
+        None => event_date.format("%Y-%m-%d").to_string(),
+    };
+
+    NewTask {
+        description: format!("Prep: {}", title.trim()),
+        tags: all_tags,
+        project,
+        due: Some(due.format("%Y-%m-%d").to_string()),
+        annotations: vec![format!("Event: {} on {}", title.trim(), when)],
+    }
+}
+
+#[derive(Clone)]
+pub struct CaptureTools {
+    calendar: CalendarTools,
+    email: EmailTools,
+    tasks: TaskTools,
+}
+
+impl CaptureTools {
+    pub fn new(calendar: CalendarTools, email: EmailTools, tasks: TaskTools) -> Self {
+        Self {
+            calendar,
+            email,
+            tasks,
+        }
+    }
+
+    /// Look the event up in khal; returns its start time as listed
+    async fn find_event(&self, title: &str, date: NaiveDate) -> Result<Option<String>> {
+        let listed = self.calendar.event_lines(date, 1).await;
+        if let Some(e) = listed.error {
+            return Err(e.context("Could not check the calendar"));
+        }
+        listed
+            .lines
+            .iter()
+            .map(|line| parse_event_line(line))
+            .find(|event| event.title.eq_ignore_ascii_case(title.trim()))
+            .map(|event| event.start_time.map(String::from))
+            .with_context(|| {
+                format!(
+                    "No event '{}' on {} in the calendar",
+                    title.trim(),
+                    date.format("%Y-%m-%d")
+                )
+            })
+    }
+
+    async fn capture_event_task(
+        &self,
+        title: &str,
+        event_date: NaiveDate,
+        start_time: Option<String>,
+        days_before: u32,
+        project: Option<String>,
+        tags: Vec<String>,
+    ) -> Result<(AddedTask, NewTask)> {
+        let listed_time = self.find_event(title, event_date).await?;
+        let task = event_prep_task(
+            title,
+            event_date,
+            start_time.or(listed_time).as_deref(),
+            days_before,
+            project,
+            tags,
+        );
+        let added = self.tasks.add(&task).await?;
+        Ok((added, task))
+    }
+
+    async fn capture_email_task(
+        &self,
+        thread_id: &str,
+        due: Option<String>,
+        project: Option<String>,
+        tags: Vec<String>,
+    ) -> Result<(AddedTask, NewTask)> {
+        let query = EmailTools::normalize_thread_id(thread_id);
+
+        let thread = self
+            .email
+            .thread_summaries(&query, 1)
+            .await?
+            .into_iter()
+            .next()
+            .with_context(|| format!("No email found with ID: {}", query))?;
+
+        let message_id = self
+            .email
+            .message_ids(thread_id)
+            .await?
+            .into_iter()
+            .next()
+            .with_context(|| format!("No messages found in {}", query))?;
+
+        let task = email_task(&thread.subject, &message_id, due, project, tags);
+        let added = self.tasks.add(&task).await?;
+        Ok((added, task))
+    }
+
+    /// Create an inbox task from an email thread
+    pub async fn capture_email(
+        &self,
+        thread_id: String,
+        due: Option<String>,
+        project: Option<String>,
+        tags: Option<String>,
+    ) -> String {
+        match self
+            .capture_email_task(&thread_id, due, project, split_tags(tags))
+            .await
+        {
+            Ok((added, task)) => format!(
+                "Captured to inbox: {}\nUUID: {}\nDescription: {}\nDue: {}\n{}",
+                added,
+                added.uuid,
+                task.description,
+                task.due.as_deref().unwrap_or("(none)"),
+                task.annotations.join("\n")
+            ),
+            Err(e) => format!("Error capturing email: {:#}", e),
+        }
+    }
+
+    /// Create an inbox task to prepare for a calendar event
+    ///
+    /// The event must be listed by khal on `date` under `title`; a missing
+    /// start time is taken from the listing.
+    pub async fn capture_event_prep(
+        &self,
+        title: String,
+        date: String,
+        start_time: Option<String>,
+        days_before: Option<u32>,
+        project: Option<String>,
+        tags: Option<String>,
+    ) -> String {
+        let event_date = match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+            Ok(d) => d,
+            Err(_) => return format!("Invalid date '{}'. Use YYYY-MM-DD.", date),
+        };
+
+        match self
+            .capture_event_task(
+                &title,
+                event_date,
+                start_time,
+                days_before.unwrap_or(DEFAULT_PREP_DAYS),
+                project,
+                split_tags(tags),
+            )
+            .await
+        {
+            Ok((added, task)) => format!(
+                "Captured to inbox: {}\nUUID: {}\nDescription: {}\nDue: {}\n{}",
+                added,
+                added.uuid,
+                task.description,
+                task.due.as_deref().unwrap_or("(none)"),
+                task.annotations.join("\n")
+            ),
+            Err(e) => format!("Error capturing event: {:#}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email_task() {
+        let task = email_task(
+            "Invoice #42",
+            "abc123@mail.example.com",
+            Some("friday".to_string()),
+            None,
+            vec!["finance".to_string()],
+        );
+
+        assert_eq!(task.description, "Invoice #42");
+        assert_eq!(task.tags, vec!["email", "finance"]);
+        assert_eq!(task.due.as_deref(), Some("friday"));
+        assert_eq!(
+            task.annotations,
+            vec!["Message-ID: <abc123@mail.example.com>"]
+        );
+    }
+
+    #[test]
+    fn test_email_task_without_subject() {
+        let task = email_task("  ", "id@x", None, None, Vec::new());
+        assert_eq!(task.description, "(no subject)");
+    }
+
+    #[test]
+    fn test_event_prep_task() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 12).unwrap();
+        let task = event_prep_task("Dentist", date, Some("09:30"), 3, None, Vec::new());
+
+        assert_eq!(task.description, "Prep: Dentist");
+        assert_eq!(task.due.as_deref(), Some("2026-01-09"));
+        assert_eq!(task.tags, vec!["calendar"]);
+        assert_eq!(task.annotations, vec!["Event: Dentist on 2026-01-12 09:30"]);
+    }
+
+    #[test]
+    fn test_parse_event_line() {
+        assert_eq!(
+            parse_event_line("[robbie] 09:00-09:30 Standup"),
+            ListedEvent {
+                title: "Standup",
+                start_time: Some("09:00"),
+            }
+        );
+        assert_eq!(
+            parse_event_line("[holidays] Vizkereszt"),
+            ListedEvent {
+                title: "Vizkereszt",
+                start_time: None,
+            }
+        );
+        assert_eq!(
+            parse_event_line("[work] Ad-hoc review").title,
+            "Ad-hoc review"
+        );
+    }
+
+    #[test]
+    fn test_split_tags() {
+        assert_eq!(split_tags(Some("a, b,,c".to_string())), vec!["a", "b", "c"]);
+        assert!(split_tags(None).is_empty());
+    }
+}
@@ -6,4 +6,10 @@
 //! which provides consistent CLI wrapping across all aiandi Rust packages.
 
 // Re-export utilities used by pim package
-pub use aiandi_cli::{run_command_stdout, run_command_with_stdin};
+pub use aiandi_cli::{Access, CommandBuilder, CommandError, run_command_stdout};
+
+/// Output cap for commands whose stdout is parsed as JSON
+///
+/// Past it the run fails with `CommandError::Truncated` instead of handing
+/// serde a cut-off document.
+pub const JSON_MAX_OUTPUT: usize = 64 * 1024 * 1024;
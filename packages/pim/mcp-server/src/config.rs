@@ -0,0 +1,158 @@
+//! Tool configuration - where pim finds its CLI tools and data
+//!
+//! Everything is resolved from environment variables so the server can be
+//! pointed at stand-in tools and fixture data (see `tests/fixtures/`):
+//!
+//! - `AIANDI_PIM_BIN_DIR` - directory searched first for every tool
+//! - `AIANDI_PIM_KHAL`, `AIANDI_PIM_KHARD`, `AIANDI_PIM_NOTMUCH`,
+//!   `AIANDI_PIM_HIMALAYA`, `AIANDI_PIM_TASK` - per-tool path (wins over the
+//!   bin dir)
+//! - `AIANDI_PIM_CONTACTS_DIR` - contacts vdir
+//! - `AIANDI_MODE_STATE` - aiandi-mode state snapshot
+//! - `AIANDI_PIM_NO_MODE_GUARD` - set to `1` to allow every tool when there
+//!   is no aiandi-mode snapshot (see `mode_guard`)
+
+use std::path::PathBuf;
+
+/// Directory searched first for every tool
+pub const BIN_DIR_ENV: &str = "AIANDI_PIM_BIN_DIR";
+
+/// Contacts vdir override
+pub const CONTACTS_DIR_ENV: &str = "AIANDI_PIM_CONTACTS_DIR";
+
+/// aiandi-mode snapshot override (shared with aiandi-mode)
+pub const MODE_STATE_ENV: &str = "AIANDI_MODE_STATE";
+
+/// Opt out of the default mode policy when aiandi-mode has not run
+pub const NO_MODE_GUARD_ENV: &str = "AIANDI_PIM_NO_MODE_GUARD";
+
+/// Resolved paths for every CLI tool and data directory pim touches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PimConfig {
+    pub khal: String,
+    pub khard: String,
+    pub notmuch: String,
+    pub himalaya: String,
+    pub task: String,
+    pub contacts_dir: PathBuf,
+    pub mode_state: PathBuf,
+    /// Allow every tool when there is no mode snapshot
+    pub no_mode_guard: bool,
+}
+
+impl Default for PimConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+impl PimConfig {
+    /// Resolve from the process environment
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Resolve using `lookup` for environment variables
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let home = PathBuf::from(lookup("HOME").unwrap_or_default());
+        let bin_dir = lookup(BIN_DIR_ENV).map(PathBuf::from);
+
+        let tool = |name: &str, default: String| -> String {
+            let env_key = format!("AIANDI_PIM_{}", name.to_uppercase());
+            if let Some(path) = lookup(&env_key) {
+                return path;
+            }
+            match bin_dir {
+                Some(ref dir) => dir.join(name).to_string_lossy().into_owned(),
+                None => default,
+            }
+        };
+
+        let state_home = lookup("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".local/state"));
+
+        Self {
+            khal: tool("khal", "khal".to_string()),
+            khard: tool("khard", "khard".to_string()),
+            notmuch: tool("notmuch", "notmuch".to_string()),
+            // himalaya is installed via pipx, not in system PATH
+            himalaya: tool(
+                "himalaya",
+                home.join(".local/bin/himalaya")
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            task: tool("task", "task".to_string()),
+            contacts_dir: lookup(CONTACTS_DIR_ENV)
+                .map(PathBuf::from)
+                .unwrap_or_else(|| home.join(".local/share/vdirsyncer/contacts/default")),
+            mode_state: lookup(MODE_STATE_ENV)
+                .map(PathBuf::from)
+                .unwrap_or_else(|| state_home.join("aiandi").join("mode-state.json")),
+            no_mode_guard: lookup(NO_MODE_GUARD_ENV)
+                .is_some_and(|v| matches!(v.trim(), "1" | "true" | "yes")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(vars: &[(&str, &str)]) -> PimConfig {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        PimConfig::from_lookup(|key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = config(&[("HOME", "/home/robbie")]);
+
+        assert_eq!(config.khal, "khal");
+        assert_eq!(config.notmuch, "notmuch");
+        assert_eq!(config.himalaya, "/home/robbie/.local/bin/himalaya");
+        assert_eq!(
+            config.contacts_dir,
+            PathBuf::from("/home/robbie/.local/share/vdirsyncer/contacts/default")
+        );
+        assert_eq!(
+            config.mode_state,
+            PathBuf::from("/home/robbie/.local/state/aiandi/mode-state.json")
+        );
+        assert!(!config.no_mode_guard);
+    }
+
+    #[test]
+    fn test_no_mode_guard_opt_out() {
+        assert!(config(&[(NO_MODE_GUARD_ENV, "1")]).no_mode_guard);
+        assert!(!config(&[(NO_MODE_GUARD_ENV, "0")]).no_mode_guard);
+    }
+
+    #[test]
+    fn test_bin_dir_applies_to_all_tools() {
+        let config = config(&[("HOME", "/home/robbie"), (BIN_DIR_ENV, "/opt/fake")]);
+
+        assert_eq!(config.khal, "/opt/fake/khal");
+        assert_eq!(config.khard, "/opt/fake/khard");
+        assert_eq!(config.himalaya, "/opt/fake/himalaya");
+        assert_eq!(config.task, "/opt/fake/task");
+    }
+
+    #[test]
+    fn test_per_tool_path_wins_over_bin_dir() {
+        let config = config(&[
+            (BIN_DIR_ENV, "/opt/fake"),
+            ("AIANDI_PIM_NOTMUCH", "/usr/local/bin/notmuch"),
+            (CONTACTS_DIR_ENV, "/tmp/contacts"),
+        ]);
+
+        assert_eq!(config.notmuch, "/usr/local/bin/notmuch");
+        assert_eq!(config.khal, "/opt/fake/khal");
+        assert_eq!(config.contacts_dir, PathBuf::from("/tmp/contacts"));
+    }
+}
@@ -1,41 +1,65 @@
 //! Contact tools - wraps khard CLI
 
-use crate::cli::run_command_stdout;
+use crate::cli::{CommandError, run_command_stdout};
+use crate::config::PimConfig;
+use std::path::PathBuf;
+
+/// Whether a khard exit 1 means "nothing matched" rather than a failure
+fn is_no_match(stdout: &str, stderr: &str) -> bool {
+    const NO_MATCH: &str = "Found no contacts";
+    stdout.contains(NO_MATCH) || stderr.contains(NO_MATCH)
+}
 
-#[derive(Clone, Default)]
-pub struct ContactTools;
+#[derive(Clone)]
+pub struct ContactTools {
+    khard: String,
+    /// The contacts vdir synced by vdirsyncer
+    contacts_dir: PathBuf,
+}
+
+impl Default for ContactTools {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl ContactTools {
     pub fn new() -> Self {
-        Self
+        Self::from_config(&PimConfig::from_env())
+    }
+
+    pub fn from_config(config: &PimConfig) -> Self {
+        Self {
+            khard: config.khard.clone(),
+            contacts_dir: config.contacts_dir.clone(),
+        }
     }
 
     /// Search contacts by name, email, or phone
     pub async fn search(&self, query: String) -> String {
         // khard list supports search terms
-        match run_command_stdout("khard", &["list", &query]).await {
-            Ok(output) => {
-                if output.trim().is_empty() || output.contains("No contacts found") {
-                    format!("No contacts found matching: {}", query)
-                } else {
-                    format!("Contacts matching '{}':\n\n{}", query, output)
-                }
+        match run_command_stdout(&self.khard, &["list", &query]).await {
+            Ok(output) if output.trim().is_empty() => {
+                format!("No contacts found matching: {}", query)
             }
-            Err(e) => {
-                // khard returns error if no matches, check if it's just empty results
-                let err_str = e.to_string();
-                if err_str.contains("No contacts found") {
-                    format!("No contacts found matching: {}", query)
-                } else {
-                    format!("Error searching contacts: {}", e)
-                }
+            Ok(output) => format!("Contacts matching '{}':\n\n{}", query, output),
+            // khard exits 1 when nothing matches, but also on config and
+            // address book errors
+            Err(CommandError::NonZeroExit {
+                code: 1,
+                ref stdout,
+                ref stderr,
+                ..
+            }) if is_no_match(stdout, stderr) => {
+                format!("No contacts found matching: {}", query)
             }
+            Err(e) => format!("Error searching contacts: {}", e),
         }
     }
 
     /// Get full details of a specific contact
     pub async fn get(&self, name: String) -> String {
-        match run_command_stdout("khard", &["show", &name]).await {
+        match run_command_stdout(&self.khard, &["show", &name]).await {
             Ok(output) => {
                 if output.trim().is_empty() {
                     format!("No contact found with name: {}", name)
@@ -79,10 +103,7 @@ impl ContactTools {
         vcard.push_str("END:VCARD\n");
 
         // Write to contacts directory
-        let contact_dir = format!(
-            "{}/.local/share/vdirsyncer/contacts/default",
-            std::env::var("HOME").unwrap_or_default()
-        );
+        let contact_dir = &self.contacts_dir;
 
         // Generate a unique filename
         let uuid = format!(
@@ -92,7 +113,7 @@ impl ContactTools {
                 .unwrap_or_default()
                 .as_nanos()
         );
-        let filename = format!("{}/{}.vcf", contact_dir, uuid);
+        let filename = contact_dir.join(format!("{}.vcf", uuid));
 
         match tokio::fs::write(&filename, &vcard).await {
             Ok(_) => {
@@ -107,7 +128,7 @@ impl ContactTools {
                     email.unwrap_or_else(|| "(none)".into()),
                     phone.unwrap_or_else(|| "(none)".into()),
                     organization.unwrap_or_else(|| "(none)".into()),
-                    filename
+                    filename.display()
                 )
             }
             Err(e) => format!("Error creating contact: {}", e),
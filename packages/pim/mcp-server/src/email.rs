@@ -1,9 +1,27 @@
 //! Email tools - wraps notmuch (search/read) and himalaya (send)
 
-use crate::cli::{run_command_stdout, run_command_with_stdin};
+use crate::cli::{Access, CommandBuilder, JSON_MAX_OUTPUT, run_command_stdout};
+use crate::config::PimConfig;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// A thread as returned by `notmuch search --format=json`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ThreadSummary {
+    pub thread: String,
+    #[serde(default)]
+    pub date_relative: String,
+    #[serde(default)]
+    pub authors: String,
+    #[serde(default)]
+    pub subject: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
 
 #[derive(Clone)]
 pub struct EmailTools {
+    notmuch: String,
     himalaya_path: String,
 }
 
@@ -15,12 +33,13 @@ impl Default for EmailTools {
 
 impl EmailTools {
     pub fn new() -> Self {
+        Self::from_config(&PimConfig::from_env())
+    }
+
+    pub fn from_config(config: &PimConfig) -> Self {
         Self {
-            // himalaya is installed via pipx, not in system PATH
-            himalaya_path: format!(
-                "{}/.local/bin/himalaya",
-                std::env::var("HOME").unwrap_or_default()
-            ),
+            notmuch: config.notmuch.clone(),
+            himalaya_path: config.himalaya.clone(),
         }
     }
 
@@ -41,7 +60,7 @@ impl EmailTools {
         // Use notmuch search with structured output
         let args = vec!["search", "--limit", &limit_str, "--format=text", &query];
 
-        match run_command_stdout("notmuch", &args).await {
+        match run_command_stdout(&self.notmuch, &args).await {
             Ok(output) => {
                 if output.trim().is_empty() {
                     format!("No emails found matching: {}", query)
@@ -58,11 +77,51 @@ impl EmailTools {
         }
     }
 
+    /// Parse `notmuch search --format=json` output
+    pub fn parse_thread_summaries(output: &str) -> Result<Vec<ThreadSummary>> {
+        if output.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        serde_json::from_str(output).context("Failed to parse notmuch search output")
+    }
+
+    /// Search threads and return structured summaries
+    pub async fn thread_summaries(&self, query: &str, limit: u32) -> Result<Vec<ThreadSummary>> {
+        let limit_str = limit.to_string();
+        let output = CommandBuilder::new(&self.notmuch)
+            .args(&["search", "--limit", &limit_str, "--format=json", query])
+            .max_output(JSON_MAX_OUTPUT)
+            .run_stdout()
+            .await?;
+        Self::parse_thread_summaries(&output)
+    }
+
+    /// Message IDs in a thread, newest first
+    pub async fn message_ids(&self, thread_id: &str) -> Result<Vec<String>> {
+        let query = Self::normalize_thread_id(thread_id);
+        let output = CommandBuilder::new(&self.notmuch)
+            .args(&[
+                "search",
+                "--output=messages",
+                "--format=json",
+                "--sort=newest-first",
+                &query,
+            ])
+            .max_output(JSON_MAX_OUTPUT)
+            .run_stdout()
+            .await?;
+
+        if output.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        serde_json::from_str(&output).context("Failed to parse notmuch message list")
+    }
+
     /// Read the full content of an email thread
     pub async fn read_thread(&self, thread_id: String) -> String {
         let query = Self::normalize_thread_id(&thread_id);
 
-        match run_command_stdout("notmuch", &["show", "--format=text", &query]).await {
+        match run_command_stdout(&self.notmuch, &["show", "--format=text", &query]).await {
             Ok(output) => {
                 if output.trim().is_empty() {
                     format!("No email found with ID: {}", query)
@@ -91,7 +150,14 @@ impl EmailTools {
         // himalaya message write --to X --subject Y < body
         let args = vec!["message", "write", "--to", &to, "--subject", &subject];
 
-        match run_command_with_stdin(&self.himalaya_path, &args, &body).await {
+        // Sending also saves a copy to the Sent folder
+        let sent = CommandBuilder::new(&self.himalaya_path)
+            .args(&args)
+            .stdin(body.as_str())
+            .access(Access::Write)
+            .run_stdout()
+            .await;
+        match sent {
             Ok(output) => {
                 format!(
                     "Email sent successfully!\n\
@@ -122,6 +188,20 @@ mod tests {
         assert_eq!(result, "thread:00000000000012ab");
     }
 
+    #[test]
+    fn test_parse_thread_summaries() {
+        let json = r#"[{"thread": "00000000000012ab", "timestamp": 1767772800,
+            "date_relative": "Yest. 09:12", "matched": 1, "total": 2,
+            "authors": "John Doe| Jane", "subject": "Lunch?",
+            "query": ["id:x", null], "tags": ["inbox", "unread"]}]"#;
+        let threads = EmailTools::parse_thread_summaries(json).unwrap();
+
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].thread, "00000000000012ab");
+        assert_eq!(threads[0].subject, "Lunch?");
+        assert!(threads[0].tags.contains(&"unread".to_string()));
+    }
+
     #[tokio::test]
     async fn test_send_without_confirmation() {
         let tools = EmailTools::new();
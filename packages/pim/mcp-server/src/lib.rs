@@ -1,6 +1,12 @@
 //! pim library - shared code for tests
 
+pub mod birthdays;
+pub mod briefing;
 pub mod calendar;
+pub mod capture;
 pub mod cli;
+pub mod config;
 pub mod contacts;
 pub mod email;
+pub mod mode_guard;
+pub mod tasks;
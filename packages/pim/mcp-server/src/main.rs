@@ -2,26 +2,44 @@
 //!
 //! Provides tools for email, calendar, and contacts access via CLI wrappers:
 //! - Email: notmuch (search/read), himalaya (send)
-//! - Calendar: khal (list/create/update/delete)
+//! - Calendar: khal (list/create/update/delete), birthdays from contacts
 //! - Contacts: khard (search/get/create/update/delete)
+//! - Briefing: calendar + email + TaskWarrior + aiandi-mode attention stack
+//! - Capture: emails and events to TaskWarrior inbox tasks
+//!
+//! Tool locations are configurable through the environment; see `config`.
+//! Tools blocked in the current aiandi-mode mode are refused; see `mode_guard`.
 
 use anyhow::Result;
 use rmcp::{
-    ServerHandler, ServiceExt,
-    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+    ErrorData, RoleServer, ServerHandler, ServiceExt,
+    handler::server::{router::tool::ToolRouter, tool::ToolCallContext, wrapper::Parameters},
     model::*,
-    schemars, tool, tool_handler, tool_router,
+    schemars,
+    service::RequestContext,
+    tool, tool_router,
 };
 use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 
+mod birthdays;
+mod briefing;
 mod calendar;
+mod capture;
 mod cli;
+mod config;
 mod contacts;
 mod email;
+mod mode_guard;
+mod tasks;
 
+use briefing::BriefingTools;
 use calendar::CalendarTools;
+use capture::CaptureTools;
+use config::PimConfig;
 use contacts::ContactTools;
 use email::EmailTools;
+use mode_guard::{ModeGuard, Verdict};
+use tasks::TaskTools;
 
 // ============================================================================
 // Request types for each tool (with JSON Schema generation)
@@ -35,6 +53,12 @@ pub struct ListEventsRequest {
     days: Option<u32>,
 }
 
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct UpcomingBirthdaysRequest {
+    /// Number of days to look ahead (default: 30)
+    days: Option<u32>,
+}
+
 #[derive(serde::Deserialize, schemars::JsonSchema)]
 pub struct CreateEventRequest {
     /// Event title
@@ -101,26 +125,89 @@ pub struct CreateContactRequest {
     organization: Option<String>,
 }
 
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct DailyBriefingRequest {
+    /// PLAN phase: "morning", "afternoon", "evening", or "night" (default: detected from current time)
+    time_of_day: Option<String>,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct CaptureEmailRequest {
+    /// Thread ID from search results (e.g., 'thread:00000000000012ab')
+    thread_id: String,
+    /// Due date in TaskWarrior syntax (e.g., '2026-01-09', 'friday', 'eow')
+    due: Option<String>,
+    /// Project to assign
+    project: Option<String>,
+    /// Additional tags (comma-separated). +inbox and +email are always added.
+    tags: Option<String>,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct CaptureEventRequest {
+    /// Event title, as listed by khal on that date
+    title: String,
+    /// Event date in YYYY-MM-DD format
+    date: String,
+    /// Event start time in HH:MM format (24-hour), recorded in the annotation.
+    /// Defaults to the start time khal lists.
+    start_time: Option<String>,
+    /// Days before the event the prep task is due (default: 1)
+    days_before: Option<u32>,
+    /// Project to assign
+    project: Option<String>,
+    /// Additional tags (comma-separated). +inbox and +calendar are always added.
+    tags: Option<String>,
+}
+
 // ============================================================================
 // The PIM MCP Server
 // ============================================================================
 
+/// Run a tool body until it finishes or the client cancels the request
+///
+/// Dropping the body drops any CLI command it is waiting on, and `aiandi_cli`
+/// kills that command's process group.
+async fn cancellable(
+    ctx: &RequestContext<RoleServer>,
+    body: impl Future<Output = String>,
+) -> String {
+    tokio::select! {
+        output = body => output,
+        _ = ctx.ct.cancelled() => "Cancelled".to_string(),
+    }
+}
+
 #[derive(Clone)]
 pub struct PimServer {
     tool_router: ToolRouter<Self>,
     calendar: CalendarTools,
     email: EmailTools,
     contacts: ContactTools,
+    briefing: BriefingTools,
+    capture: CaptureTools,
+    guard: ModeGuard,
 }
 
 #[tool_router]
 impl PimServer {
     fn new() -> Self {
+        Self::from_config(&PimConfig::from_env())
+    }
+
+    fn from_config(config: &PimConfig) -> Self {
+        let calendar = CalendarTools::from_config(config);
+        let email = EmailTools::from_config(config);
+        let tasks = TaskTools::from_config(config);
+
         Self {
             tool_router: Self::tool_router(),
-            calendar: CalendarTools::new(),
-            email: EmailTools::new(),
-            contacts: ContactTools::new(),
+            briefing: BriefingTools::new(calendar.clone(), email.clone(), tasks.clone(), config),
+            capture: CaptureTools::new(calendar.clone(), email.clone(), tasks),
+            calendar,
+            email,
+            contacts: ContactTools::from_config(config),
+            guard: ModeGuard::new(&config.mode_state, config.no_mode_guard),
         }
     }
 
@@ -129,24 +216,45 @@ impl PimServer {
     // =========================================================================
 
     #[tool(
-        description = "List calendar events for a date range. Returns events from all calendars (personal, Meli, Dennis, holidays)."
+        description = "List calendar events for a date range. Returns events from all calendars (personal, Meli, Dennis, holidays) plus birthdays and anniversaries from contacts."
+    )]
+    async fn list_events(
+        &self,
+        Parameters(req): Parameters<ListEventsRequest>,
+        ctx: RequestContext<RoleServer>,
+    ) -> String {
+        cancellable(&ctx, self.calendar.list_events(req.start_date, req.days)).await
+    }
+
+    #[tool(
+        description = "List upcoming birthdays and anniversaries from contacts. Looks ahead 30 days by default."
     )]
-    async fn list_events(&self, Parameters(req): Parameters<ListEventsRequest>) -> String {
-        self.calendar.list_events(req.start_date, req.days).await
+    async fn upcoming_birthdays(
+        &self,
+        Parameters(req): Parameters<UpcomingBirthdaysRequest>,
+        ctx: RequestContext<RoleServer>,
+    ) -> String {
+        cancellable(&ctx, self.calendar.upcoming_birthdays(req.days)).await
     }
 
     #[tool(description = "Create a new calendar event on your personal calendar.")]
-    async fn create_event(&self, Parameters(req): Parameters<CreateEventRequest>) -> String {
-        self.calendar
-            .create_event(
+    async fn create_event(
+        &self,
+        Parameters(req): Parameters<CreateEventRequest>,
+        ctx: RequestContext<RoleServer>,
+    ) -> String {
+        cancellable(
+            &ctx,
+            self.calendar.create_event(
                 req.title,
                 req.date,
                 req.start_time,
                 req.end_time,
                 req.location,
                 req.description,
-            )
-            .await
+            ),
+        )
+        .await
     }
 
     // =========================================================================
@@ -156,20 +264,34 @@ impl PimServer {
     #[tool(
         description = "Search emails using notmuch query syntax. Supports: from:, to:, subject:, date:, tag:, and free text."
     )]
-    async fn search_emails(&self, Parameters(req): Parameters<SearchEmailsRequest>) -> String {
-        self.email.search(req.query, req.limit).await
+    async fn search_emails(
+        &self,
+        Parameters(req): Parameters<SearchEmailsRequest>,
+        ctx: RequestContext<RoleServer>,
+    ) -> String {
+        cancellable(&ctx, self.email.search(req.query, req.limit)).await
     }
 
     #[tool(description = "Read the full content of an email thread.")]
-    async fn read_email(&self, Parameters(req): Parameters<ReadEmailRequest>) -> String {
-        self.email.read_thread(req.thread_id).await
+    async fn read_email(
+        &self,
+        Parameters(req): Parameters<ReadEmailRequest>,
+        ctx: RequestContext<RoleServer>,
+    ) -> String {
+        cancellable(&ctx, self.email.read_thread(req.thread_id)).await
     }
 
     #[tool(description = "Send an email. Requires explicit confirmation for safety.")]
-    async fn send_email(&self, Parameters(req): Parameters<SendEmailRequest>) -> String {
-        self.email
-            .send(req.to, req.subject, req.body, req.confirm)
-            .await
+    async fn send_email(
+        &self,
+        Parameters(req): Parameters<SendEmailRequest>,
+        ctx: RequestContext<RoleServer>,
+    ) -> String {
+        cancellable(
+            &ctx,
+            self.email.send(req.to, req.subject, req.body, req.confirm),
+        )
+        .await
     }
 
     // =========================================================================
@@ -177,24 +299,95 @@ impl PimServer {
     // =========================================================================
 
     #[tool(description = "Search contacts by name, email, or phone number.")]
-    async fn find_contact(&self, Parameters(req): Parameters<FindContactRequest>) -> String {
-        self.contacts.search(req.query).await
+    async fn find_contact(
+        &self,
+        Parameters(req): Parameters<FindContactRequest>,
+        ctx: RequestContext<RoleServer>,
+    ) -> String {
+        cancellable(&ctx, self.contacts.search(req.query)).await
     }
 
     #[tool(description = "Get full details of a specific contact.")]
-    async fn get_contact(&self, Parameters(req): Parameters<GetContactRequest>) -> String {
-        self.contacts.get(req.name).await
+    async fn get_contact(
+        &self,
+        Parameters(req): Parameters<GetContactRequest>,
+        ctx: RequestContext<RoleServer>,
+    ) -> String {
+        cancellable(&ctx, self.contacts.get(req.name)).await
     }
 
     #[tool(description = "Create a new contact.")]
-    async fn create_contact(&self, Parameters(req): Parameters<CreateContactRequest>) -> String {
-        self.contacts
-            .create(req.name, req.email, req.phone, req.organization)
-            .await
+    async fn create_contact(
+        &self,
+        Parameters(req): Parameters<CreateContactRequest>,
+        ctx: RequestContext<RoleServer>,
+    ) -> String {
+        cancellable(
+            &ctx,
+            self.contacts
+                .create(req.name, req.email, req.phone, req.organization),
+        )
+        .await
+    }
+
+    // =========================================================================
+    // Capture Tools
+    // =========================================================================
+
+    #[tool(
+        description = "Capture an email thread as a TaskWarrior inbox task. Uses the subject as description, tags +inbox +email, and annotates with the Message-ID."
+    )]
+    async fn capture_email_as_task(
+        &self,
+        Parameters(req): Parameters<CaptureEmailRequest>,
+        ctx: RequestContext<RoleServer>,
+    ) -> String {
+        cancellable(
+            &ctx,
+            self.capture
+                .capture_email(req.thread_id, req.due, req.project, req.tags),
+        )
+        .await
+    }
+
+    #[tool(
+        description = "Create a TaskWarrior inbox task to prepare for a calendar event, due N days before it (default: 1). The event is looked up in khal by title and date; capture fails if it is not found."
+    )]
+    async fn capture_event_prep_task(
+        &self,
+        Parameters(req): Parameters<CaptureEventRequest>,
+        ctx: RequestContext<RoleServer>,
+    ) -> String {
+        cancellable(
+            &ctx,
+            self.capture.capture_event_prep(
+                req.title,
+                req.date,
+                req.start_time,
+                req.days_before,
+                req.project,
+                req.tags,
+            ),
+        )
+        .await
+    }
+
+    // =========================================================================
+    // Briefing Tools
+    // =========================================================================
+
+    #[tool(
+        description = "Daily briefing: today's and tomorrow's events, unread/flagged email threads, due and overdue tasks, and hot/waiting attention items in one JSON document. Takes the PLAN time of day (morning, afternoon, evening, night)."
+    )]
+    async fn daily_briefing(
+        &self,
+        Parameters(req): Parameters<DailyBriefingRequest>,
+        ctx: RequestContext<RoleServer>,
+    ) -> String {
+        cancellable(&ctx, self.briefing.daily_briefing(req.time_of_day)).await
     }
 }
 
-#[tool_handler]
 impl ServerHandler for PimServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -202,6 +395,36 @@ impl ServerHandler for PimServer {
             ..Default::default()
         }
     }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        let tools = self
+            .guard
+            .allowed(self.tool_router.list_all(), |tool| tool.name.as_ref())
+            .await;
+
+        Ok(ListToolsResult {
+            tools,
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Verdict::Blocked(reason) = self.guard.check(&request.name).await {
+            return Ok(CallToolResult::error(vec![Content::text(reason)]));
+        }
+
+        let tcc = ToolCallContext::new(self, request, context);
+        self.tool_router.call(tcc).await
+    }
 }
 
 #[tokio::main]
@@ -209,7 +432,11 @@ async fn main() -> Result<()> {
     // Initialize logging to stderr (NEVER stdout - would corrupt JSON-RPC)
     tracing_subscriber::registry()
         .with(fmt::layer().with_writer(std::io::stderr))
-        .with(EnvFilter::from_default_env().add_directive("pim=info".parse()?))
+        .with(
+            EnvFilter::from_default_env()
+                .add_directive("pim=info".parse()?)
+                .add_directive("aiandi_cli=info".parse()?),
+        )
         .init();
 
     tracing::info!("Starting pim MCP server");
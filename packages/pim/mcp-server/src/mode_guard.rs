@@ -0,0 +1,262 @@
+//! Mode guard - refuses tools the current aiandi-mode policy blocks
+//!
+//! aiandi-mode lists the tools its policy blocks in the current mode under
+//! `blocked_tools` in its state snapshot (e.g. `send_email` and
+//! `create_event` outside ops mode). Every pim tool call is checked against
+//! that list before it runs.
+//!
+//! Without a snapshot (aiandi-mode has not run) the default policy applies:
+//! [`DEFAULT_BLOCKED`] is refused, unless the guard was built with the
+//! opt-out (`AIANDI_PIM_NO_MODE_GUARD`). A snapshot written before
+//! aiandi-mode had a policy gets the same defaults outside ops mode. A
+//! snapshot that exists but can't be read blocks everything it might list,
+//! since it can't be told apart from one that blocks the tool.
+//!
+//! aiandi-mode records its process id as `owner_pid`. A snapshot whose owner
+//! is no longer running counts as missing, so a session that ended in ops
+//! mode doesn't leave `send_email` unblocked.
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Tools aiandi-mode's default policy keeps to ops mode
+pub const DEFAULT_BLOCKED: &[&str] = &["create_event", "send_email"];
+
+#[derive(Deserialize)]
+struct ModeSnapshot {
+    #[serde(default)]
+    current_mode: Option<String>,
+    #[serde(default)]
+    blocked_tools: Option<Vec<String>>,
+    /// The aiandi-mode server that wrote it (absent in older snapshots)
+    #[serde(default)]
+    owner_pid: Option<u32>,
+}
+
+/// What the guard knows about the current mode
+enum ModeState {
+    /// No snapshot, and the opt-out is set
+    Unguarded,
+    /// No snapshot, or its owner has exited; the default policy applies
+    Missing,
+    Snapshot(ModeSnapshot),
+    Unreadable(String),
+}
+
+/// What the snapshot says about a tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Allowed,
+    Blocked(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ModeGuard {
+    snapshot: PathBuf,
+    /// Allow every tool when there is no snapshot
+    allow_without_snapshot: bool,
+}
+
+impl ModeGuard {
+    pub fn new(snapshot: impl Into<PathBuf>, allow_without_snapshot: bool) -> Self {
+        Self {
+            snapshot: snapshot.into(),
+            allow_without_snapshot,
+        }
+    }
+
+    async fn read(&self) -> ModeState {
+        let content = match tokio::fs::read_to_string(&self.snapshot).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return self.missing(),
+            Err(e) => return ModeState::Unreadable(e.to_string()),
+        };
+
+        match serde_json::from_str::<ModeSnapshot>(&content) {
+            Ok(snapshot) if snapshot.owner_pid.is_some_and(|pid| !process_alive(pid)) => {
+                self.missing()
+            }
+            Ok(snapshot) => ModeState::Snapshot(snapshot),
+            Err(e) => ModeState::Unreadable(e.to_string()),
+        }
+    }
+
+    fn missing(&self) -> ModeState {
+        if self.allow_without_snapshot {
+            ModeState::Unguarded
+        } else {
+            ModeState::Missing
+        }
+    }
+
+    fn verdict(&self, state: &ModeState, tool: &str) -> Verdict {
+        let snapshot = match state {
+            ModeState::Unguarded => return Verdict::Allowed,
+            ModeState::Missing if DEFAULT_BLOCKED.contains(&tool) => {
+                return Verdict::Blocked(format!(
+                    "'{}' is only available in ops mode, and no running aiandi-mode has written {}. \
+                     Enter ops mode (aiandi-mode enter_mode) first.",
+                    tool,
+                    self.snapshot.display()
+                ));
+            }
+            ModeState::Missing => return Verdict::Allowed,
+            ModeState::Unreadable(error) => return unreadable(&self.snapshot, tool, error),
+            ModeState::Snapshot(snapshot) => snapshot,
+        };
+
+        let mode = snapshot.current_mode.as_deref();
+        let blocked = match &snapshot.blocked_tools {
+            Some(blocked) => blocked.iter().any(|t| t == tool),
+            None => mode != Some("ops") && DEFAULT_BLOCKED.contains(&tool),
+        };
+        if !blocked {
+            return Verdict::Allowed;
+        }
+
+        Verdict::Blocked(format!(
+            "'{}' is not available in {} mode. Enter ops mode (aiandi-mode enter_mode) first.",
+            tool,
+            mode.unwrap_or("the current")
+        ))
+    }
+
+    /// Whether `tool` may run in the mode recorded in the snapshot
+    pub async fn check(&self, tool: &str) -> Verdict {
+        self.verdict(&self.read().await, tool)
+    }
+
+    /// The subset of `tools` that may run, reading the snapshot once
+    pub async fn allowed<T>(&self, tools: Vec<T>, name: impl Fn(&T) -> &str) -> Vec<T> {
+        let state = self.read().await;
+        tools
+            .into_iter()
+            .filter(|tool| self.verdict(&state, name(tool)) == Verdict::Allowed)
+            .collect()
+    }
+}
+
+/// Whether process `pid` still exists
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    if pid <= 0 {
+        return false;
+    }
+    // SAFETY: signal 0 sends nothing, it only checks the process exists
+    let signalled = unsafe { libc::kill(pid, 0) } == 0;
+    // EPERM: it exists but belongs to another user
+    signalled || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+fn unreadable(path: &Path, tool: &str, error: &str) -> Verdict {
+    Verdict::Blocked(format!(
+        "'{}' refused: could not read the aiandi-mode state at {}: {}",
+        tool,
+        path.display(),
+        error
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn verdict(snapshot: Option<&str>, tool: &str) -> Verdict {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mode-state.json");
+        if let Some(json) = snapshot {
+            std::fs::write(&path, json).unwrap();
+        }
+        ModeGuard::new(&path, false).check(tool).await
+    }
+
+    #[tokio::test]
+    async fn test_blocked_tools_are_refused() {
+        let json =
+            r#"{"current_mode": "default", "blocked_tools": ["create_event", "send_email"]}"#;
+
+        assert_eq!(verdict(Some(json), "list_events").await, Verdict::Allowed);
+        match verdict(Some(json), "send_email").await {
+            Verdict::Blocked(message) => assert!(message.contains("default mode")),
+            Verdict::Allowed => panic!("send_email should be blocked"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_missing_or_old_snapshot_uses_default_policy() {
+        assert!(matches!(
+            verdict(None, "send_email").await,
+            Verdict::Blocked(_)
+        ));
+        assert_eq!(verdict(None, "list_events").await, Verdict::Allowed);
+
+        // Written before aiandi-mode had a policy
+        let old = r#"{"current_mode": "default", "attention_stack": []}"#;
+        assert!(matches!(
+            verdict(Some(old), "create_event").await,
+            Verdict::Blocked(_)
+        ));
+        let old_ops = r#"{"current_mode": "ops", "attention_stack": []}"#;
+        assert_eq!(verdict(Some(old_ops), "send_email").await, Verdict::Allowed);
+    }
+
+    #[tokio::test]
+    async fn test_opt_out_allows_without_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let guard = ModeGuard::new(dir.path().join("mode-state.json"), true);
+
+        assert_eq!(guard.check("send_email").await, Verdict::Allowed);
+    }
+
+    #[tokio::test]
+    async fn test_allowed_filters_blocked_tools() {
+        let dir = tempfile::tempdir().unwrap();
+        let guard = ModeGuard::new(dir.path().join("mode-state.json"), false);
+
+        let tools = vec!["create_event", "list_events", "send_email"];
+        assert_eq!(guard.allowed(tools, |t| t).await, vec!["list_events"]);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_of_exited_owner_is_ignored() {
+        let mut owner = std::process::Command::new("true").spawn().unwrap();
+        let dead = owner.id();
+        owner.wait().unwrap();
+
+        // Left behind by a session that ended in ops mode
+        let orphan = format!(
+            r#"{{"current_mode": "ops", "blocked_tools": [], "owner_pid": {}}}"#,
+            dead
+        );
+        assert!(matches!(
+            verdict(Some(&orphan), "send_email").await,
+            Verdict::Blocked(_)
+        ));
+        assert_eq!(
+            verdict(Some(&orphan), "list_events").await,
+            Verdict::Allowed
+        );
+
+        let live = format!(
+            r#"{{"current_mode": "ops", "blocked_tools": [], "owner_pid": {}}}"#,
+            std::process::id()
+        );
+        assert_eq!(verdict(Some(&live), "send_email").await, Verdict::Allowed);
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_snapshot_blocks() {
+        assert!(matches!(
+            verdict(Some("{not json"), "list_events").await,
+            Verdict::Blocked(_)
+        ));
+    }
+}
@@ -0,0 +1,292 @@
+//! Task tools - wraps TaskWarrior CLI
+//!
+//! pim reads TaskWarrior so briefings can put due work next to the calendar
+//! and mailbox, and adds inbox tasks so emails and events can be captured
+//! the same way `aiandi inbox` captures text. Task management itself stays
+//! with TaskWarrior.
+
+use crate::cli::{Access, CommandBuilder, JSON_MAX_OUTPUT, run_command_stdout};
+use crate::config::PimConfig;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// A task as exported by `task export`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Task {
+    #[serde(default)]
+    pub id: u32,
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub urgency: f64,
+}
+
+/// A task to be added to the inbox
+#[derive(Debug, Clone, Default)]
+pub struct NewTask {
+    pub description: String,
+    /// Extra tags, with or without leading `+` (`+inbox` is always added)
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    /// TaskWarrior date expression (e.g. `2026-01-09`, `friday`)
+    pub due: Option<String>,
+    /// Annotations added after the task is created
+    pub annotations: Vec<String>,
+}
+
+/// A task `add` created
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddedTask {
+    /// Stable across renumbering; used for every follow-up command
+    pub uuid: String,
+    /// Working-set ID at the time of the lookup, for display
+    pub id: Option<u32>,
+}
+
+impl std::fmt::Display for AddedTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.id {
+            Some(id) => write!(f, "task {}", id),
+            None => write!(f, "task {}", self.uuid),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TaskTools {
+    task: String,
+}
+
+impl Default for TaskTools {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaskTools {
+    pub fn new() -> Self {
+        Self::from_config(&PimConfig::from_env())
+    }
+
+    pub fn from_config(config: &PimConfig) -> Self {
+        Self {
+            task: config.task.clone(),
+        }
+    }
+
+    /// Build the arguments for `task <filter> export`
+    pub fn export_args(filter: &[&str]) -> Vec<String> {
+        let mut args = vec![
+            "rc.verbose=nothing".to_string(),
+            "status:pending".to_string(),
+        ];
+        args.extend(filter.iter().map(|s| s.to_string()));
+        args.push("export".to_string());
+        args
+    }
+
+    /// Build the arguments for `task add`
+    ///
+    /// Attributes come first and the description follows `--`, so subjects
+    /// like "Re: project:foo" are never parsed as modifications.
+    pub fn add_args(task: &NewTask) -> Vec<String> {
+        let mut args = vec!["rc.verbose=new-uuid".to_string(), "add".to_string()];
+
+        // Always add +inbox tag for inbox
+        args.push("+inbox".to_string());
+
+        for tag in &task.tags {
+            let tag = if tag.starts_with('+') {
+                tag.clone()
+            } else {
+                format!("+{}", tag)
+            };
+            args.push(tag);
+        }
+
+        if let Some(ref project) = task.project {
+            args.push(format!("project:{}", project));
+        }
+        if let Some(ref due) = task.due {
+            args.push(format!("due:{}", due));
+        }
+
+        args.push("--".to_string());
+        args.push(task.description.clone());
+        args
+    }
+
+    /// Parse the task UUID from TaskWarrior output ("Created task <uuid>.")
+    pub fn parse_task_uuid(output: &str) -> Option<String> {
+        output
+            .split_whitespace()
+            .map(|word| word.trim_end_matches('.'))
+            .find(|word| {
+                word.len() == 36
+                    && word.matches('-').count() == 4
+                    && word.chars().all(|c| c == '-' || c.is_ascii_hexdigit())
+            })
+            .map(String::from)
+    }
+
+    /// Add a task to the inbox and annotate it
+    ///
+    /// Annotations address the task by UUID: IDs can be renumbered by any
+    /// other TaskWarrior command in between.
+    pub async fn add(&self, task: &NewTask) -> Result<AddedTask> {
+        if task.description.trim().is_empty() {
+            anyhow::bail!("Task description cannot be empty");
+        }
+
+        let args = Self::add_args(task);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = self.write(&args).await?;
+
+        let uuid = Self::parse_task_uuid(&output)
+            .with_context(|| format!("Could not find task UUID in output: {}", output.trim()))?;
+
+        for annotation in &task.annotations {
+            self.write(&["rc.verbose=nothing", &uuid, "annotate", "--", annotation])
+                .await
+                .with_context(|| format!("Task {} created but annotation failed", uuid))?;
+        }
+
+        let id = self.current_id(&uuid).await;
+        Ok(AddedTask { uuid, id })
+    }
+
+    /// The task's current working-set ID, if it has one
+    async fn current_id(&self, uuid: &str) -> Option<u32> {
+        let field = format!("{}.id", uuid);
+        let output = run_command_stdout(&self.task, &["rc.verbose=nothing", "_get", &field])
+            .await
+            .ok()?;
+        output.trim().parse().ok().filter(|id| *id != 0)
+    }
+
+    /// Run a command that modifies the data dir, after any other task runs
+    async fn write(&self, args: &[&str]) -> Result<String> {
+        Ok(CommandBuilder::new(&self.task)
+            .args(args)
+            .access(Access::Write)
+            .run_stdout()
+            .await?)
+    }
+
+    /// Parse `task export` JSON, sorted by urgency (highest first)
+    pub fn parse_export(output: &str) -> Result<Vec<Task>> {
+        if output.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut tasks: Vec<Task> =
+            serde_json::from_str(output).context("Failed to parse TaskWarrior export")?;
+        tasks.sort_by(|a, b| b.urgency.total_cmp(&a.urgency));
+        Ok(tasks)
+    }
+
+    /// Pending tasks matching a TaskWarrior filter (e.g. `+OVERDUE`)
+    pub async fn pending(&self, filter: &[&str]) -> Result<Vec<Task>> {
+        let args = Self::export_args(filter);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+        let output = CommandBuilder::new(&self.task)
+            .args(&args)
+            .max_output(JSON_MAX_OUTPUT)
+            .run_stdout()
+            .await?;
+        Self::parse_export(&output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_args() {
+        let args = TaskTools::export_args(&["+OVERDUE"]);
+        assert_eq!(
+            args,
+            vec!["rc.verbose=nothing", "status:pending", "+OVERDUE", "export"]
+        );
+    }
+
+    #[test]
+    fn test_parse_export_sorts_by_urgency() {
+        let json = r#"[
+            {"id": 1, "description": "Low", "urgency": 1.5, "status": "pending"},
+            {"id": 2, "description": "High", "urgency": 9.1, "due": "20260107T230000Z",
+             "project": "home", "tags": ["inbox"], "status": "pending"}
+        ]"#;
+        let tasks = TaskTools::parse_export(json).unwrap();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].description, "High");
+        assert_eq!(tasks[0].project.as_deref(), Some("home"));
+        assert_eq!(tasks[1].id, 1);
+    }
+
+    #[test]
+    fn test_add_args() {
+        let task = NewTask {
+            description: "Re: project:x budget".to_string(),
+            tags: vec!["email".to_string(), "+urgent".to_string()],
+            project: Some("home".to_string()),
+            due: Some("friday".to_string()),
+            ..Default::default()
+        };
+        let args = TaskTools::add_args(&task);
+
+        assert_eq!(
+            args,
+            vec![
+                "rc.verbose=new-uuid",
+                "add",
+                "+inbox",
+                "+email",
+                "+urgent",
+                "project:home",
+                "due:friday",
+                "--",
+                "Re: project:x budget",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_task_uuid() {
+        assert_eq!(
+            TaskTools::parse_task_uuid("Created task 0d9a2f4e-3c1b-4f5e-9a7d-2b6c8e1f0a37.")
+                .as_deref(),
+            Some("0d9a2f4e-3c1b-4f5e-9a7d-2b6c8e1f0a37")
+        );
+        assert_eq!(TaskTools::parse_task_uuid("Created task 42."), None);
+        assert_eq!(TaskTools::parse_task_uuid("nothing here"), None);
+    }
+
+    #[test]
+    fn test_added_task_display() {
+        let uuid = "0d9a2f4e-3c1b-4f5e-9a7d-2b6c8e1f0a37".to_string();
+        let task = AddedTask {
+            uuid: uuid.clone(),
+            id: Some(7),
+        };
+        assert_eq!(task.to_string(), "task 7");
+        assert_eq!(
+            AddedTask { uuid, id: None }.to_string(),
+            format!("task {}", task.uuid)
+        );
+    }
+
+    #[test]
+    fn test_parse_export_empty() {
+        assert!(TaskTools::parse_export("").unwrap().is_empty());
+        assert!(TaskTools::parse_export("[]").unwrap().is_empty());
+    }
+}
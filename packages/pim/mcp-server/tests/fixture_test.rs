@@ -0,0 +1,417 @@
+//! End-to-end tests: MCP client -> aiandi-pim -> stand-in CLI tools
+
+mod support;
+
+use serde_json::{Value, json};
+use support::Fixture;
+
+#[tokio::test]
+async fn test_lists_all_tools() {
+    let fixture = Fixture::start().await;
+
+    let tools = fixture.client.list_all_tools().await.unwrap();
+    let mut names: Vec<&str> = tools.iter().map(|t| t.name.as_ref()).collect();
+    names.sort();
+
+    assert_eq!(
+        names,
+        vec![
+            "capture_email_as_task",
+            "capture_event_prep_task",
+            "create_contact",
+            "create_event",
+            "daily_briefing",
+            "find_contact",
+            "get_contact",
+            "list_events",
+            "read_email",
+            "search_emails",
+            "send_email",
+            "upcoming_birthdays",
+        ]
+    );
+
+    fixture.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_list_events_merges_birthdays() {
+    let fixture = Fixture::start().await;
+
+    let output = fixture
+        .call(
+            "list_events",
+            json!({"start_date": "2026-01-07", "days": 366}),
+        )
+        .await;
+
+    assert!(
+        output.contains("[robbie] 09:00-09:30 Standup"),
+        "{}",
+        output
+    );
+    assert!(output.contains("[holidays] Vizkereszt"), "{}", output);
+    // Merged by date after khal's days, under their own day headers
+    let standup = output.find("Standup").unwrap();
+    let jane = output
+        .find("2026-04-12\n[birthdays] Birthday: Jane Smith (turns 41)")
+        .expect(&output);
+    let john = output
+        .find("2026-06-05\n[birthdays] Anniversary: John Doe (16 years)")
+        .expect(&output);
+    assert!(standup < jane && jane < john, "{}", output);
+    assert!(
+        fixture
+            .calls()
+            .iter()
+            .any(|c| c.starts_with("khal list") && c.contains("2026-01-07"))
+    );
+
+    fixture.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_list_events_birthdays_with_relative_start() {
+    let fixture = Fixture::start().await;
+
+    let output = fixture
+        .call("list_events", json!({"start_date": "today", "days": 366}))
+        .await;
+    assert!(output.contains("Birthday: Jane Smith"), "{}", output);
+    assert!(!output.contains("Birthdays skipped"), "{}", output);
+
+    let output = fixture
+        .call("list_events", json!({"start_date": "next week", "days": 7}))
+        .await;
+    assert!(
+        output.contains("Birthdays skipped: could not resolve start date 'next week'"),
+        "{}",
+        output
+    );
+
+    fixture.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_list_events_keeps_birthdays_when_khal_fails() {
+    let fixture = Fixture::start_with(&[("AIANDI_PIM_KHAL", "/nonexistent/khal")]).await;
+
+    let output = fixture
+        .call(
+            "list_events",
+            json!({"start_date": "2026-01-07", "days": 366}),
+        )
+        .await;
+    assert!(output.starts_with("Error listing events"), "{}", output);
+    assert!(
+        output.contains("[birthdays] 2026-04-12 Birthday: Jane Smith"),
+        "{}",
+        output
+    );
+
+    fixture.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_search_and_read_email() {
+    let fixture = Fixture::start().await;
+
+    let results = fixture
+        .call("search_emails", json!({"query": "tag:unread"}))
+        .await;
+    assert!(results.contains("thread:0000000000000001"), "{}", results);
+    assert!(results.contains("Lunch on Friday?"), "{}", results);
+
+    let thread = fixture
+        .call("read_email", json!({"thread_id": "0000000000000001"}))
+        .await;
+    assert!(thread.contains("Are we still on for lunch"), "{}", thread);
+
+    fixture.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_send_email_requires_confirmation() {
+    let fixture = Fixture::start().await;
+
+    let preview = fixture
+        .call(
+            "send_email",
+            json!({"to": "jane@example.com", "subject": "Re: Lunch", "body": "Yes!", "confirm": false}),
+        )
+        .await;
+    assert!(preview.contains("Preview"), "{}", preview);
+    assert!(fixture.calls().iter().all(|c| !c.starts_with("himalaya")));
+
+    let sent = fixture
+        .call(
+            "send_email",
+            json!({"to": "jane@example.com", "subject": "Re: Lunch", "body": "Yes!", "confirm": true}),
+        )
+        .await;
+    assert!(sent.contains("Email sent successfully"), "{}", sent);
+
+    let calls = fixture.calls();
+    assert!(
+        calls.contains(
+            &"himalaya message write --to jane@example.com --subject Re: Lunch".to_string()
+        )
+    );
+    assert!(calls.contains(&"himalaya stdin: Yes!".to_string()));
+
+    fixture.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_find_and_get_contact() {
+    let fixture = Fixture::start().await;
+
+    let found = fixture.call("find_contact", json!({"query": "jane"})).await;
+    assert!(found.contains("jane@example.com"), "{}", found);
+
+    let missing = fixture
+        .call("find_contact", json!({"query": "nobody"}))
+        .await;
+    assert!(
+        missing.contains("No contacts found matching: nobody"),
+        "{}",
+        missing
+    );
+
+    let card = fixture
+        .call("get_contact", json!({"name": "John Doe"}))
+        .await;
+    assert!(card.contains("ANNIVERSARY:20100605"), "{}", card);
+
+    fixture.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_find_contact_reports_khard_failure() {
+    let fixture =
+        Fixture::start_with(&[("AIANDI_PIM_CONTACTS_DIR", "/nonexistent/contacts")]).await;
+
+    let output = fixture.call("find_contact", json!({"query": "jane"})).await;
+    assert!(
+        output.starts_with("Error searching contacts:"),
+        "{}",
+        output
+    );
+    assert!(output.contains("is not a directory"), "{}", output);
+
+    fixture.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_capture_email_as_task() {
+    let fixture = Fixture::start().await;
+
+    let output = fixture
+        .call(
+            "capture_email_as_task",
+            json!({"thread_id": "thread:0000000000000001", "due": "friday"}),
+        )
+        .await;
+    assert!(output.contains("Captured to inbox: task 7"), "{}", output);
+
+    let calls = fixture.calls();
+    assert!(calls.contains(
+        &"task rc.verbose=new-uuid add +inbox +email due:friday -- Lunch on Friday?".to_string()
+    ));
+    assert!(calls.contains(
+        &"task rc.verbose=nothing 0d9a2f4e-3c1b-4f5e-9a7d-2b6c8e1f0a37 annotate -- Message-ID: <fixture-1@example.com>"
+            .to_string()
+    ));
+
+    fixture.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_capture_event_prep_checks_calendar() {
+    let fixture = Fixture::start().await;
+
+    let output = fixture
+        .call(
+            "capture_event_prep_task",
+            json!({"title": "standup", "date": "2026-01-07"}),
+        )
+        .await;
+    assert!(output.contains("Captured to inbox: task 7"), "{}", output);
+    assert!(
+        output.contains("Event: standup on 2026-01-07 09:00"),
+        "{}",
+        output
+    );
+    assert!(fixture.calls().contains(
+        &"task rc.verbose=nothing 0d9a2f4e-3c1b-4f5e-9a7d-2b6c8e1f0a37 annotate -- Event: standup on 2026-01-07 09:00"
+            .to_string()
+    ));
+
+    let output = fixture
+        .call(
+            "capture_event_prep_task",
+            json!({"title": "Dentist", "date": "2026-01-07"}),
+        )
+        .await;
+    assert!(
+        output.contains("No event 'Dentist' on 2026-01-07 in the calendar"),
+        "{}",
+        output
+    );
+    let adds = fixture
+        .calls()
+        .iter()
+        .filter(|call| call.contains(" add "))
+        .count();
+    assert_eq!(adds, 1);
+
+    fixture.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_daily_briefing() {
+    let fixture = Fixture::start().await;
+
+    let output = fixture
+        .call("daily_briefing", json!({"time_of_day": "evening"}))
+        .await;
+    let briefing: Value = serde_json::from_str(&output).expect(&output);
+
+    assert_eq!(briefing["phase"], "evening");
+    assert_eq!(briefing["focus"], "Close + tomorrow seed");
+    assert_eq!(briefing["email"]["items"][0]["subject"], "Lunch on Friday?");
+    assert_eq!(
+        briefing["tasks"]["overdue"]["items"][0]["description"],
+        "Renew passport"
+    );
+    assert_eq!(briefing["tasks"]["due_tomorrow"]["items"], json!([]));
+
+    let attention: Vec<&str> = briefing["attention"]["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(attention, vec!["ins-1", "cal-1"]);
+
+    fixture.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_daily_briefing_reports_khal_failure_per_section() {
+    let fixture = Fixture::start_with(&[("AIANDI_PIM_KHAL", "/nonexistent/khal")]).await;
+
+    let output = fixture
+        .call("daily_briefing", json!({"time_of_day": "morning"}))
+        .await;
+    let briefing: Value = serde_json::from_str(&output).expect(&output);
+
+    assert!(
+        briefing["events"]["today"]["error"].is_string(),
+        "{}",
+        output
+    );
+    assert_eq!(
+        briefing["email"]["items"][0]["subject"], "Lunch on Friday?",
+        "{}",
+        output
+    );
+
+    fixture.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_replays_cassette_without_tools() {
+    let cassette = support::fixtures_dir().join("../cassettes/email-contacts.json");
+    let fixture = Fixture::start_with(&[
+        ("AIANDI_PIM_BIN_DIR", "/nonexistent/aiandi-pim-bin"),
+        ("AIANDI_CLI_CASSETTE", cassette.to_str().unwrap()),
+    ])
+    .await;
+
+    let results = fixture
+        .call("search_emails", json!({"query": "tag:unread"}))
+        .await;
+    assert!(results.contains("Lunch on Friday?"), "{}", results);
+
+    let found = fixture
+        .call("find_contact", json!({"query": "nobody"}))
+        .await;
+    assert!(
+        found.contains("No contacts found matching: nobody"),
+        "{}",
+        found
+    );
+
+    // Not on the tape: fails with the command that was missing
+    let missing = fixture
+        .call("search_emails", json!({"query": "tag:flagged"}))
+        .await;
+    assert!(missing.contains("No recorded run in"), "{}", missing);
+    assert!(missing.contains("tag:flagged"), "{}", missing);
+
+    assert!(fixture.calls().is_empty());
+    fixture.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_mode_policy_blocks_send_outside_ops() {
+    let state = tempfile::tempdir().unwrap();
+    let snapshot = state.path().join("mode-state.json");
+    std::fs::write(
+        &snapshot,
+        r#"{"current_mode": "default", "attention_stack": [], "blocked_tools": ["create_event", "send_email"]}"#,
+    )
+    .unwrap();
+    let fixture = Fixture::start_with(&[("AIANDI_MODE_STATE", snapshot.to_str().unwrap())]).await;
+
+    let refused = fixture
+        .call(
+            "send_email",
+            json!({"to": "jane@example.com", "subject": "Hi", "body": "Hi", "confirm": true}),
+        )
+        .await;
+    assert!(
+        refused.contains("not available in default mode"),
+        "{}",
+        refused
+    );
+    assert!(fixture.calls().iter().all(|c| !c.starts_with("himalaya")));
+
+    // Reads are unaffected
+    let results = fixture
+        .call("search_emails", json!({"query": "tag:unread"}))
+        .await;
+    assert!(results.contains("Lunch on Friday?"), "{}", results);
+
+    fixture.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_blocked_tools_are_not_listed() {
+    let state = tempfile::tempdir().unwrap();
+    let snapshot = state.path().join("mode-state.json");
+    let fixture = Fixture::start_with(&[("AIANDI_MODE_STATE", snapshot.to_str().unwrap())]).await;
+
+    // No snapshot: the default policy keeps writes to ops mode
+    let tools = fixture.client.list_all_tools().await.unwrap();
+    let names: Vec<&str> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert!(!names.contains(&"send_email"), "{:?}", names);
+    assert!(!names.contains(&"create_event"), "{:?}", names);
+    assert!(names.contains(&"list_events"), "{:?}", names);
+
+    let refused = fixture
+        .call(
+            "create_event",
+            json!({"title": "Lunch", "date": "2026-01-09"}),
+        )
+        .await;
+    assert!(
+        refused.contains("only available in ops mode"),
+        "{}",
+        refused
+    );
+    assert!(fixture.calls().iter().all(|c| !c.starts_with("khal new")));
+
+    fixture.shutdown().await;
+}
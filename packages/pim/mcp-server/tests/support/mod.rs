@@ -0,0 +1,101 @@
+//! Fixture harness: runs the pim server against stand-in CLI tools
+//!
+//! `tests/fixtures/bin` holds shell scripts named after the real tools
+//! (khal, khard, notmuch, himalaya, task). They print canned output based on
+//! `tests/fixtures/data` and append every invocation to a log file, so tests
+//! can check both what the server returns and which commands it ran.
+
+use rmcp::{
+    ServiceExt,
+    model::{CallToolRequestParam, RawContent},
+    service::{RoleClient, RunningService},
+    transport::TokioChildProcess,
+};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use tokio::process::Command;
+
+pub type Client = RunningService<RoleClient, ()>;
+
+pub fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// One server process wired to the stand-in tools
+pub struct Fixture {
+    home: TempDir,
+    pub client: Client,
+}
+
+impl Fixture {
+    /// Spawn the server with every tool path pointed at the fixtures
+    pub async fn start() -> Self {
+        Self::start_with(&[]).await
+    }
+
+    /// Like `start`, with extra (or overriding) environment variables
+    pub async fn start_with(env: &[(&str, &str)]) -> Self {
+        let home = TempDir::new().expect("Failed to create temp HOME");
+        let fixtures = fixtures_dir();
+
+        let mut command = Command::new(env!("CARGO_BIN_EXE_aiandi-pim"));
+        command
+            .env_clear()
+            .env("PATH", std::env::var("PATH").unwrap_or_default())
+            .env("HOME", home.path())
+            .env("RUST_LOG", "error")
+            .env("AIANDI_PIM_BIN_DIR", fixtures.join("bin"))
+            .env("AIANDI_PIM_CONTACTS_DIR", fixtures.join("data/contacts"))
+            .env("AIANDI_MODE_STATE", fixtures.join("data/mode-state.json"))
+            .env("PIM_FIXTURE_DATA", fixtures.join("data"))
+            .env("PIM_FIXTURE_LOG", home.path().join("calls.log"))
+            .envs(env.iter().copied());
+
+        let transport = TokioChildProcess::new(command).expect("Failed to spawn aiandi-pim");
+        let client = ().serve(transport).await.expect("Failed to initialize MCP session");
+
+        Self { home, client }
+    }
+
+    /// Call a tool and return its text output
+    pub async fn call(&self, name: &str, arguments: Value) -> String {
+        let arguments: Map<String, Value> = match arguments {
+            Value::Object(map) => map,
+            Value::Null => Map::new(),
+            other => panic!("Tool arguments must be an object, got {}", other),
+        };
+
+        let result = self
+            .client
+            .call_tool(CallToolRequestParam {
+                name: name.to_string().into(),
+                arguments: Some(arguments),
+            })
+            .await
+            .unwrap_or_else(|e| panic!("{} failed: {}", name, e));
+
+        result
+            .content
+            .iter()
+            .filter_map(|c| match &c.raw {
+                RawContent::Text(t) => Some(t.text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Every stand-in tool invocation so far, one line each
+    pub fn calls(&self) -> Vec<String> {
+        std::fs::read_to_string(self.home.path().join("calls.log"))
+            .unwrap_or_default()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    pub async fn shutdown(self) {
+        self.client.cancel().await.ok();
+    }
+}
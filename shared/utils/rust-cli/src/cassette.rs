@@ -0,0 +1,382 @@
+//! Record/replay of command runs for deterministic tests
+//!
+//! A [`Cassette`] is a JSON file of interactions: what was run (program file
+//! name, args, stdin, variables set with [`CommandBuilder::env`]) and what it
+//! produced (stdout, stderr, exit code or signal).
+//!
+//! - **Record**: commands run for real and each interaction is appended to the
+//!   cassette, which is rewritten after every run.
+//! - **Replay**: nothing is spawned. Each run is answered by the first unused
+//!   interaction with the same request; a run with no match fails with
+//!   [`CommandError::CassetteMismatch`].
+//!
+//! Cassettes are activated per task with [`Cassette::scope`], or for the whole
+//! process with `AIANDI_CLI_CASSETTE=<path>` and
+//! `AIANDI_CLI_CASSETTE_MODE=record|replay` (default: replay). The environment
+//! route lets a test drive a server binary whose wrappers build their own
+//! commands.
+//!
+//! Programs are matched by file name, so a cassette recorded against
+//! `~/.local/bin/himalaya` replays for `/usr/bin/himalaya`.
+//!
+//! [`CommandBuilder::env`]: crate::CommandBuilder::env
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Output};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::{exit_signal, CommandError, Result};
+
+/// Cassette file path
+pub const CASSETTE_ENV: &str = "AIANDI_CLI_CASSETTE";
+
+/// `record` or `replay` (default)
+pub const CASSETTE_MODE_ENV: &str = "AIANDI_CLI_CASSETTE_MODE";
+
+tokio::task_local! {
+    static SCOPED: Arc<Cassette>;
+}
+
+/// Whether a cassette runs commands or answers them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    Record,
+    Replay,
+}
+
+/// What was run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Request {
+    /// File name of the program (`/usr/bin/khal` → `khal`)
+    pub program: String,
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdin: Option<String>,
+    /// Variables set explicitly on the builder
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+impl Request {
+    fn command_line(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// What it produced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Response {
+    #[serde(default)]
+    pub stdout: String,
+    #[serde(default)]
+    pub stderr: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signal: Option<i32>,
+}
+
+/// One recorded run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    #[serde(flatten)]
+    pub request: Request,
+    #[serde(flatten)]
+    pub response: Response,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+struct Tape {
+    interactions: Vec<Interaction>,
+    /// Replay: which interactions have been served
+    used: Vec<bool>,
+}
+
+/// A set of recorded command runs; see the module docs
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    tape: Mutex<Tape>,
+}
+
+impl Cassette {
+    /// Replay from an existing cassette file
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| CommandError::cassette(&path, format!("Failed to read: {}", e)))?;
+        let file: CassetteFile = serde_json::from_str(&content)
+            .map_err(|e| CommandError::cassette(&path, format!("Failed to parse: {}", e)))?;
+
+        let used = vec![false; file.interactions.len()];
+        Ok(Self {
+            path,
+            mode: CassetteMode::Replay,
+            tape: Mutex::new(Tape {
+                interactions: file.interactions,
+                used,
+            }),
+        })
+    }
+
+    /// Record into `path`, replacing it on the first run
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            mode: CassetteMode::Record,
+            tape: Mutex::new(Tape {
+                interactions: Vec::new(),
+                used: Vec::new(),
+            }),
+        }
+    }
+
+    /// Open the cassette named by `AIANDI_CLI_CASSETTE`, if any
+    pub fn from_env() -> Option<Result<Self>> {
+        let path = std::env::var_os(CASSETTE_ENV)?;
+        let mode = std::env::var(CASSETTE_MODE_ENV).unwrap_or_default();
+
+        Some(match mode.as_str() {
+            "record" => Ok(Self::record(path)),
+            "" | "replay" => Self::replay(path),
+            other => Err(CommandError::cassette(
+                Path::new(&path),
+                format!("Unknown {}: {}", CASSETTE_MODE_ENV, other),
+            )),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Replay: interactions not served yet (for "every call happened" asserts)
+    pub fn remaining(&self) -> usize {
+        let tape = self.tape.lock().unwrap();
+        tape.used.iter().filter(|used| !**used).count()
+    }
+
+    /// Run `future` with this cassette answering (or recording) its commands
+    pub async fn scope<F: Future>(self: Arc<Self>, future: F) -> F::Output {
+        SCOPED.scope(self, future).await
+    }
+
+    /// The cassette for the current task: scoped first, then the environment
+    pub(crate) fn active() -> Result<Option<Arc<Cassette>>> {
+        if let Ok(cassette) = SCOPED.try_with(Arc::clone) {
+            return Ok(Some(cassette));
+        }
+
+        static FROM_ENV: OnceLock<Option<std::result::Result<Arc<Cassette>, String>>> =
+            OnceLock::new();
+        let from_env = FROM_ENV.get_or_init(|| {
+            Self::from_env().map(|opened| {
+                opened.map(Arc::new).map_err(|e| match e {
+                    CommandError::CassetteFailed { message, .. } => message,
+                    other => other.to_string(),
+                })
+            })
+        });
+        match from_env {
+            None => Ok(None),
+            Some(Ok(cassette)) => Ok(Some(Arc::clone(cassette))),
+            Some(Err(message)) => Err(CommandError::CassetteFailed {
+                cassette: std::env::var_os(CASSETTE_ENV).unwrap_or_default().into(),
+                message: message.clone(),
+            }),
+        }
+    }
+
+    /// Answer `request` from the tape
+    pub(crate) fn answer(&self, request: &Request) -> Result<Output> {
+        let mut tape = self.tape.lock().unwrap();
+        let Tape { interactions, used } = &mut *tape;
+
+        let index = interactions
+            .iter()
+            .zip(used.iter())
+            .position(|(i, used)| !used && i.request == *request)
+            .ok_or_else(|| CommandError::CassetteMismatch {
+                cassette: self.path.clone(),
+                command: request.command_line(),
+                stdin: request.stdin.clone(),
+            })?;
+
+        used[index] = true;
+        Ok(interactions[index].response.to_output())
+    }
+
+    /// Append a run and rewrite the cassette file
+    pub(crate) fn store(&self, request: Request, output: &Output) -> Result<()> {
+        let mut tape = self.tape.lock().unwrap();
+        tape.interactions.push(Interaction {
+            request,
+            response: Response::from_output(output),
+        });
+
+        let file = CassetteFile {
+            interactions: tape.interactions.clone(),
+        };
+        let json = serde_json::to_string_pretty(&file)
+            .map_err(|e| CommandError::cassette(&self.path, e.to_string()))?;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| CommandError::cassette(&self.path, e.to_string()))?;
+        }
+        std::fs::write(&self.path, json + "\n")
+            .map_err(|e| CommandError::cassette(&self.path, format!("Failed to write: {}", e)))
+    }
+}
+
+impl Response {
+    fn from_output(output: &Output) -> Self {
+        Self {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            exit_code: output.status.code(),
+            signal: exit_signal(output.status),
+        }
+    }
+
+    fn to_output(&self) -> Output {
+        Output {
+            status: exit_status(self.exit_code, self.signal),
+            stdout: self.stdout.clone().into_bytes(),
+            stderr: self.stderr.clone().into_bytes(),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn exit_status(code: Option<i32>, signal: Option<i32>) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    match (code, signal) {
+        (_, Some(signal)) => ExitStatus::from_raw(signal),
+        (code, None) => ExitStatus::from_raw((code.unwrap_or(0) & 0xff) << 8),
+    }
+}
+
+#[cfg(windows)]
+fn exit_status(code: Option<i32>, _signal: Option<i32>) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code.unwrap_or(0) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_command_stdout, CommandBuilder};
+
+    fn temp_cassette(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("aiandi-cli-{}-{}.json", name, std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let path = temp_cassette("roundtrip");
+
+        let recorder = Arc::new(Cassette::record(&path));
+        let recorded = recorder
+            .scope(async {
+                CommandBuilder::new("/bin/sh")
+                    .args(&["-c", "cat; echo \"$GREETING\""])
+                    .stdin("from stdin\n")
+                    .env("GREETING", "hello")
+                    .run_stdout()
+                    .await
+                    .unwrap()
+            })
+            .await;
+        assert_eq!(recorded, "from stdin\nhello\n");
+
+        // Same request via a different path to `sh`: served from the tape
+        let player = Arc::new(Cassette::replay(&path).unwrap());
+        let replayed = Arc::clone(&player)
+            .scope(async {
+                CommandBuilder::new("/nonexistent/bin/sh")
+                    .args(&["-c", "cat; echo \"$GREETING\""])
+                    .stdin("from stdin\n")
+                    .env("GREETING", "hello")
+                    .run_stdout()
+                    .await
+                    .unwrap()
+            })
+            .await;
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(replayed, recorded);
+        assert_eq!(player.remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_replay_mismatch_fails_clearly() {
+        let path = temp_cassette("mismatch");
+        std::fs::write(
+            &path,
+            r#"{"interactions": [{"program": "khal", "args": ["list", "today"], "stdout": "x"}]}"#,
+        )
+        .unwrap();
+
+        let player = Arc::new(Cassette::replay(&path).unwrap());
+        let err = player
+            .scope(run_command_stdout("khal", &["list", "tomorrow"]))
+            .await
+            .unwrap_err();
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(err, CommandError::CassetteMismatch { .. }));
+        assert!(err.to_string().contains("khal list tomorrow"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_replay_exit_code_and_lines() {
+        let path = temp_cassette("lines");
+        std::fs::write(
+            &path,
+            r#"{"interactions": [
+                {"program": "notmuch", "args": ["search", "tag:inbox"],
+                 "stdout": "thread:1\nthread:2\n", "stderr": "partial\n", "exit_code": 1}
+            ]}"#,
+        )
+        .unwrap();
+
+        let player = Arc::new(Cassette::replay(&path).unwrap());
+        let items = player
+            .scope(async {
+                let mut lines = CommandBuilder::new("notmuch")
+                    .args(&["search", "tag:inbox"])
+                    .run_lines()
+                    .unwrap();
+                let mut items = Vec::new();
+                while let Some(item) = lines.next_line().await {
+                    items.push(item);
+                }
+                items
+            })
+            .await;
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].as_ref().unwrap(), "thread:1");
+        assert_eq!(items[1].as_ref().unwrap(), "thread:2");
+        assert!(matches!(
+            items[2],
+            Err(CommandError::NonZeroExit { code: 1, .. })
+        ));
+    }
+}
@@ -8,17 +8,34 @@
 //! ## Features
 //!
 //! - **Async execution** via tokio::process::Command
-//! - **Structured error handling** with anyhow context
+//! - **Typed errors** ([`CommandError`]) callers can match on
 //! - **Stdout/stderr capture** with UTF-8 conversion
 //! - **Stdin piping** for interactive commands
+//! - **Streaming** stdout line by line ([`CommandBuilder::run_lines`])
 //! - **Lossy execution** for commands that use stderr for info
-//! - **Environment variable support**
+//! - **Environment variable support**, including a cleared environment with
+//!   an allowlist
 //! - **Working directory control**
+//! - **Timeouts** that kill the whole process group ([`DEFAULT_TIMEOUT`])
+//! - **Cancellation** via a [`CancellationToken`]
+//! - **Output caps**: over-limit stdout is an error, stderr gets a truncation
+//!   marker ([`DEFAULT_MAX_OUTPUT`])
+//! - **Concurrency limits** per program, with writes serialized ([`limit`])
+//! - **Record/replay** of runs for deterministic tests ([`cassette`])
+//! - **Tracing**: every run is a `command` span with redacted args, duration,
+//!   exit code and output sizes ([`redact`])
+//!
+//! Every failure is a [`CommandError`], so "the tool said no" (a
+//! [`CommandError::NonZeroExit`] with its code and output) can be told apart
+//! from "the tool is not installed" or "the tool hung" without inspecting
+//! message text. `CommandError` implements `std::error::Error`, so `?` into
+//! `anyhow::Result` keeps working.
 //!
 //! ## Example
 //!
 //! ```rust,no_run
 //! use aiandi_cli::{run_command_stdout, CommandBuilder};
+//! use std::time::Duration;
 //!
 //! #[tokio::main]
 //! async fn main() -> anyhow::Result<()> {
@@ -31,6 +48,7 @@
 //!         .args(&["pattern", "file.txt"])
 //!         .env("GREP_COLOR", "always")
 //!         .current_dir("/tmp")
+//!         .timeout(Duration::from_secs(5))
 //!         .run_stdout()
 //!         .await?;
 //!
@@ -38,13 +56,169 @@
 //! }
 //! ```
 
-use anyhow::{Context, Result};
-use std::process::Output;
-use tokio::process::Command;
+use futures_core::Stream;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::process::{ExitStatus, Output, Stdio};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, Command};
+use tracing::{field, Instrument, Span};
+
+pub mod cassette;
+pub mod limit;
+pub mod redact;
+
+pub use cassette::{Cassette, CassetteMode};
+pub use limit::{set_concurrency_limit, Access};
+pub use redact::{set_redaction_policy, RedactionPolicy};
+pub use tokio_util::sync::CancellationToken;
+
+/// Time limit applied unless a call sets its own
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Bytes kept from each of stdout and stderr unless a call sets its own
+pub const DEFAULT_MAX_OUTPUT: usize = 1024 * 1024;
+
+/// Why a command did not produce usable output
+///
+/// `command` fields hold the program and arguments joined with spaces.
+#[derive(Debug, thiserror::Error)]
+pub enum CommandError {
+    /// The program is not installed (or not on `PATH`)
+    #[error("Command not found: {program}")]
+    NotFound { program: String },
+
+    /// The program exists but could not be started
+    #[error("Failed to execute: {command}: {source}")]
+    SpawnFailed {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The program ran and exited with a code outside the accepted set
+    #[error("Command failed: {command}\nExit code: {code}\nStderr: {stderr}")]
+    NonZeroExit {
+        command: String,
+        code: i32,
+        stdout: String,
+        stderr: String,
+    },
+
+    /// The program was killed by a signal
+    #[error("Command killed by signal {signal}: {command}\nStderr: {stderr}")]
+    Signal {
+        command: String,
+        signal: i32,
+        stderr: String,
+    },
+
+    /// The command ran past its time limit; its process group was killed
+    #[error("Command timed out after {timeout:?}: {command}")]
+    Timeout { command: String, timeout: Duration },
+
+    /// The cancellation token fired; the process group was killed
+    #[error("Command cancelled: {command}")]
+    Cancelled { command: String },
+
+    /// Stdout ran past [`CommandBuilder::max_output`]; nothing is returned
+    /// rather than a cut-off document
+    #[error("Command output exceeded {limit} bytes ({omitted} bytes omitted): {command}")]
+    Truncated {
+        command: String,
+        limit: usize,
+        omitted: usize,
+    },
+
+    /// Stdout was not valid UTF-8
+    #[error("Command output is not valid UTF-8: {command}")]
+    InvalidUtf8 {
+        command: String,
+        #[source]
+        source: std::string::FromUtf8Error,
+    },
+
+    /// Talking to the running program over its pipes failed
+    #[error("I/O error running {command}: {source}")]
+    Io {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Replaying: no unused recorded run matches this command
+    #[error(
+        "No recorded run in {} matches: {command} (stdin: {})",
+        .cassette.display(),
+        .stdin.as_deref().unwrap_or("none")
+    )]
+    CassetteMismatch {
+        cassette: PathBuf,
+        command: String,
+        stdin: Option<String>,
+    },
+
+    /// The cassette could not be read, parsed or written
+    #[error("Cassette {}: {message}", .cassette.display())]
+    CassetteFailed { cassette: PathBuf, message: String },
+}
+
+impl CommandError {
+    /// Short, payload-free name of the variant (safe to log)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CommandError::NotFound { .. } => "not_found",
+            CommandError::SpawnFailed { .. } => "spawn_failed",
+            CommandError::NonZeroExit { .. } => "non_zero_exit",
+            CommandError::Signal { .. } => "signal",
+            CommandError::Timeout { .. } => "timeout",
+            CommandError::Cancelled { .. } => "cancelled",
+            CommandError::Truncated { .. } => "truncated",
+            CommandError::InvalidUtf8 { .. } => "invalid_utf8",
+            CommandError::Io { .. } => "io",
+            CommandError::CassetteMismatch { .. } => "cassette_mismatch",
+            CommandError::CassetteFailed { .. } => "cassette_failed",
+        }
+    }
+
+    fn spawn(program: &str, command: &str, source: std::io::Error) -> Self {
+        if source.kind() == std::io::ErrorKind::NotFound {
+            CommandError::NotFound {
+                program: program.to_string(),
+            }
+        } else {
+            CommandError::SpawnFailed {
+                command: command.to_string(),
+                source,
+            }
+        }
+    }
+
+    fn cassette(cassette: &Path, message: impl Into<String>) -> Self {
+        CommandError::CassetteFailed {
+            cassette: cassette.to_path_buf(),
+            message: message.into(),
+        }
+    }
+
+    fn io(command: &str, source: std::io::Error) -> Self {
+        CommandError::Io {
+            command: command.to_string(),
+            source,
+        }
+    }
+}
+
+/// Result of running a command
+pub type Result<T> = std::result::Result<T, CommandError>;
 
 /// Execute a CLI command and return its full output (stdout, stderr, status).
 ///
 /// Does NOT check exit status - returns raw Output for manual handling.
+/// Runs with [`DEFAULT_TIMEOUT`] and [`DEFAULT_MAX_OUTPUT`].
 ///
 /// # Example
 ///
@@ -60,19 +234,16 @@ use tokio::process::Command;
 /// # }
 /// ```
 pub async fn run_command(program: &str, args: &[&str]) -> Result<Output> {
-    Command::new(program)
-        .args(args)
-        .output()
-        .await
-        .with_context(|| format!("Failed to execute: {} {}", program, args.join(" ")))
+    CommandBuilder::new(program).args(args).run().await
 }
 
 /// Execute a CLI command and return stdout as a String.
 ///
 /// Returns an error if:
-/// - Command fails to execute
-/// - Exit code is non-zero
-/// - Stdout is not valid UTF-8
+/// - Command is not installed ([`CommandError::NotFound`]) or fails to execute
+/// - Exit code is non-zero ([`CommandError::NonZeroExit`])
+/// - The command times out ([`CommandError::Timeout`])
+/// - Stdout is not valid UTF-8 ([`CommandError::InvalidUtf8`])
 ///
 /// # Example
 ///
@@ -88,20 +259,7 @@ pub async fn run_command(program: &str, args: &[&str]) -> Result<Output> {
 /// # }
 /// ```
 pub async fn run_command_stdout(program: &str, args: &[&str]) -> Result<String> {
-    let output = run_command(program, args).await?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!(
-            "Command failed: {} {}\nExit code: {:?}\nStderr: {}",
-            program,
-            args.join(" "),
-            output.status.code(),
-            stderr
-        );
-    }
-
-    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    CommandBuilder::new(program).args(args).run_stdout().await
 }
 
 /// Execute a CLI command, returning stdout even if it fails.
@@ -137,8 +295,9 @@ pub async fn run_command_stdout_lossy(program: &str, args: &[&str]) -> String {
 /// Returns an error if:
 /// - Command fails to spawn
 /// - Writing to stdin fails
-/// - Exit code is non-zero
-/// - Stdout is not valid UTF-8
+/// - Exit code is non-zero ([`CommandError::NonZeroExit`])
+/// - The command times out ([`CommandError::Timeout`])
+/// - Stdout is not valid UTF-8 ([`CommandError::InvalidUtf8`])
 ///
 /// # Example
 ///
@@ -160,44 +319,20 @@ pub async fn run_command_with_stdin(
     args: &[&str],
     stdin_data: &str,
 ) -> Result<String> {
-    use tokio::io::AsyncWriteExt;
-
-    let mut child = Command::new(program)
+    CommandBuilder::new(program)
         .args(args)
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-        .with_context(|| format!("Failed to spawn: {} {}", program, args.join(" ")))?;
-
-    // Write to stdin
-    if let Some(mut stdin) = child.stdin.take() {
-        stdin
-            .write_all(stdin_data.as_bytes())
-            .await
-            .context("Failed to write to stdin")?;
-        // stdin is dropped here, closing the pipe
-    }
-
-    let output = child
-        .wait_with_output()
+        .stdin(stdin_data)
+        .run_stdout()
         .await
-        .context("Failed to wait for command")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!(
-            "Command failed: {} {}\nStderr: {}",
-            program,
-            args.join(" "),
-            stderr
-        );
-    }
-
-    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
 
-/// Builder for advanced command execution with environment variables and working directory.
+/// Builder for advanced command execution with environment variables, working
+/// directory, stdin, stdio modes, time limit, cancellation, output cap and
+/// line streaming.
+///
+/// Commands run in their own process group. On timeout, cancellation, or when
+/// the returned future is dropped mid-run, the whole group is killed, so
+/// helpers a tool spawned (pinentry, pagers, sync daemons) go with it.
 ///
 /// # Example
 ///
@@ -209,6 +344,7 @@ pub async fn run_command_with_stdin(
 ///     .args(&["build", "--release"])
 ///     .env("RUSTFLAGS", "-C target-cpu=native")
 ///     .current_dir("./my-project")
+///     .no_timeout()
 ///     .run_stdout()
 ///     .await?;
 /// # Ok(())
@@ -218,7 +354,41 @@ pub struct CommandBuilder {
     program: String,
     args: Vec<String>,
     envs: Vec<(String, String)>,
+    /// `Some(allowlist)` when the parent environment is cleared
+    env_allow: Option<Vec<String>>,
     cwd: Option<String>,
+    stdin: Option<Vec<u8>>,
+    stdin_mode: StdioMode,
+    stdout_mode: StdioMode,
+    stderr_mode: StdioMode,
+    timeout: Option<Duration>,
+    max_output: usize,
+    cancel: Option<CancellationToken>,
+    success_codes: Vec<i32>,
+    redaction: Option<Arc<dyn RedactionPolicy>>,
+    access: Access,
+    lock_key: Option<String>,
+}
+
+/// Where one of the child's standard streams is connected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StdioMode {
+    /// Captured by us (stdout/stderr) or fed from [`CommandBuilder::stdin`]
+    Piped,
+    /// Shared with the current process
+    Inherit,
+    /// Connected to /dev/null
+    Null,
+}
+
+impl From<StdioMode> for Stdio {
+    fn from(mode: StdioMode) -> Self {
+        match mode {
+            StdioMode::Piped => Stdio::piped(),
+            StdioMode::Inherit => Stdio::inherit(),
+            StdioMode::Null => Stdio::null(),
+        }
+    }
 }
 
 impl CommandBuilder {
@@ -228,7 +398,19 @@ impl CommandBuilder {
             program: program.into(),
             args: Vec::new(),
             envs: Vec::new(),
+            env_allow: None,
             cwd: None,
+            stdin: None,
+            stdin_mode: StdioMode::Null,
+            stdout_mode: StdioMode::Piped,
+            stderr_mode: StdioMode::Piped,
+            timeout: Some(DEFAULT_TIMEOUT),
+            max_output: DEFAULT_MAX_OUTPUT,
+            cancel: None,
+            success_codes: vec![0],
+            redaction: None,
+            access: Access::Read,
+            lock_key: None,
         }
     }
 
@@ -250,36 +432,176 @@ impl CommandBuilder {
         self
     }
 
+    /// Start from an empty environment, passing through only `allow`.
+    ///
+    /// Variables in `allow` that are unset in the current process are
+    /// skipped. Variables set with [`env`](Self::env) are always passed.
+    pub fn env_clear(mut self, allow: &[&str]) -> Self {
+        self.env_allow = Some(allow.iter().map(|s| s.to_string()).collect());
+        self
+    }
+
     /// Set the current working directory.
     pub fn current_dir(mut self, dir: impl Into<String>) -> Self {
         self.cwd = Some(dir.into());
         self
     }
 
-    /// Execute and return stdout as String (fails on non-zero exit).
-    pub async fn run_stdout(self) -> Result<String> {
-        let program = self.program.clone();
-        let args = self.args.clone();
-        let output = self.run().await?;
+    /// Write `data` to the command's stdin, then close it.
+    pub fn stdin(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.stdin = Some(data.into());
+        self.stdin_mode = StdioMode::Piped;
+        self
+    }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!(
-                "Command failed: {} {}\nExit code: {:?}\nStderr: {}",
-                program,
-                args.join(" "),
-                output.status.code(),
-                stderr
-            );
+    /// Connect stdin (default: [`StdioMode::Null`]; [`stdin`](Self::stdin) sets `Piped`).
+    pub fn stdin_mode(mut self, mode: StdioMode) -> Self {
+        if mode != StdioMode::Piped {
+            self.stdin = None;
         }
+        self.stdin_mode = mode;
+        self
+    }
 
-        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    /// Connect stdout (default: [`StdioMode::Piped`]). Not captured otherwise.
+    pub fn stdout_mode(mut self, mode: StdioMode) -> Self {
+        self.stdout_mode = mode;
+        self
     }
 
-    /// Execute and return full Output (does not check exit status).
-    pub async fn run(self) -> Result<Output> {
+    /// Connect stderr (default: [`StdioMode::Piped`]). Not captured otherwise.
+    pub fn stderr_mode(mut self, mode: StdioMode) -> Self {
+        self.stderr_mode = mode;
+        self
+    }
+
+    /// Kill the command if it runs longer than `timeout` (default: [`DEFAULT_TIMEOUT`]).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Let the command run as long as it needs.
+    pub fn no_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Keep at most `bytes` of stdout and of stderr (default: [`DEFAULT_MAX_OUTPUT`]).
+    ///
+    /// Anything beyond is read and discarded. Over-limit stdout fails the
+    /// run with [`CommandError::Truncated`]; over-limit stderr gets a
+    /// `[output truncated: N bytes omitted]` line appended.
+    pub fn max_output(mut self, bytes: usize) -> Self {
+        self.max_output = bytes;
+        self
+    }
+
+    /// Kill the command when `token` is cancelled.
+    pub fn cancel_on(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Exit codes `run_stdout` treats as success (default: `[0]`).
+    ///
+    /// For tools like `grep` that exit 1 on "no matches".
+    pub fn success_codes(mut self, codes: &[i32]) -> Self {
+        self.success_codes = codes.to_vec();
+        self
+    }
+
+    /// Whether this command changes the tool's state (default: [`Access::Read`]).
+    ///
+    /// Writes wait for every other run of the same [`lock_key`](Self::lock_key)
+    /// and run alone; reads run side by side up to the key's limit.
+    pub fn access(mut self, access: Access) -> Self {
+        self.access = access;
+        self
+    }
+
+    /// Concurrency limit this command counts against (default: the
+    /// program's file name).
+    ///
+    /// Use one key for programs sharing state, or one per data dir.
+    pub fn lock_key(mut self, key: impl Into<String>) -> Self {
+        self.lock_key = Some(key.into());
+        self
+    }
+
+    /// How this command's arguments appear in logs (default: the
+    /// process-wide policy, see [`set_redaction_policy`]).
+    pub fn redaction(mut self, policy: Arc<dyn RedactionPolicy>) -> Self {
+        self.redaction = Some(policy);
+        self
+    }
+
+    /// The `command` span this run is traced under
+    fn span(&self) -> Span {
+        let policy = self
+            .redaction
+            .clone()
+            .unwrap_or_else(redact::redaction_policy);
+        let args = policy.redact_args(&self.program, &self.args).join(" ");
+
+        tracing::info_span!(
+            "command",
+            program = %self.program,
+            args = %args,
+            stdin_bytes = self.stdin.as_ref().map_or(0, Vec::len),
+            exit_code = field::Empty,
+            duration_ms = field::Empty,
+            stdout_bytes = field::Empty,
+            stderr_bytes = field::Empty,
+        )
+    }
+
+    fn command_line(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn timed_out(&self, command_line: &str) -> CommandError {
+        CommandError::Timeout {
+            command: command_line.to_string(),
+            timeout: self.timeout.unwrap_or_default(),
+        }
+    }
+
+    fn cancelled_error(command_line: &str) -> CommandError {
+        CommandError::Cancelled {
+            command: command_line.to_string(),
+        }
+    }
+
+    /// Spawn the child in its own process group and start feeding stdin
+    ///
+    /// The returned future writes stdin; drive it alongside the output.
+    fn spawn(
+        &mut self,
+        command_line: &str,
+    ) -> Result<(
+        Child,
+        ProcessGroupGuard,
+        impl std::future::Future<Output = std::io::Result<()>>,
+    )> {
         let mut cmd = Command::new(&self.program);
-        cmd.args(&self.args);
+        cmd.args(&self.args)
+            .stdin(self.stdin_mode)
+            .stdout(self.stdout_mode)
+            .stderr(self.stderr_mode)
+            .kill_on_drop(true);
+
+        if let Some(allow) = &self.env_allow {
+            cmd.env_clear();
+            for key in allow {
+                if let Some(value) = std::env::var_os(key) {
+                    cmd.env(key, value);
+                }
+            }
+        }
 
         for (key, value) in &self.envs {
             cmd.env(key, value);
@@ -289,9 +611,511 @@ impl CommandBuilder {
             cmd.current_dir(cwd);
         }
 
-        cmd.output()
-            .await
-            .with_context(|| format!("Failed to execute: {} {}", self.program, self.args.join(" ")))
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| CommandError::spawn(&self.program, command_line, e))?;
+        let group = ProcessGroupGuard::new(child.id());
+
+        let stdin = child.stdin.take();
+        let stdin_data = self.stdin.take().unwrap_or_default();
+        let write_stdin = async move {
+            if let Some(mut pipe) = stdin {
+                pipe.write_all(&stdin_data).await?;
+                // pipe is dropped here, closing stdin
+            }
+            Ok(())
+        };
+
+        Ok((child, group, write_stdin))
+    }
+
+    /// Execute and return stdout as String (fails unless the exit code is
+    /// one of [`success_codes`](Self::success_codes)).
+    pub async fn run_stdout(self) -> Result<String> {
+        let command_line = self.command_line();
+        let success_codes = self.success_codes.clone();
+        let output = self.run().await?;
+
+        check_status(&command_line, &output, &success_codes)?;
+
+        String::from_utf8(output.stdout).map_err(|source| CommandError::InvalidUtf8 {
+            command: command_line,
+            source,
+        })
+    }
+
+    /// Execute and return full Output (does not check exit status).
+    ///
+    /// Streams not set to [`StdioMode::Piped`] come back empty. Inside an
+    /// active [`Cassette`], the run is recorded or replayed.
+    pub async fn run(self) -> Result<Output> {
+        let span = self.span();
+        let started = Instant::now();
+        let result = self.dispatch().instrument(span.clone()).await;
+
+        let (exit_code, stdout_bytes, stderr_bytes) = match &result {
+            Ok(output) => (
+                output.status.code(),
+                output.stdout.len(),
+                output.stderr.len(),
+            ),
+            Err(_) => (None, 0, 0),
+        };
+        trace_outcome(
+            &span,
+            started,
+            exit_code,
+            stdout_bytes,
+            stderr_bytes,
+            result.as_ref().err(),
+        );
+        result
+    }
+
+    /// Run for real, or through the active cassette
+    async fn dispatch(self) -> Result<Output> {
+        // Boxed so callers joining many commands don't carry each run's
+        // state on the stack
+        let Some(cassette) = Cassette::active()? else {
+            return Box::pin(self.execute()).await;
+        };
+
+        let request = self.request();
+        match cassette.mode() {
+            CassetteMode::Replay => cassette.answer(&request),
+            CassetteMode::Record => {
+                let output = Box::pin(self.execute()).await?;
+                cassette.store(request, &output)?;
+                Ok(output)
+            }
+        }
+    }
+
+    /// The program's file name, which doesn't depend on where it's installed
+    fn program_name(&self) -> String {
+        Path::new(&self.program)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.program.clone())
+    }
+
+    fn limit_key(&self) -> String {
+        self.lock_key.clone().unwrap_or_else(|| self.program_name())
+    }
+
+    /// How this run appears in a cassette
+    fn request(&self) -> cassette::Request {
+        cassette::Request {
+            program: self.program_name(),
+            args: self.args.clone(),
+            stdin: self
+                .stdin
+                .as_ref()
+                .map(|data| String::from_utf8_lossy(data).into_owned()),
+            env: self.envs.iter().cloned().collect(),
+        }
+    }
+
+    async fn execute(mut self) -> Result<Output> {
+        let command_line = self.command_line();
+        let deadline = deadline(self.timeout);
+        let cancelled = cancelled(self.cancel.clone());
+        tokio::pin!(deadline, cancelled);
+
+        let key = self.limit_key();
+        let _permit = tokio::select! {
+            permit = limit::acquire(&key, self.access) => permit,
+            _ = &mut deadline => return Err(self.timed_out(&command_line)),
+            _ = &mut cancelled => return Err(Self::cancelled_error(&command_line)),
+        };
+        let (mut child, mut group, write_stdin) = self.spawn(&command_line)?;
+
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let max_output = self.max_output;
+
+        let finished = tokio::select! {
+            done = async {
+                tokio::join!(
+                    write_stdin,
+                    read_capped(stdout, max_output),
+                    read_capped(stderr, max_output),
+                    child.wait(),
+                )
+            } => Ok(done),
+            _ = &mut deadline => Err(self.timed_out(&command_line)),
+            _ = &mut cancelled => Err(Self::cancelled_error(&command_line)),
+        };
+
+        match finished {
+            Ok((written, stdout, stderr, status)) => {
+                group.disarm();
+                let io = |e| CommandError::io(&command_line, e);
+                let status = status.map_err(io)?;
+                written.map_err(io)?;
+                let (stdout, omitted) = stdout.map_err(io)?;
+                if omitted > 0 {
+                    return Err(CommandError::Truncated {
+                        command: command_line,
+                        limit: max_output,
+                        omitted,
+                    });
+                }
+                let (stderr, omitted) = stderr.map_err(io)?;
+                Ok(Output {
+                    status,
+                    stdout,
+                    stderr: with_marker(stderr, omitted),
+                })
+            }
+            Err(e) => {
+                group.kill();
+                let _ = child.kill().await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Execute and yield stdout line by line as it is produced.
+    ///
+    /// Stdout is always piped; [`max_output`](Self::max_output) applies to
+    /// stderr only. After the last line, a failed exit (per
+    /// [`success_codes`](Self::success_codes)), timeout or cancellation
+    /// arrives as a final `Err` item; its `NonZeroExit::stdout` is empty since
+    /// the lines were already yielded. Failing to start (including a timeout
+    /// while waiting on [`limit`] permits) is the only item. Dropping the
+    /// stream kills the command.
+    ///
+    /// ```rust,no_run
+    /// # use aiandi_cli::CommandBuilder;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let mut threads = CommandBuilder::new("notmuch")
+    ///     .args(&["search", "--output=threads", "tag:inbox"])
+    ///     .run_lines()?;
+    /// while let Some(line) = threads.next_line().await {
+    ///     println!("{}", line?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run_lines(mut self) -> Result<LineStream> {
+        self.stdout_mode = StdioMode::Piped;
+        let command_line = self.command_line();
+        let (tx, rx) = tokio::sync::mpsc::channel(LINE_BUFFER);
+
+        // Cassette runs are buffered whole, then handed out line by line
+        // (the task-local scope does not follow tokio::spawn, so re-enter it)
+        if let Some(cassette) = Cassette::active()? {
+            tokio::spawn(cassette.scope(async move {
+                let success_codes = self.success_codes.clone();
+                let output = match self.run().await {
+                    Ok(output) => output,
+                    Err(e) => {
+                        let _ = tx.send(Err(e)).await;
+                        return;
+                    }
+                };
+                for line in String::from_utf8_lossy(&output.stdout).lines() {
+                    if tx.send(Ok(line.to_string())).await.is_err() {
+                        return;
+                    }
+                }
+                let status = Output {
+                    stdout: Vec::new(),
+                    ..output
+                };
+                if let Err(e) = check_status(&command_line, &status, &success_codes) {
+                    let _ = tx.send(Err(e)).await;
+                }
+            }));
+            return Ok(LineStream { rx });
+        }
+
+        let span = self.span();
+        let started = Instant::now();
+
+        // Spawned from the task, since it may first wait for its permits
+        let stream = async move {
+            let deadline = deadline(self.timeout);
+            let cancelled = cancelled(self.cancel.clone());
+            tokio::pin!(deadline, cancelled);
+
+            let key = self.limit_key();
+            let spawned = tokio::select! {
+                permit = limit::acquire(&key, self.access) => {
+                    self.spawn(&command_line).map(|spawned| (permit, spawned))
+                }
+                _ = &mut deadline => Err(self.timed_out(&command_line)),
+                _ = &mut cancelled => Err(Self::cancelled_error(&command_line)),
+                _ = tx.closed() => return,
+            };
+            let (_permit, (mut child, mut group, write_stdin)) = match spawned {
+                Ok(spawned) => spawned,
+                Err(e) => {
+                    trace_outcome(&Span::current(), started, None, 0, 0, Some(&e));
+                    let _ = tx.send(Err(e)).await;
+                    return;
+                }
+            };
+
+            let stdout = child.stdout.take().expect("stdout is piped");
+            let stderr = tokio::spawn(read_capped(child.stderr.take(), self.max_output));
+            tokio::spawn(async move {
+                let _ = write_stdin.await;
+            });
+
+            let mut lines = BufReader::new(stdout).lines();
+            let mut stdout_bytes = 0;
+            let mut stderr_bytes = 0;
+            let mut exit_code = None;
+
+            let stopped = loop {
+                tokio::select! {
+                    line = lines.next_line() => match line {
+                        Ok(Some(line)) => {
+                            stdout_bytes += line.len() + 1;
+                            if tx.send(Ok(line)).await.is_err() {
+                                // Receiver dropped; the guard kills the group
+                                return;
+                            }
+                        }
+                        Ok(None) => break None,
+                        Err(e) => break Some(CommandError::io(&command_line, e)),
+                    },
+                    _ = &mut deadline => break Some(self.timed_out(&command_line)),
+                    _ = &mut cancelled => break Some(Self::cancelled_error(&command_line)),
+                    _ = tx.closed() => return,
+                }
+            };
+
+            let result = match stopped {
+                Some(e) => Err(e),
+                None => tokio::select! {
+                    status = child.wait() => match status {
+                        Ok(status) => {
+                            group.disarm();
+                            let stderr = match stderr.await {
+                                Ok(Ok((stderr, omitted))) => with_marker(stderr, omitted),
+                                _ => Vec::new(),
+                            };
+                            exit_code = status.code();
+                            stderr_bytes = stderr.len();
+                            let output = Output { status, stdout: Vec::new(), stderr };
+                            check_status(&command_line, &output, &self.success_codes)
+                        }
+                        Err(e) => Err(CommandError::io(&command_line, e)),
+                    },
+                    _ = &mut deadline => Err(self.timed_out(&command_line)),
+                    _ = &mut cancelled => Err(Self::cancelled_error(&command_line)),
+                },
+            };
+
+            trace_outcome(
+                &Span::current(),
+                started,
+                exit_code,
+                stdout_bytes,
+                stderr_bytes,
+                result.as_ref().err(),
+            );
+
+            if let Err(e) = result {
+                group.kill();
+                let _ = child.kill().await;
+                let _ = tx.send(Err(e)).await;
+            }
+        };
+        tokio::spawn(stream.instrument(span));
+
+        Ok(LineStream { rx })
+    }
+}
+
+/// Lines buffered between a running command and a slow consumer
+const LINE_BUFFER: usize = 64;
+
+/// Stdout of a running command, one line at a time
+///
+/// Returned by [`CommandBuilder::run_lines`]. Use [`next_line`](Self::next_line)
+/// directly or any `Stream` combinator.
+pub struct LineStream {
+    rx: tokio::sync::mpsc::Receiver<Result<String>>,
+}
+
+impl LineStream {
+    /// The next line (without its newline), or `None` once the command is done.
+    pub async fn next_line(&mut self) -> Option<Result<String>> {
+        self.rx.recv().await
+    }
+}
+
+impl Stream for LineStream {
+    type Item = Result<String>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// Resolves when `timeout` elapses; never if there is none
+async fn deadline(timeout: Option<Duration>) {
+    match timeout {
+        Some(timeout) => tokio::time::sleep(timeout).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Resolves when `token` is cancelled; never if there is none
+async fn cancelled(token: Option<CancellationToken>) {
+    match token {
+        Some(token) => token.cancelled().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Record a finished run on its span and log one line for it
+///
+/// Errors are logged by kind only: their messages carry unredacted args.
+fn trace_outcome(
+    span: &Span,
+    started: Instant,
+    exit_code: Option<i32>,
+    stdout_bytes: usize,
+    stderr_bytes: usize,
+    error: Option<&CommandError>,
+) {
+    let duration_ms = started.elapsed().as_millis() as u64;
+    span.record("duration_ms", duration_ms);
+    span.record("stdout_bytes", stdout_bytes);
+    span.record("stderr_bytes", stderr_bytes);
+    if let Some(code) = exit_code {
+        span.record("exit_code", code);
+    }
+
+    span.in_scope(|| match error {
+        None => tracing::info!(duration_ms, ?exit_code, "command finished"),
+        Some(e) => tracing::warn!(duration_ms, error = e.kind(), "command failed"),
+    });
+}
+
+/// Map an exit status outside `success_codes` to an error
+fn check_status(command_line: &str, output: &Output, success_codes: &[i32]) -> Result<()> {
+    let stderr = || String::from_utf8_lossy(&output.stderr).into_owned();
+
+    match output.status.code() {
+        Some(code) if success_codes.contains(&code) => Ok(()),
+        Some(code) => Err(CommandError::NonZeroExit {
+            command: command_line.to_string(),
+            code,
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: stderr(),
+        }),
+        None => Err(CommandError::Signal {
+            command: command_line.to_string(),
+            signal: exit_signal(output.status).unwrap_or_default(),
+            stderr: stderr(),
+        }),
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn exit_signal(status: ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+pub(crate) fn exit_signal(_status: ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Read everything from `reader`, keeping at most `limit` bytes
+///
+/// Returns the kept bytes and how many were omitted. A stream that was not
+/// piped (`None`) reads as empty. The rest is drained so the writer never
+/// blocks on a full pipe.
+async fn read_capped<R: AsyncRead + Unpin>(
+    reader: Option<R>,
+    limit: usize,
+) -> std::io::Result<(Vec<u8>, usize)> {
+    let mut kept = Vec::new();
+    let Some(mut reader) = reader else {
+        return Ok((kept, 0));
+    };
+    let mut omitted = 0usize;
+    // On the heap: this future is embedded in every caller's future
+    let mut buf = vec![0u8; 8192];
+
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        let take = n.min(limit.saturating_sub(kept.len()));
+        kept.extend_from_slice(&buf[..take]);
+        omitted += n - take;
+    }
+    Ok((kept, omitted))
+}
+
+/// Append a truncation marker to capped output if anything was omitted
+fn with_marker(mut kept: Vec<u8>, mut omitted: usize) -> Vec<u8> {
+    if omitted > 0 {
+        // Don't leave half a character in front of the marker
+        if let Err(e) = std::str::from_utf8(&kept) {
+            if e.error_len().is_none() {
+                omitted += kept.len() - e.valid_up_to();
+                kept.truncate(e.valid_up_to());
+            }
+        }
+        kept.extend_from_slice(
+            format!("\n[output truncated: {} bytes omitted]\n", omitted).as_bytes(),
+        );
+    }
+    kept
+}
+
+/// Kills a child's process group on drop unless disarmed
+///
+/// Covers the paths where the run future is dropped mid-command (e.g. the
+/// caller's own request was cancelled), not just timeouts.
+struct ProcessGroupGuard {
+    pgid: Option<u32>,
+}
+
+impl ProcessGroupGuard {
+    fn new(pgid: Option<u32>) -> Self {
+        Self { pgid }
+    }
+
+    /// The command finished normally; leave its group alone
+    fn disarm(&mut self) {
+        self.pgid = None;
+    }
+
+    fn kill(&mut self) {
+        if let Some(pgid) = self.pgid.take() {
+            #[cfg(unix)]
+            if let Ok(pgid) = libc::pid_t::try_from(pgid) {
+                // SAFETY: kill(2) has no memory-safety preconditions; a
+                // negative pid addresses the process group we created.
+                unsafe {
+                    libc::kill(-pgid, libc::SIGKILL);
+                }
+            }
+            #[cfg(not(unix))]
+            let _ = pgid;
+        }
+    }
+}
+
+impl Drop for ProcessGroupGuard {
+    fn drop(&mut self) {
+        self.kill();
     }
 }
 
@@ -318,7 +1142,54 @@ mod tests {
     async fn test_run_command_stdout_failure() {
         // Try to run a command that doesn't exist
         let result = run_command_stdout("nonexistent_command_xyz", &[]).await;
-        assert!(result.is_err());
+        assert!(matches!(result, Err(CommandError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_non_zero_exit() {
+        let err = run_command_stdout("sh", &["-c", "echo out; echo oops >&2; exit 3"])
+            .await
+            .unwrap_err();
+
+        match err {
+            CommandError::NonZeroExit {
+                code,
+                stdout,
+                stderr,
+                ..
+            } => {
+                assert_eq!(code, 3);
+                assert_eq!(stdout, "out\n");
+                assert_eq!(stderr, "oops\n");
+            }
+            other => panic!("expected NonZeroExit, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_success_codes() {
+        let output = CommandBuilder::new("sh")
+            .args(&["-c", "echo no matches; exit 1"])
+            .success_codes(&[0, 1])
+            .run_stdout()
+            .await
+            .unwrap();
+        assert_eq!(output, "no matches\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_signal() {
+        let err = run_command_stdout("sh", &["-c", "kill -TERM $$"])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CommandError::Signal { signal: 15, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_utf8() {
+        let err = run_command_stdout("printf", &["\\377"]).await.unwrap_err();
+        assert!(matches!(err, CommandError::InvalidUtf8 { .. }));
     }
 
     #[tokio::test]
@@ -348,4 +1219,210 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap().trim(), "environment_value");
     }
+
+    #[tokio::test]
+    async fn test_large_stdin_does_not_deadlock() {
+        let input = "x".repeat(1024 * 1024);
+        let result = run_command_with_stdin("cat", &[], &input).await.unwrap();
+        assert_eq!(result.len(), input.len());
+    }
+
+    #[tokio::test]
+    async fn test_timeout_is_typed() {
+        let start = std::time::Instant::now();
+        let err = CommandBuilder::new("sleep")
+            .arg("10")
+            .timeout(Duration::from_millis(200))
+            .run_stdout()
+            .await
+            .unwrap_err();
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(matches!(err, CommandError::Timeout { .. }));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_timeout_kills_process_group() {
+        let pid_file = std::env::temp_dir().join(format!("aiandi-cli-pg-{}", std::process::id()));
+        let script = format!("sleep 30 & echo $! > {}; wait", pid_file.display());
+
+        let result = CommandBuilder::new("sh")
+            .args(&["-c", &script])
+            .timeout(Duration::from_millis(300))
+            .run()
+            .await;
+        assert!(result.is_err());
+
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        std::fs::remove_file(&pid_file).ok();
+
+        // Gone, or a zombie waiting for init to reap it
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let stat =
+            std::fs::read_to_string(format!("/proc/{}/stat", pid.trim())).unwrap_or_default();
+        assert!(
+            stat.is_empty() || stat.contains(") Z "),
+            "background sleep survived: {}",
+            stat
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancellation() {
+        let token = CancellationToken::new();
+        let trigger = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            trigger.cancel();
+        });
+
+        let err = CommandBuilder::new("sleep")
+            .arg("10")
+            .cancel_on(token)
+            .run()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, CommandError::Cancelled { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_max_output_fails_on_long_stdout() {
+        // A JSON array of 1000 zeros: 2001 bytes
+        let err = CommandBuilder::new("sh")
+            .args(&[
+                "-c",
+                "printf '['; for i in $(seq 999); do printf '0,'; done; printf '0]'",
+            ])
+            .max_output(100)
+            .run_stdout()
+            .await
+            .unwrap_err();
+
+        match err {
+            CommandError::Truncated { limit, omitted, .. } => {
+                assert_eq!(limit, 100);
+                assert_eq!(omitted, 1901);
+            }
+            other => panic!("expected Truncated, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_output_marks_long_stderr() {
+        let output = CommandBuilder::new("sh")
+            .args(&["-c", "head -c 10000 /dev/zero | tr '\\0' a >&2"])
+            .max_output(100)
+            .run()
+            .await
+            .unwrap();
+
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.starts_with(&"a".repeat(100)));
+        assert!(stderr.ends_with("\n[output truncated: 9900 bytes omitted]\n"));
+    }
+
+    #[tokio::test]
+    async fn test_env_clear_with_allowlist() {
+        let output = CommandBuilder::new("sh")
+            .args(&["-c", "echo \"${HOME:-unset} ${AIANDI_CLI_EXTRA}\""])
+            .env_clear(&["PATH"])
+            .env("AIANDI_CLI_EXTRA", "kept")
+            .run_stdout()
+            .await
+            .unwrap();
+        assert_eq!(output.trim(), "unset kept");
+    }
+
+    #[tokio::test]
+    async fn test_stdout_null_is_not_captured() {
+        let output = CommandBuilder::new("echo")
+            .arg("hidden")
+            .stdout_mode(StdioMode::Null)
+            .run()
+            .await
+            .unwrap();
+        assert!(output.status.success());
+        assert!(output.stdout.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_lines() {
+        let mut lines = CommandBuilder::new("sh")
+            .args(&["-c", "cat; echo three"])
+            .stdin("one\ntwo\n")
+            .run_lines()
+            .unwrap();
+
+        let mut seen = Vec::new();
+        while let Some(line) = lines.next_line().await {
+            seen.push(line.unwrap());
+        }
+        assert_eq!(seen, vec!["one", "two", "three"]);
+    }
+
+    #[tokio::test]
+    async fn test_run_lines_reports_failure_last() {
+        let mut lines = CommandBuilder::new("sh")
+            .args(&["-c", "echo partial; echo broken >&2; exit 2"])
+            .run_lines()
+            .unwrap();
+
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), "partial");
+        match lines.next_line().await {
+            Some(Err(CommandError::NonZeroExit { code, stderr, .. })) => {
+                assert_eq!(code, 2);
+                assert_eq!(stderr, "broken\n");
+            }
+            other => panic!("expected NonZeroExit, got {:?}", other),
+        }
+        assert!(lines.next_line().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_run_lines_timeout() {
+        let mut lines = CommandBuilder::new("sh")
+            .args(&["-c", "echo first; sleep 10"])
+            .timeout(Duration::from_millis(200))
+            .run_lines()
+            .unwrap();
+
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), "first");
+        assert!(matches!(
+            lines.next_line().await,
+            Some(Err(CommandError::Timeout { .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_writes_do_not_overlap() {
+        let marker = std::env::temp_dir().join(format!("aiandi-cli-write-{}", std::process::id()));
+        let script = format!(
+            "[ -e {0} ] && echo overlap; touch {0}; sleep 0.1; rm {0}",
+            marker.display()
+        );
+        let write = || {
+            CommandBuilder::new("sh")
+                .args(&["-c", &script])
+                .access(Access::Write)
+                .lock_key("test-writes")
+                .run_stdout()
+        };
+
+        let (a, b, c) = tokio::join!(write(), write(), write());
+        assert_eq!(a.unwrap() + &b.unwrap() + &c.unwrap(), "");
+    }
+
+    #[tokio::test]
+    async fn test_timeout_covers_waiting_for_write() {
+        let held = limit::acquire("test-held", Access::Write).await;
+        let result = CommandBuilder::new("true")
+            .lock_key("test-held")
+            .timeout(Duration::from_millis(100))
+            .run()
+            .await;
+        assert!(matches!(result, Err(CommandError::Timeout { .. })));
+        drop(held);
+    }
 }
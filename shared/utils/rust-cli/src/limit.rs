@@ -0,0 +1,119 @@
+//! Per-program concurrency limits
+//!
+//! MCP clients call tools in parallel, and several wrapped tools don't
+//! tolerate concurrent writers: `notmuch` takes an exclusive database lock,
+//! `task` rewrites its data files, `khal` and `khard` write into a vdir.
+//! Every real run therefore holds permits on a semaphore keyed by program
+//! name (see [`CommandBuilder::lock_key`](crate::CommandBuilder::lock_key))
+//! for as long as the process runs:
+//!
+//! - [`Access::Read`] (the default) takes one permit, so up to the limit run
+//!   side by side
+//! - [`Access::Write`] takes all of them, so it runs alone
+//!
+//! Waiting counts toward the command's timeout and can be cancelled. The
+//! semaphore is fair, so a queued write is not starved by a stream of reads.
+//! Runs replayed from a cassette take no permits.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Concurrent reads per key unless [`set_concurrency_limit`] says otherwise
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// What a command does to the state of the tool it runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Access {
+    /// Only reads; runs alongside other reads of the same key
+    #[default]
+    Read,
+    /// Changes state; runs alone for its key
+    Write,
+}
+
+struct Limiter {
+    limit: usize,
+    semaphore: Arc<Semaphore>,
+}
+
+impl Limiter {
+    fn new(limit: usize) -> Self {
+        let limit = limit.max(1);
+        Self {
+            limit,
+            semaphore: Arc::new(Semaphore::new(limit)),
+        }
+    }
+}
+
+fn limiters() -> &'static Mutex<HashMap<String, Limiter>> {
+    static LIMITERS: OnceLock<Mutex<HashMap<String, Limiter>>> = OnceLock::new();
+    LIMITERS.get_or_init(Default::default)
+}
+
+/// Allow at most `limit` concurrent reads for `key` (0 is treated as 1).
+///
+/// A limit of 1 serializes reads as well as writes. Meant to be called at
+/// startup: runs already holding permits finish under the old limit.
+pub fn set_concurrency_limit(key: &str, limit: usize) {
+    limiters()
+        .lock()
+        .unwrap()
+        .insert(key.to_string(), Limiter::new(limit));
+}
+
+/// Wait until a run of `key` with `access` may start
+pub(crate) async fn acquire(key: &str, access: Access) -> OwnedSemaphorePermit {
+    let (semaphore, limit) = {
+        let mut limiters = limiters().lock().unwrap();
+        let limiter = limiters
+            .entry(key.to_string())
+            .or_insert_with(|| Limiter::new(DEFAULT_CONCURRENCY));
+        (Arc::clone(&limiter.semaphore), limiter.limit)
+    };
+
+    let permits = match access {
+        Access::Read => 1,
+        Access::Write => limit as u32,
+    };
+    semaphore
+        .acquire_many_owned(permits)
+        .await
+        .expect("limiter semaphores are never closed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn blocked(key: &str, access: Access) -> bool {
+        tokio::time::timeout(Duration::from_millis(50), acquire(key, access))
+            .await
+            .is_err()
+    }
+
+    #[tokio::test]
+    async fn test_reads_share_writes_exclude() {
+        let key = "limit-test-share";
+        let first = acquire(key, Access::Read).await;
+        let second = acquire(key, Access::Read).await;
+        assert!(blocked(key, Access::Write).await);
+
+        drop((first, second));
+        let write = acquire(key, Access::Write).await;
+        assert!(blocked(key, Access::Read).await);
+        drop(write);
+        assert!(!blocked(key, Access::Read).await);
+    }
+
+    #[tokio::test]
+    async fn test_limit_caps_reads() {
+        let key = "limit-test-cap";
+        set_concurrency_limit(key, 2);
+        let _a = acquire(key, Access::Read).await;
+        let _b = acquire(key, Access::Read).await;
+        assert!(blocked(key, Access::Read).await);
+    }
+}
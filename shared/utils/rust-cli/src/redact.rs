@@ -0,0 +1,224 @@
+//! Redaction of command arguments before they reach the logs
+//!
+//! Every run is traced with its program and arguments (see
+//! [`CommandBuilder::run`](crate::CommandBuilder::run)). Arguments pass through
+//! a [`RedactionPolicy`] first, so subjects and addresses given to `himalaya`
+//! or `notmuch` don't land in the server log verbatim. Stdin is never logged,
+//! only its size.
+//!
+//! The process-wide policy is [`DefaultRedaction`] unless replaced with
+//! [`set_redaction_policy`]; a single command can override it with
+//! [`CommandBuilder::redaction`](crate::CommandBuilder::redaction).
+
+use std::sync::{Arc, RwLock};
+
+/// Replacement for a value that was hidden
+pub const REDACTED: &str = "[redacted]";
+
+/// Decides how a command's arguments appear in logs
+pub trait RedactionPolicy: Send + Sync {
+    /// Arguments as they should be logged (same length not required)
+    fn redact_args(&self, program: &str, args: &[String]) -> Vec<String>;
+}
+
+impl<F> RedactionPolicy for F
+where
+    F: Fn(&str, &[String]) -> Vec<String> + Send + Sync,
+{
+    fn redact_args(&self, program: &str, args: &[String]) -> Vec<String> {
+        self(program, args)
+    }
+}
+
+/// Hides values of sensitive flags and the local part of email addresses
+///
+/// - `--subject X`, `--password=X` and the like become `--subject [redacted]`
+/// - `key=value` / `key:value` with a secret-looking key are hidden
+/// - `from:jane@example.com` becomes `from:***@example.com`
+#[derive(Debug, Clone)]
+pub struct DefaultRedaction {
+    /// Flags whose value (next argument, or after `=`) is hidden
+    pub sensitive_flags: Vec<String>,
+}
+
+impl Default for DefaultRedaction {
+    fn default() -> Self {
+        Self {
+            sensitive_flags: [
+                "--subject",
+                "--body",
+                "--password",
+                "--passwd",
+                "--token",
+                "--secret",
+                "--api-key",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        }
+    }
+}
+
+/// Keys in `key=value` / `key:value` whose value is hidden
+const SECRET_KEYS: &[&str] = &["password", "passwd", "token", "secret", "api_key", "apikey"];
+
+impl RedactionPolicy for DefaultRedaction {
+    fn redact_args(&self, _program: &str, args: &[String]) -> Vec<String> {
+        let mut out = Vec::with_capacity(args.len());
+        let mut hide_next = false;
+
+        for arg in args {
+            if hide_next {
+                out.push(REDACTED.to_string());
+                hide_next = false;
+                continue;
+            }
+
+            if self.sensitive_flags.iter().any(|f| f == arg) {
+                out.push(arg.clone());
+                hide_next = true;
+                continue;
+            }
+
+            if let Some((flag, _)) = arg.split_once('=') {
+                if self.sensitive_flags.iter().any(|f| f == flag) {
+                    out.push(format!("{}={}", flag, REDACTED));
+                    continue;
+                }
+            }
+
+            out.push(redact_secrets(&redact_emails(arg)));
+        }
+
+        out
+    }
+}
+
+/// Hides every argument, keeping only how many there were
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RedactAll;
+
+impl RedactionPolicy for RedactAll {
+    fn redact_args(&self, _program: &str, args: &[String]) -> Vec<String> {
+        vec![format!("[{} args redacted]", args.len())]
+    }
+}
+
+/// Replace the local part of anything that looks like an email address
+pub fn redact_emails(text: &str) -> String {
+    text.split(' ')
+        .map(|word| match word.find('@') {
+            Some(at) => {
+                // The address starts after the last delimiter before `@`
+                let start = word[..at]
+                    .rfind([':', '<', '(', '"', '\'', ',', '='])
+                    .map(|i| i + 1)
+                    .unwrap_or(0);
+                let domain = &word[at + 1..];
+                if at > start && domain.contains('.') {
+                    format!("{}***{}", &word[..start], &word[at..])
+                } else {
+                    word.to_string()
+                }
+            }
+            None => word.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Hide values of `key=value` / `key:value` pairs with a secret-looking key
+fn redact_secrets(arg: &str) -> String {
+    for sep in ['=', ':'] {
+        if let Some((key, _)) = arg.split_once(sep) {
+            let lower = key.trim_start_matches('-').to_lowercase();
+            if SECRET_KEYS.iter().any(|k| lower.ends_with(k)) {
+                return format!("{}{}{}", key, sep, REDACTED);
+            }
+        }
+    }
+    arg.to_string()
+}
+
+static POLICY: RwLock<Option<Arc<dyn RedactionPolicy>>> = RwLock::new(None);
+
+/// Replace the process-wide policy (default: [`DefaultRedaction`])
+pub fn set_redaction_policy(policy: Arc<dyn RedactionPolicy>) {
+    *POLICY.write().unwrap() = Some(policy);
+}
+
+/// The process-wide policy
+pub fn redaction_policy() -> Arc<dyn RedactionPolicy> {
+    POLICY
+        .read()
+        .unwrap()
+        .clone()
+        .unwrap_or_else(|| Arc::new(DefaultRedaction::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_default_hides_flag_values() {
+        let redacted = DefaultRedaction::default().redact_args(
+            "himalaya",
+            &args(&[
+                "message",
+                "write",
+                "--to",
+                "jane@example.com",
+                "--subject",
+                "Biopsy results",
+            ]),
+        );
+        assert_eq!(
+            redacted,
+            args(&[
+                "message",
+                "write",
+                "--to",
+                "***@example.com",
+                "--subject",
+                REDACTED
+            ])
+        );
+    }
+
+    #[test]
+    fn test_default_hides_inline_values_and_secrets() {
+        let redacted = DefaultRedaction::default().redact_args(
+            "tool",
+            &args(&["--password=hunter2", "rc.api_token=abc", "status:pending"]),
+        );
+        assert_eq!(
+            redacted,
+            args(&[
+                "--password=[redacted]",
+                "rc.api_token=[redacted]",
+                "status:pending"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_redact_emails_in_queries() {
+        assert_eq!(
+            redact_emails("from:john@x.com and to:<a.b@y.org>"),
+            "from:***@x.com and to:<***@y.org>"
+        );
+        assert_eq!(redact_emails("thread:0000 @home"), "thread:0000 @home");
+    }
+
+    #[test]
+    fn test_closure_policy() {
+        let policy = |_: &str, args: &[String]| vec![args.len().to_string()];
+        assert_eq!(policy.redact_args("x", &args(&["a", "b"])), args(&["2"]));
+    }
+}